@@ -1,55 +1,302 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    Storage, Uint128,
 };
 use cw2::set_contract_version;
+use cw_utils::nonpayable;
 
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, InstantiateOperator, QueryMsg};
-use crate::state::{Config, OpInfo, CONFIG};
+use crate::state::{
+    Config, Constraints, CONFIG, METADATA, OPERATORS, OPERATORS_BY_POWER, OPERATOR_COUNT,
+    TOTAL_POWER,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Page size of `AllVoters` if no limit is given
+pub const DEFAULT_LIMIT: u32 = 100;
+/// Maximum page size of `AllVoters`
+pub const MAX_LIMIT: u32 = 250;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let mut total_power = Uint128::zero();
-    let operators = msg
-        .operators
-        .into_iter()
-        .map(|InstantiateOperator { addr, voting_power }| {
-            let op = deps.api.addr_validate(&addr)?;
-            let power = Uint128::from(voting_power);
-            total_power += power;
-            Ok(OpInfo { op, power })
-        })
-        .collect::<StdResult<Vec<_>>>()?;
-    let config = Config {
-        operators,
-        total_power,
-    };
-    CONFIG.save(deps.storage, &config)?;
+    let height = env.block.height;
+    TOTAL_POWER.save(deps.storage, &Uint128::zero(), height)?;
+    OPERATOR_COUNT.save(deps.storage, &0)?;
+    for InstantiateOperator { addr, voting_power } in msg.operators {
+        let op = deps.api.addr_validate(&addr)?;
+        if OPERATORS.has(deps.storage, &op) {
+            return Err(ContractError::DuplicateOperator(addr));
+        }
+        update_power(deps.storage, height, &op, Some(voting_power.into()))?;
+    }
+
+    msg.constraints.validate()?;
+    check_constraints(deps.storage, &msg.constraints)?;
+
+    let admin = msg
+        .admin
+        .map(|admin| deps.api.addr_validate(&admin))
+        .transpose()?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            admin,
+            constraints: msg.constraints,
+        },
+    )?;
 
     Ok(Response::new())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    _deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
-    _msg: ExecuteMsg,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    unimplemented!()
+    nonpayable(&info)?;
+
+    match msg {
+        ExecuteMsg::AddOperator { addr, voting_power } => {
+            ensure_admin(deps.as_ref(), &info)?;
+            execute::add_operator(deps, env, addr, voting_power)
+        }
+        ExecuteMsg::RemoveOperator { addr } => {
+            ensure_admin(deps.as_ref(), &info)?;
+            execute::remove_operator(deps, env, addr)
+        }
+        ExecuteMsg::SetPower { addr, voting_power } => {
+            ensure_admin(deps.as_ref(), &info)?;
+            execute::set_power(deps, env, addr, voting_power)
+        }
+        ExecuteMsg::UpdateConstraints { constraints } => {
+            ensure_admin(deps.as_ref(), &info)?;
+            execute::update_constraints(deps, constraints)
+        }
+        // operators manage their own metadata
+        ExecuteMsg::UpdateMetadata { metadata } => execute::update_metadata(deps, info, metadata),
+    }
+}
+
+fn ensure_admin(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Stores the power of an operator (`None` removes it), keeping the power index, operator count
+/// and total power in sync. Returns the previous power and the new total power.
+fn update_power(
+    storage: &mut dyn Storage,
+    height: u64,
+    op: &Addr,
+    power: Option<Uint128>,
+) -> Result<(Option<Uint128>, Uint128), ContractError> {
+    let old_power = OPERATORS.may_load(storage, op)?;
+    let mut count = OPERATOR_COUNT.load(storage)?;
+    if let Some(old_power) = old_power {
+        OPERATORS_BY_POWER.remove(storage, (old_power.u128(), op));
+        count -= 1;
+    }
+    match power {
+        Some(power) => {
+            OPERATORS.save(storage, op, &power, height)?;
+            OPERATORS_BY_POWER.save(storage, (power.u128(), op), &())?;
+            count += 1;
+        }
+        None => OPERATORS.remove(storage, op, height)?,
+    }
+    OPERATOR_COUNT.save(storage, &count)?;
+
+    let total_power = TOTAL_POWER
+        .load(storage)?
+        .checked_sub(old_power.unwrap_or_default())?
+        .checked_add(power.unwrap_or_default())?;
+    TOTAL_POWER.save(storage, &total_power, height)?;
+    Ok((old_power, total_power))
+}
+
+/// Checks the current operator set against the constraints, only looking at the smallest and
+/// largest power so it doesn't depend on the size of the set
+fn check_constraints(
+    storage: &dyn Storage,
+    constraints: &Constraints,
+) -> Result<(), ContractError> {
+    let power_at_end = |order| -> StdResult<Uint128> {
+        let power = OPERATORS_BY_POWER
+            .keys(storage, None, None, order)
+            .next()
+            .transpose()?
+            .map(|(power, _)| power)
+            .unwrap_or_default();
+        Ok(power.into())
+    };
+    constraints.check(
+        OPERATOR_COUNT.load(storage)?,
+        power_at_end(Order::Ascending)?,
+        power_at_end(Order::Descending)?,
+        TOTAL_POWER.load(storage)?,
+    )
+}
+
+mod execute {
+    use super::*;
+
+    use crate::state::{OperatorMetadata, OperatorPublicKey};
+
+    const MAX_MONIKER_LENGTH: usize = 70;
+    const MAX_URL_LENGTH: usize = 256;
+
+    pub fn add_operator(
+        deps: DepsMut,
+        env: Env,
+        addr: String,
+        voting_power: u32,
+    ) -> Result<Response, ContractError> {
+        let height = env.block.height;
+        let op = deps.api.addr_validate(&addr)?;
+        if OPERATORS.has(deps.storage, &op) {
+            return Err(ContractError::DuplicateOperator(addr));
+        }
+
+        let power = Uint128::from(voting_power);
+        let (_, total_power) = update_power(deps.storage, height, &op, Some(power))?;
+        check_constraints(deps.storage, &CONFIG.load(deps.storage)?.constraints)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "add_operator")
+            .add_attribute("operator", addr)
+            .add_attribute("power", power)
+            .add_attribute("total_power", total_power))
+    }
+
+    pub fn remove_operator(
+        deps: DepsMut,
+        env: Env,
+        addr: String,
+    ) -> Result<Response, ContractError> {
+        let height = env.block.height;
+        let op = deps.api.addr_validate(&addr)?;
+        let (power, total_power) = match update_power(deps.storage, height, &op, None)? {
+            (Some(power), total_power) => (power, total_power),
+            (None, _) => return Err(ContractError::UnknownOperator(addr)),
+        };
+        METADATA.remove(deps.storage, &op);
+        check_constraints(deps.storage, &CONFIG.load(deps.storage)?.constraints)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "remove_operator")
+            .add_attribute("operator", addr)
+            .add_attribute("power", power)
+            .add_attribute("total_power", total_power))
+    }
+
+    pub fn set_power(
+        deps: DepsMut,
+        env: Env,
+        addr: String,
+        voting_power: u32,
+    ) -> Result<Response, ContractError> {
+        let height = env.block.height;
+        let op = deps.api.addr_validate(&addr)?;
+        if !OPERATORS.has(deps.storage, &op) {
+            return Err(ContractError::UnknownOperator(addr));
+        }
+
+        let power = Uint128::from(voting_power);
+        let (old_power, total_power) = update_power(deps.storage, height, &op, Some(power))?;
+        let old_power = old_power.unwrap_or_default();
+        check_constraints(deps.storage, &CONFIG.load(deps.storage)?.constraints)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_power")
+            .add_attribute("operator", addr)
+            .add_attribute("old_power", old_power)
+            .add_attribute("power", power)
+            .add_attribute("total_power", total_power))
+    }
+
+    pub fn update_constraints(
+        deps: DepsMut,
+        constraints: Constraints,
+    ) -> Result<Response, ContractError> {
+        constraints.validate()?;
+        check_constraints(deps.storage, &constraints)?;
+        CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+            config.constraints = constraints;
+            Ok(config)
+        })?;
+
+        Ok(Response::new().add_attribute("method", "update_constraints"))
+    }
+
+    pub fn update_metadata(
+        deps: DepsMut,
+        info: MessageInfo,
+        metadata: OperatorMetadata,
+    ) -> Result<Response, ContractError> {
+        if !OPERATORS.has(deps.storage, &info.sender) {
+            return Err(ContractError::UnknownOperator(info.sender.into_string()));
+        }
+        validate_metadata(&metadata)?;
+        METADATA.save(deps.storage, &info.sender, &metadata)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "update_metadata")
+            .add_attribute("operator", info.sender))
+    }
+
+    fn validate_metadata(metadata: &OperatorMetadata) -> Result<(), ContractError> {
+        if let Some(moniker) = &metadata.moniker {
+            validate_length("moniker", moniker, MAX_MONIKER_LENGTH)?;
+        }
+        if let Some(website) = &metadata.website {
+            validate_url("website", website)?;
+        }
+        if let Some(endpoint) = &metadata.wasmatic_endpoint {
+            validate_url("wasmatic_endpoint", endpoint)?;
+        }
+        match &metadata.public_key {
+            Some(OperatorPublicKey::Secp256k1(key)) if key.len() != 33 && key.len() != 65 => Err(
+                ContractError::InvalidPublicKey("secp256k1".to_string(), key.len()),
+            ),
+            Some(OperatorPublicKey::Ed25519(key)) if key.len() != 32 => Err(
+                ContractError::InvalidPublicKey("ed25519".to_string(), key.len()),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_length(field: &str, value: &str, max: usize) -> Result<(), ContractError> {
+        if value.is_empty() || value.len() > max {
+            return Err(ContractError::InvalidLength(field.to_string(), max));
+        }
+        Ok(())
+    }
+
+    fn validate_url(field: &str, url: &str) -> Result<(), ContractError> {
+        validate_length(field, url, MAX_URL_LENGTH)?;
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(ContractError::InvalidUrl(field.to_string()));
+        }
+        Ok(())
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -61,17 +308,23 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::TotalPowerAtHeight { height } => {
             to_json_binary(&query::total_power(deps, env, height)?)
         }
-        QueryMsg::AllVoters {} => to_json_binary(&query::all_voters(deps, env)?),
+        QueryMsg::AllVoters { start_after, limit } => {
+            to_json_binary(&query::all_voters(deps, start_after, limit)?)
+        }
+        QueryMsg::Constraints {} => to_json_binary(&CONFIG.load(deps.storage)?.constraints),
+        QueryMsg::OperatorInfo { address } => to_json_binary(&query::operator_info(deps, address)?),
     }
 }
 
-// Although the queries take a height parameter, they don't use it to query historical data.
-// This doesn't matter here since we're just mocking the operators, and they don't change.
+// Powers are returned as they were at the end of the given block, so changes made in that block
+// are included. This way a task created right after the operators were set up sees them.
 mod query {
     use super::*;
 
-    use lavs_apis::interfaces::voting::{
-        AllVotersResponse, TotalPowerResponse, VoterInfo, VotingPowerResponse,
+    use cw_storage_plus::Bound;
+
+    use crate::msg::{
+        AllVotersResponse, OperatorInfoResponse, TotalPowerResponse, VoterInfo, VotingPowerResponse,
     };
 
     pub fn voting_power(
@@ -80,33 +333,68 @@ mod query {
         address: String,
         height: Option<u64>,
     ) -> StdResult<VotingPowerResponse> {
-        let height = height.unwrap_or(env.block.height);
         let addr = deps.api.addr_validate(&address)?;
-        let config = CONFIG.load(deps.storage)?;
-        let op = config.operators.iter().find(|op| op.op == addr);
-        let power = op.map(|op| op.power).unwrap_or_default();
+        let power = match height {
+            Some(height) => {
+                OPERATORS.may_load_at_height(deps.storage, &addr, height.saturating_add(1))?
+            }
+            None => OPERATORS.may_load(deps.storage, &addr)?,
+        }
+        .unwrap_or_default();
+        let height = height.unwrap_or(env.block.height);
         Ok(VotingPowerResponse { power, height })
     }
 
     pub fn total_power(deps: Deps, env: Env, height: Option<u64>) -> StdResult<TotalPowerResponse> {
+        let power = match height {
+            Some(height) => {
+                TOTAL_POWER.may_load_at_height(deps.storage, height.saturating_add(1))?
+            }
+            None => TOTAL_POWER.may_load(deps.storage)?,
+        }
+        .unwrap_or_default();
         let height = height.unwrap_or(env.block.height);
-        let power = CONFIG.load(deps.storage)?.total_power;
         let res = TotalPowerResponse { power, height };
         Ok(res)
     }
 
-    pub fn all_voters(deps: Deps, _env: Env) -> StdResult<AllVotersResponse> {
-        let config = CONFIG.load(deps.storage)?;
-        let voters = config
-            .operators
-            .into_iter()
-            .map(|op| VoterInfo {
-                power: op.power,
-                address: op.op.into_string(),
+    pub fn all_voters(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllVotersResponse> {
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let min = start_after.as_ref().map(Bound::exclusive);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let voters = OPERATORS
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (address, power) = item?;
+                Ok(VoterInfo {
+                    power,
+                    address: address.into_string(),
+                })
             })
-            .collect();
+            .collect::<StdResult<_>>()?;
         Ok(AllVotersResponse { voters })
     }
+
+    pub fn operator_info(deps: Deps, address: String) -> StdResult<Option<OperatorInfoResponse>> {
+        let addr = deps.api.addr_validate(&address)?;
+        let Some(power) = OPERATORS.may_load(deps.storage, &addr)? else {
+            return Ok(None);
+        };
+        let metadata = METADATA.may_load(deps.storage, &addr)?.unwrap_or_default();
+        Ok(Some(OperatorInfoResponse {
+            address: addr.into_string(),
+            power,
+            metadata,
+        }))
+    }
 }
 
 #[cfg(test)]
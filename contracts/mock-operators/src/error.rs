@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,8 +7,41 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized,
+
+    #[error("Operator {0} is already in the set")]
+    DuplicateOperator(String),
+
+    #[error("Operator {0} is not in the set")]
+    UnknownOperator(String),
+
+    #[error("Invalid {0} public key length: {1}")]
+    InvalidPublicKey(String, usize),
+
+    #[error("Invalid {0}: must be an http(s) url")]
+    InvalidUrl(String),
+
+    #[error("{0} must be between 1 and {1} characters")]
+    InvalidLength(String, usize),
+
+    #[error("Max power share must be more than 0 and at most 1, got {0}")]
+    InvalidMaxPowerShare(Decimal),
+
+    #[error("The operator set needs at least {0} operators")]
+    TooFewOperators(u32),
+
+    #[error("Every operator needs at least {0} voting power")]
+    PowerTooLow(Uint128),
+
+    #[error("No operator can hold more than {0} of the total power")]
+    PowerShareExceeded(Decimal),
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -1,11 +1,21 @@
-use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
 
-// This pulls in the queries
-pub use lavs_apis::interfaces::voting::*;
+use crate::state::{Constraints, OperatorMetadata};
+
+// Same responses as the voting interface, so verifiers can use this contract as operator set
+pub use lavs_apis::interfaces::voting::{
+    AllVotersResponse, TotalPowerResponse, VoterInfo, VotingPowerResponse,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub operators: Vec<InstantiateOperator>,
+    /// Allowed to change the operator set, it is fixed if not set
+    pub admin: Option<String>,
+    /// Limits the operator set must respect, checked on instantiate and every change
+    #[serde(default)]
+    pub constraints: Constraints,
 }
 
 #[cw_serde]
@@ -25,4 +35,51 @@ impl InstantiateOperator {
 #[cw_serde]
 #[derive(cw_orch::ExecuteFns)]
 #[cw_orch(disable_fields_sorting)]
-pub enum ExecuteMsg {}
+pub enum ExecuteMsg {
+    /// Adds a new operator to the set (admin only)
+    AddOperator { addr: String, voting_power: u32 },
+    /// Removes an operator from the set (admin only)
+    RemoveOperator { addr: String },
+    /// Changes the voting power of an existing operator (admin only)
+    SetPower { addr: String, voting_power: u32 },
+    /// Replaces the constraints, the current set must respect them (admin only)
+    UpdateConstraints { constraints: Constraints },
+    /// Replaces the metadata of the sender, who must be in the operator set
+    UpdateMetadata { metadata: OperatorMetadata },
+}
+
+#[cw_serde]
+#[derive(cw_orch::QueryFns)]
+#[cw_orch(disable_fields_sorting)]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the voting power of an operator at the end of the given block (latest if not set)
+    #[returns(VotingPowerResponse)]
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    /// Returns the total voting power at the end of the given block (latest if not set)
+    #[returns(TotalPowerResponse)]
+    TotalPowerAtHeight { height: Option<u64> },
+    /// Returns the operators with voting power, ordered by address.
+    /// Without pagination this is the first page, which keeps `AllVoters {}` working for small sets.
+    #[returns(AllVotersResponse)]
+    AllVoters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the limits the operator set must respect
+    #[returns(Constraints)]
+    Constraints {},
+    /// Returns the current power and metadata of an operator, `None` if it is not in the set
+    #[returns(Option<OperatorInfoResponse>)]
+    OperatorInfo { address: String },
+}
+
+#[cw_serde]
+pub struct OperatorInfoResponse {
+    pub address: String,
+    pub power: Uint128,
+    pub metadata: OperatorMetadata,
+}
@@ -1,17 +1,111 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+
+use crate::error::ContractError;
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Voting power of every operator, with history so it can be queried at past heights
+pub const OPERATORS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "operators",
+    "operators__checkpoints",
+    "operators__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Sum of all the operators' voting power, with history like `OPERATORS`
+pub const TOTAL_POWER: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_power",
+    "total_power__checkpoints",
+    "total_power__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Operators ordered by power, to check the constraints without loading the whole set
+pub const OPERATORS_BY_POWER: Map<(u128, &Addr), ()> = Map::new("operators_by_power");
+
+/// Number of operators in the set
+pub const OPERATOR_COUNT: Item<u32> = Item::new("operator_count");
+
+/// Metadata the operators publish about themselves, removed with the operator
+pub const METADATA: Map<&Addr, OperatorMetadata> = Map::new("metadata");
+
 #[cw_serde]
 pub struct Config {
-    pub operators: Vec<OpInfo>,
-    pub total_power: Uint128,
+    pub admin: Option<Addr>,
+    pub constraints: Constraints,
+}
+
+/// Limits the operator set must respect after every change, unset fields are not checked
+#[cw_serde]
+#[derive(Default)]
+pub struct Constraints {
+    /// Maximum share of the total power a single operator can hold, between 0 (exclusive) and 1
+    pub max_power_share: Option<Decimal>,
+    /// Minimum number of operators in the set
+    pub min_operators: Option<u32>,
+    /// Minimum voting power of every operator
+    pub min_power: Option<Uint128>,
+}
+
+impl Constraints {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        match self.max_power_share {
+            Some(share) if share.is_zero() || share > Decimal::one() => {
+                Err(ContractError::InvalidMaxPowerShare(share))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a set of `count` operators, given its smallest, largest and total power
+    pub fn check(
+        &self,
+        count: u32,
+        smallest: Uint128,
+        largest: Uint128,
+        total: Uint128,
+    ) -> Result<(), ContractError> {
+        if let Some(min_operators) = self.min_operators {
+            if count < min_operators {
+                return Err(ContractError::TooFewOperators(min_operators));
+            }
+        }
+        if count == 0 {
+            return Ok(());
+        }
+        if let Some(min_power) = self.min_power {
+            if smallest < min_power {
+                return Err(ContractError::PowerTooLow(min_power));
+            }
+        }
+        if let Some(max_share) = self.max_power_share {
+            // max_share is at most 1, so this can't overflow
+            if largest > total.mul_floor(max_share) {
+                return Err(ContractError::PowerShareExceeded(max_share));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct OperatorMetadata {
+    /// Human readable name of the operator
+    pub moniker: Option<String>,
+    pub website: Option<String>,
+    /// Base url of the wasmatic node run by this operator
+    pub wasmatic_endpoint: Option<String>,
+    /// Key used to sign results off-chain
+    pub public_key: Option<OperatorPublicKey>,
 }
 
 #[cw_serde]
-pub struct OpInfo {
-    pub op: Addr,
-    pub power: Uint128,
+pub enum OperatorPublicKey {
+    /// Compressed (33 bytes) or uncompressed (65 bytes) secp256k1 public key
+    Secp256k1(Binary),
+    /// 32 bytes ed25519 public key
+    Ed25519(Binary),
 }
@@ -1,11 +1,12 @@
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Binary, Decimal, Uint128};
 use cw_orch::environment::CwEnv;
 use cw_orch::prelude::*;
 
 use lavs_orch::{Addressable, AltSigner};
 
 use crate::interface::Contract;
-use crate::msg::{InstantiateMsg, InstantiateOperator, QueryMsgFns};
+use crate::msg::{ExecuteMsgFns, InstantiateMsg, InstantiateOperator, QueryMsgFns};
+use crate::state::{Constraints, OperatorMetadata, OperatorPublicKey};
 
 pub const BECH_PREFIX: &str = "layer";
 
@@ -35,25 +36,35 @@ where
     ];
 
     // put real message here
-    let msg = InstantiateMsg { operators };
+    let msg = InstantiateMsg {
+        operators,
+        admin: None,
+        constraints: Constraints::default(),
+    };
     let contract = setup(chain.clone(), msg);
 
+    let height = chain.block_info().unwrap().height;
+
     // now query the total power
     let total_power = contract.total_power_at_height(None).unwrap();
     assert_eq!(total_power.power, Uint128::from(600u64));
-    assert_ne!(total_power.height, 0u64);
+    assert_eq!(total_power.height, height);
 
-    // now query the total power
-    let total_power = contract.total_power_at_height(Some(173)).unwrap();
+    // now query the total power at a later height
+    let total_power = contract.total_power_at_height(Some(height + 173)).unwrap();
     assert_eq!(total_power.power, Uint128::from(600u64));
-    assert_eq!(total_power.height, 173u64);
+    assert_eq!(total_power.height, height + 173);
+
+    // there was no power before the contract was instantiated
+    let total_power = contract.total_power_at_height(Some(height - 1)).unwrap();
+    assert_eq!(total_power.power, Uint128::zero());
 
     // query the power of an operator
     let total_power = contract
-        .voting_power_at_height(op2.addr().into_string(), Some(287))
+        .voting_power_at_height(op2.addr().into_string(), Some(height + 287))
         .unwrap();
     assert_eq!(total_power.power, Uint128::from(200u64));
-    assert_eq!(total_power.height, 287u64);
+    assert_eq!(total_power.height, height + 287);
 
     // query the power of an operator with None height (should return the current height, just ensure it works)
     let total_power = contract
@@ -63,8 +74,463 @@ where
 
     // query the power of a non-operator
     let total_power = contract
-        .voting_power_at_height(noop.addr().into_string(), Some(287))
+        .voting_power_at_height(noop.addr().into_string(), Some(height + 287))
         .unwrap();
     assert_eq!(total_power.power, Uint128::zero());
-    assert_eq!(total_power.height, 287u64);
+    assert_eq!(total_power.height, height + 287);
+}
+
+pub fn manage_operators<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let admin = chain.alt_signer(1);
+    let op1 = chain.alt_signer(2);
+    let op2 = chain.alt_signer(3);
+    let op3 = chain.alt_signer(4);
+
+    let msg = InstantiateMsg {
+        operators: vec![
+            InstantiateOperator::new(op1.addr().to_string(), 100),
+            InstantiateOperator::new(op2.addr().to_string(), 200),
+        ],
+        admin: Some(admin.addr().to_string()),
+        constraints: Constraints::default(),
+    };
+    let contract = setup(chain.clone(), msg);
+
+    // only the admin can change the set
+    contract
+        .call_as(&op1)
+        .add_operator(op3.addr().to_string(), 300)
+        .unwrap_err();
+
+    let contract = contract.call_as(&admin);
+    contract.add_operator(op3.addr().to_string(), 300).unwrap();
+    // no duplicates
+    contract
+        .add_operator(op3.addr().to_string(), 50)
+        .unwrap_err();
+    assert_eq!(
+        contract.total_power_at_height(None).unwrap().power,
+        Uint128::new(600)
+    );
+
+    contract.set_power(op1.addr().to_string(), 150).unwrap();
+    let power = contract
+        .voting_power_at_height(op1.addr().into_string(), None)
+        .unwrap();
+    assert_eq!(power.power, Uint128::new(150));
+    assert_eq!(
+        contract.total_power_at_height(None).unwrap().power,
+        Uint128::new(650)
+    );
+
+    contract.remove_operator(op2.addr().to_string()).unwrap();
+    // can't remove or update an operator that is not in the set
+    contract
+        .remove_operator(op2.addr().to_string())
+        .unwrap_err();
+    contract.set_power(op2.addr().to_string(), 10).unwrap_err();
+    let power = contract
+        .voting_power_at_height(op2.addr().into_string(), None)
+        .unwrap();
+    assert_eq!(power.power, Uint128::zero());
+    assert_eq!(
+        contract.total_power_at_height(None).unwrap().power,
+        Uint128::new(450)
+    );
+
+    // total power always matches the voters
+    let voters = contract.all_voters(None, None).unwrap().voters;
+    assert_eq!(voters.len(), 2);
+    let sum: Uint128 = voters.iter().map(|v| v.power).sum();
+    assert_eq!(sum, Uint128::new(450));
+}
+
+pub fn fixed_operators<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op1 = chain.alt_signer(1);
+
+    // duplicate addresses are rejected on instantiate as well
+    let msg = InstantiateMsg {
+        operators: vec![
+            InstantiateOperator::new(op1.addr().to_string(), 100),
+            InstantiateOperator::new(op1.addr().to_string(), 200),
+        ],
+        admin: None,
+        constraints: Constraints::default(),
+    };
+    let contract = Contract::new(chain.clone());
+    contract.upload().unwrap();
+    contract.instantiate(&msg, None, &[]).unwrap_err();
+
+    // without an admin nobody can change the set
+    let msg = InstantiateMsg {
+        operators: vec![InstantiateOperator::new(op1.addr().to_string(), 100)],
+        admin: None,
+        constraints: Constraints::default(),
+    };
+    contract.instantiate(&msg, None, &[]).unwrap();
+    contract.set_power(op1.addr().to_string(), 200).unwrap_err();
+    contract
+        .call_as(&op1)
+        .set_power(op1.addr().to_string(), 200)
+        .unwrap_err();
+}
+
+pub fn historical_power<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let admin = chain.alt_signer(1);
+    let op1 = chain.alt_signer(2);
+    let op2 = chain.alt_signer(3);
+
+    let msg = InstantiateMsg {
+        operators: vec![
+            InstantiateOperator::new(op1.addr().to_string(), 100),
+            InstantiateOperator::new(op2.addr().to_string(), 200),
+        ],
+        admin: Some(admin.addr().to_string()),
+        constraints: Constraints::default(),
+    };
+    let contract = setup(chain.clone(), msg).call_as(&admin);
+    let start = chain.block_info().unwrap().height;
+
+    chain.wait_blocks(5).unwrap();
+    contract.set_power(op1.addr().to_string(), 400).unwrap();
+    let changed = chain.block_info().unwrap().height;
+
+    chain.wait_blocks(5).unwrap();
+    contract.remove_operator(op2.addr().to_string()).unwrap();
+    let removed = chain.block_info().unwrap().height;
+
+    chain.wait_blocks(1).unwrap();
+
+    let power_at = |op: &C::Sender, height: u64| {
+        contract
+            .voting_power_at_height(op.addr().into_string(), Some(height))
+            .unwrap()
+            .power
+            .u128()
+    };
+    let total_at = |height: u64| {
+        contract
+            .total_power_at_height(Some(height))
+            .unwrap()
+            .power
+            .u128()
+    };
+
+    // changes count from the end of the block they were made in
+    assert_eq!(power_at(&op1, start), 100);
+    assert_eq!(power_at(&op1, changed - 1), 100);
+    assert_eq!(power_at(&op1, changed), 400);
+    assert_eq!(power_at(&op1, removed), 400);
+
+    assert_eq!(power_at(&op2, start), 200);
+    assert_eq!(power_at(&op2, removed - 1), 200);
+    assert_eq!(power_at(&op2, removed), 0);
+
+    assert_eq!(total_at(start), 300);
+    assert_eq!(total_at(changed - 1), 300);
+    assert_eq!(total_at(changed), 600);
+    assert_eq!(total_at(removed - 1), 600);
+    assert_eq!(total_at(removed), 400);
+
+    // the current values match the latest snapshot
+    let total_power = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total_power.power, Uint128::new(400));
+}
+
+pub fn operator_metadata<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let admin = chain.alt_signer(1);
+    let op1 = chain.alt_signer(2);
+    let op2 = chain.alt_signer(3);
+    let noop = chain.alt_signer(4);
+
+    let msg = InstantiateMsg {
+        operators: vec![
+            InstantiateOperator::new(op1.addr().to_string(), 100),
+            InstantiateOperator::new(op2.addr().to_string(), 200),
+        ],
+        admin: Some(admin.addr().to_string()),
+        constraints: Constraints::default(),
+    };
+    let contract = setup(chain.clone(), msg);
+
+    // operators start without metadata
+    let info = contract
+        .operator_info(op1.addr().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.power, Uint128::new(100));
+    assert_eq!(info.metadata, OperatorMetadata::default());
+    assert_eq!(
+        contract.operator_info(noop.addr().to_string()).unwrap(),
+        None
+    );
+
+    let metadata = OperatorMetadata {
+        moniker: Some("Operator One".to_string()),
+        website: Some("https://operator.one".to_string()),
+        wasmatic_endpoint: Some("http://localhost:8081".to_string()),
+        public_key: Some(OperatorPublicKey::Ed25519(Binary::new(vec![7; 32]))),
+    };
+    contract
+        .call_as(&op1)
+        .update_metadata(metadata.clone())
+        .unwrap();
+    let info = contract
+        .operator_info(op1.addr().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.address, op1.addr().to_string());
+    assert_eq!(info.metadata, metadata);
+
+    // only operators in the set can publish metadata, not even the admin
+    contract
+        .call_as(&noop)
+        .update_metadata(metadata.clone())
+        .unwrap_err();
+    contract
+        .call_as(&admin)
+        .update_metadata(metadata.clone())
+        .unwrap_err();
+
+    // invalid metadata is rejected and the previous one is kept
+    let op2_contract = contract.call_as(&op2);
+    let invalid = [
+        OperatorMetadata {
+            moniker: Some(String::new()),
+            ..Default::default()
+        },
+        OperatorMetadata {
+            moniker: Some("x".repeat(71)),
+            ..Default::default()
+        },
+        OperatorMetadata {
+            wasmatic_endpoint: Some("localhost:8081".to_string()),
+            ..Default::default()
+        },
+        OperatorMetadata {
+            website: Some("ftp://operator.two".to_string()),
+            ..Default::default()
+        },
+        OperatorMetadata {
+            public_key: Some(OperatorPublicKey::Secp256k1(Binary::new(vec![2; 32]))),
+            ..Default::default()
+        },
+        OperatorMetadata {
+            public_key: Some(OperatorPublicKey::Ed25519(Binary::new(vec![7; 33]))),
+            ..Default::default()
+        },
+    ];
+    for metadata in invalid {
+        op2_contract.update_metadata(metadata).unwrap_err();
+    }
+    let info = contract
+        .operator_info(op2.addr().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.metadata, OperatorMetadata::default());
+
+    // both secp256k1 encodings are accepted
+    for len in [33, 65] {
+        let metadata = OperatorMetadata {
+            public_key: Some(OperatorPublicKey::Secp256k1(Binary::new(vec![2; len]))),
+            ..Default::default()
+        };
+        op2_contract.update_metadata(metadata).unwrap();
+    }
+
+    // removing an operator drops its metadata, so it starts clean when added back
+    let admin_contract = contract.call_as(&admin);
+    admin_contract
+        .remove_operator(op1.addr().to_string())
+        .unwrap();
+    assert_eq!(
+        contract.operator_info(op1.addr().to_string()).unwrap(),
+        None
+    );
+    admin_contract
+        .add_operator(op1.addr().to_string(), 50)
+        .unwrap();
+    let info = contract
+        .operator_info(op1.addr().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.power, Uint128::new(50));
+    assert_eq!(info.metadata, OperatorMetadata::default());
+}
+
+pub fn paginated_voters<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let mut operators: Vec<String> = (1..=7)
+        .map(|i| chain.alt_signer(i).addr().to_string())
+        .collect();
+    let msg = InstantiateMsg {
+        operators: operators
+            .iter()
+            .enumerate()
+            .map(|(i, addr)| InstantiateOperator::new(addr.clone(), 10 * (i as u32 + 1)))
+            .collect(),
+        admin: None,
+        constraints: Constraints::default(),
+    };
+    let contract = setup(chain.clone(), msg);
+    operators.sort();
+
+    // the default page holds small sets, so `AllVoters {}` still returns everyone
+    let voters = contract.all_voters(None, None).unwrap().voters;
+    let addresses: Vec<String> = voters.iter().map(|v| v.address.clone()).collect();
+    assert_eq!(addresses, operators);
+
+    // walking the pages returns every operator once, ordered by address
+    let mut start_after = None;
+    let mut paged = vec![];
+    loop {
+        let voters = contract.all_voters(start_after, Some(3)).unwrap().voters;
+        assert!(voters.len() <= 3);
+        match voters.last() {
+            Some(last) => start_after = Some(last.address.clone()),
+            None => break,
+        }
+        paged.extend(voters.into_iter().map(|v| v.address));
+    }
+    assert_eq!(paged, operators);
+
+    // the power of all the pages matches the cached total
+    let sum: Uint128 = contract
+        .all_voters(None, None)
+        .unwrap()
+        .voters
+        .iter()
+        .map(|v| v.power)
+        .sum();
+    assert_eq!(sum, contract.total_power_at_height(None).unwrap().power);
+    assert_eq!(sum, Uint128::new(280));
+}
+
+pub fn power_constraints<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let admin = chain.alt_signer(1);
+    let op1 = chain.alt_signer(2);
+    let op2 = chain.alt_signer(3);
+    let op3 = chain.alt_signer(4);
+    let op4 = chain.alt_signer(5);
+
+    let constraints = Constraints {
+        max_power_share: Some(Decimal::percent(50)),
+        min_operators: Some(3),
+        min_power: Some(Uint128::new(10)),
+    };
+    let instantiate = |operators: Vec<(&C::Sender, u32)>, constraints: Constraints| {
+        let contract = Contract::new(chain.clone());
+        contract.upload().unwrap();
+        let msg = InstantiateMsg {
+            operators: operators
+                .into_iter()
+                .map(|(op, power)| InstantiateOperator::new(op.addr().to_string(), power))
+                .collect(),
+            admin: Some(admin.addr().to_string()),
+            constraints,
+        };
+        contract.instantiate(&msg, None, &[]).map(|_| contract)
+    };
+
+    // the initial set must respect the constraints
+    instantiate(vec![(&op1, 100), (&op2, 100)], constraints.clone()).unwrap_err();
+    instantiate(
+        vec![(&op1, 5), (&op2, 100), (&op3, 100)],
+        constraints.clone(),
+    )
+    .unwrap_err();
+    instantiate(
+        vec![(&op1, 300), (&op2, 100), (&op3, 100)],
+        constraints.clone(),
+    )
+    .unwrap_err();
+    // and the constraints must make sense
+    let invalid = Constraints {
+        max_power_share: Some(Decimal::zero()),
+        ..Default::default()
+    };
+    instantiate(vec![(&op1, 100)], invalid).unwrap_err();
+    let invalid = Constraints {
+        max_power_share: Some(Decimal::percent(101)),
+        ..Default::default()
+    };
+    instantiate(vec![(&op1, 100)], invalid).unwrap_err();
+
+    let contract = instantiate(
+        vec![(&op1, 100), (&op2, 100), (&op3, 100)],
+        constraints.clone(),
+    )
+    .unwrap()
+    .call_as(&admin);
+    assert_eq!(contract.constraints().unwrap(), constraints);
+
+    // exactly half of the power is allowed, more is not
+    contract.set_power(op1.addr().to_string(), 200).unwrap();
+    contract.set_power(op1.addr().to_string(), 201).unwrap_err();
+    // below the minimum power
+    contract.set_power(op2.addr().to_string(), 9).unwrap_err();
+    contract
+        .add_operator(op4.addr().to_string(), 9)
+        .unwrap_err();
+
+    contract.add_operator(op4.addr().to_string(), 100).unwrap();
+    // removing op4 again would leave op1 with more than half
+    contract.set_power(op1.addr().to_string(), 250).unwrap();
+    contract
+        .remove_operator(op4.addr().to_string())
+        .unwrap_err();
+    contract.set_power(op1.addr().to_string(), 100).unwrap();
+    contract.remove_operator(op4.addr().to_string()).unwrap();
+    // can't go below the minimum number of operators
+    contract
+        .remove_operator(op3.addr().to_string())
+        .unwrap_err();
+
+    // failed changes are reverted
+    assert_eq!(
+        contract.total_power_at_height(None).unwrap().power,
+        Uint128::new(300)
+    );
+    assert_eq!(contract.all_voters(None, None).unwrap().voters.len(), 3);
+
+    // new constraints must hold for the current set
+    let stricter = Constraints {
+        min_operators: Some(4),
+        ..constraints.clone()
+    };
+    contract.update_constraints(stricter).unwrap_err();
+    let relaxed = Constraints {
+        min_operators: Some(2),
+        ..constraints.clone()
+    };
+    contract
+        .call_as(&op1)
+        .update_constraints(relaxed.clone())
+        .unwrap_err();
+    contract.update_constraints(relaxed.clone()).unwrap();
+    assert_eq!(contract.constraints().unwrap(), relaxed);
+    contract.remove_operator(op3.addr().to_string()).unwrap();
 }
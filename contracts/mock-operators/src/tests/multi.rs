@@ -7,3 +7,39 @@ fn happy_path_works() {
     let chain = MockBech32::new(BECH_PREFIX);
     super::common::happy_path(chain);
 }
+
+#[test]
+fn manage_operators() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::manage_operators(chain);
+}
+
+#[test]
+fn fixed_operators() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::fixed_operators(chain);
+}
+
+#[test]
+fn historical_power() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::historical_power(chain);
+}
+
+#[test]
+fn operator_metadata() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::operator_metadata(chain);
+}
+
+#[test]
+fn paginated_voters() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::paginated_voters(chain);
+}
+
+#[test]
+fn power_constraints() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::power_constraints(chain);
+}
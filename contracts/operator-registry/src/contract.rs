@@ -0,0 +1,767 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw_utils::nonpayable;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{Config, CONFIG, TOTAL_POWER};
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Page size of `AllVoters` if no limit is given
+pub const DEFAULT_LIMIT: u32 = 100;
+/// Maximum page size of `AllVoters`
+pub const MAX_LIMIT: u32 = 250;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.bond_denom.is_empty() {
+        return Err(ContractError::EmptyDenom);
+    }
+
+    let slashers = msg
+        .slashers
+        .iter()
+        .map(|slasher| deps.api.addr_validate(slasher))
+        .collect::<StdResult<Vec<_>>>()?;
+    let admin = msg
+        .admin
+        .map(|admin| deps.api.addr_validate(&admin))
+        .transpose()?;
+    let config = Config {
+        bond_denom: msg.bond_denom,
+        min_bond: msg.min_bond,
+        unbonding_period: msg.unbonding_period,
+        slashers,
+        admin,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    TOTAL_POWER.save(deps.storage, &Uint128::zero(), env.block.height)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Bond {} => execute::bond(deps, env, info),
+        ExecuteMsg::Unbond { amount } => execute::unbond(deps, env, info, amount),
+        ExecuteMsg::Claim {} => execute::claim(deps, env, info),
+        ExecuteMsg::Delegate { operator } => execute::delegate(deps, env, info, operator),
+        ExecuteMsg::Undelegate { operator, amount } => {
+            execute::undelegate(deps, env, info, operator, amount)
+        }
+        ExecuteMsg::SetCommission { commission } => execute::set_commission(deps, info, commission),
+        ExecuteMsg::DistributeRewards { operator } => {
+            execute::distribute_rewards(deps, info, operator)
+        }
+        ExecuteMsg::ClaimRewards { operator } => execute::claim_rewards(deps, info, operator),
+        ExecuteMsg::Slash { operator, amount } => execute::slash(deps, env, info, operator, amount),
+        ExecuteMsg::UpdateSlashers { add, remove } => {
+            execute::update_slashers(deps, info, add, remove)
+        }
+    }
+}
+
+mod execute {
+    use cosmwasm_std::{
+        coin, coins, Addr, BankMsg, Decimal, Decimal256, Empty, Order, Storage, Timestamp, Uint256,
+    };
+    use cw_utils::must_pay;
+
+    use crate::msg::SlashResponse;
+
+    use crate::state::{
+        Claim, BONDS, CLAIMS, COMMISSIONS, DELEGATED, DELEGATIONS, PENDING_REWARDS, POWER,
+        REWARD_INDEX, STAKER_INDEX, UNBONDING,
+    };
+
+    use super::*;
+
+    pub fn bond(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let amount = must_pay(&info, &config.bond_denom)?;
+        let operator = info.sender;
+
+        let old_bond = BONDS.may_load(deps.storage, &operator)?.unwrap_or_default();
+        let bond = old_bond.checked_add(amount)?;
+        if bond < config.min_bond {
+            return Err(ContractError::BelowMinimumBond(config.min_bond));
+        }
+        settle_rewards(deps.storage, &operator, &operator, old_bond)?;
+        BONDS.save(deps.storage, &operator, &bond)?;
+        update_power(deps.storage, env.block.height, &operator)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "bond")
+            .add_attribute("operator", operator)
+            .add_attribute("amount", amount)
+            .add_attribute("bond", bond))
+    }
+
+    pub fn unbond(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        if amount.is_zero() {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let operator = info.sender;
+        let old_bond = BONDS
+            .may_load(deps.storage, &operator)?
+            .ok_or_else(|| ContractError::NotRegistered(operator.to_string()))?;
+        let bond = old_bond
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientBond(amount, old_bond))?;
+        // either leave enough to stay registered or leave completely
+        if !bond.is_zero() && bond < config.min_bond {
+            return Err(ContractError::BelowMinimumBond(config.min_bond));
+        }
+
+        settle_rewards(deps.storage, &operator, &operator, old_bond)?;
+        if bond.is_zero() {
+            BONDS.remove(deps.storage, &operator);
+        } else {
+            BONDS.save(deps.storage, &operator, &bond)?;
+        }
+        update_power(deps.storage, env.block.height, &operator)?;
+
+        let release_at = add_claim(deps.storage, &env, &config, &operator, &operator, amount)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "unbond")
+            .add_attribute("operator", operator)
+            .add_attribute("amount", amount)
+            .add_attribute("bond", bond)
+            .add_attribute("release_at", release_at.seconds().to_string()))
+    }
+
+    pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let owner = info.sender;
+        let claims = CLAIMS.may_load(deps.storage, &owner)?.unwrap_or_default();
+        let (released, pending): (Vec<_>, Vec<_>) = claims
+            .into_iter()
+            .partition(|claim| claim.release_at <= env.block.time);
+
+        let amount = released.iter().try_fold(Uint128::zero(), |total, claim| {
+            total.checked_add(claim.amount)
+        })?;
+        if amount.is_zero() {
+            return Err(ContractError::NothingToClaim);
+        }
+
+        if pending.is_empty() {
+            CLAIMS.remove(deps.storage, &owner);
+        } else {
+            CLAIMS.save(deps.storage, &owner, &pending)?;
+        }
+        for claim in &released {
+            if !pending.iter().any(|left| left.operator == claim.operator) {
+                UNBONDING.remove(deps.storage, (&claim.operator, &owner));
+            }
+        }
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: coins(amount.u128(), config.bond_denom),
+            })
+            .add_attribute("method", "claim")
+            .add_attribute("owner", owner)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn delegate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let amount = must_pay(&info, &config.bond_denom)?;
+        let delegator = info.sender;
+
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        if operator_addr == delegator {
+            return Err(ContractError::SelfDelegation);
+        }
+        if !BONDS.has(deps.storage, &operator_addr) {
+            return Err(ContractError::NotRegistered(operator));
+        }
+
+        let old_delegation = DELEGATIONS
+            .may_load(deps.storage, (&operator_addr, &delegator))?
+            .unwrap_or_default();
+        settle_rewards(deps.storage, &operator_addr, &delegator, old_delegation)?;
+        let delegation = old_delegation.checked_add(amount)?;
+        DELEGATIONS.save(deps.storage, (&operator_addr, &delegator), &delegation)?;
+        let delegated = DELEGATED
+            .may_load(deps.storage, &operator_addr)?
+            .unwrap_or_default()
+            .checked_add(amount)?;
+        DELEGATED.save(deps.storage, &operator_addr, &delegated)?;
+        update_power(deps.storage, env.block.height, &operator_addr)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "delegate")
+            .add_attribute("operator", operator)
+            .add_attribute("delegator", delegator)
+            .add_attribute("amount", amount)
+            .add_attribute("delegation", delegation))
+    }
+
+    pub fn undelegate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        if amount.is_zero() {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let delegator = info.sender;
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let old_delegation = DELEGATIONS
+            .may_load(deps.storage, (&operator_addr, &delegator))?
+            .unwrap_or_default();
+        let delegation = old_delegation
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientDelegation(amount, old_delegation))?;
+
+        settle_rewards(deps.storage, &operator_addr, &delegator, old_delegation)?;
+        if delegation.is_zero() {
+            DELEGATIONS.remove(deps.storage, (&operator_addr, &delegator));
+        } else {
+            DELEGATIONS.save(deps.storage, (&operator_addr, &delegator), &delegation)?;
+        }
+        let delegated = DELEGATED
+            .load(deps.storage, &operator_addr)?
+            .checked_sub(amount)?;
+        DELEGATED.save(deps.storage, &operator_addr, &delegated)?;
+        update_power(deps.storage, env.block.height, &operator_addr)?;
+
+        let release_at = add_claim(
+            deps.storage,
+            &env,
+            &config,
+            &delegator,
+            &operator_addr,
+            amount,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "undelegate")
+            .add_attribute("operator", operator)
+            .add_attribute("delegator", delegator)
+            .add_attribute("amount", amount)
+            .add_attribute("delegation", delegation)
+            .add_attribute("release_at", release_at.seconds().to_string()))
+    }
+
+    pub fn set_commission(
+        deps: DepsMut,
+        info: MessageInfo,
+        commission: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        if commission > Decimal::one() {
+            return Err(ContractError::InvalidCommission(commission));
+        }
+
+        let operator = info.sender;
+        if !BONDS.has(deps.storage, &operator) {
+            return Err(ContractError::NotRegistered(operator.to_string()));
+        }
+        COMMISSIONS.save(deps.storage, &operator, &commission)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_commission")
+            .add_attribute("operator", operator)
+            .add_attribute("commission", commission.to_string()))
+    }
+
+    pub fn distribute_rewards(
+        deps: DepsMut,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let amount = must_pay(&info, &config.bond_denom)?;
+
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let stake = POWER
+            .may_load(deps.storage, &operator_addr)?
+            .unwrap_or_default();
+        if stake.is_zero() {
+            return Err(ContractError::NoStake(operator));
+        }
+
+        // the commission goes straight to the operator, the rest is shared by all the stake
+        let commission = COMMISSIONS
+            .may_load(deps.storage, &operator_addr)?
+            .unwrap_or_default();
+        let commission_amount = amount.checked_mul_floor(commission)?;
+        let pending = PENDING_REWARDS
+            .may_load(deps.storage, (&operator_addr, &operator_addr))?
+            .unwrap_or_default()
+            .checked_add(commission_amount)?;
+        PENDING_REWARDS.save(deps.storage, (&operator_addr, &operator_addr), &pending)?;
+
+        let shared = amount.checked_sub(commission_amount)?;
+        let index = REWARD_INDEX
+            .may_load(deps.storage, &operator_addr)?
+            .unwrap_or_default()
+            .checked_add(Decimal256::checked_from_ratio(shared, stake)?)?;
+        REWARD_INDEX.save(deps.storage, &operator_addr, &index)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "distribute_rewards")
+            .add_attribute("operator", operator)
+            .add_attribute("amount", amount)
+            .add_attribute("commission", commission_amount))
+    }
+
+    pub fn claim_rewards(
+        deps: DepsMut,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let staker = info.sender;
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let stake = staked_amount(deps.storage, &operator_addr, &staker)?;
+        settle_rewards(deps.storage, &operator_addr, &staker, stake)?;
+
+        let rewards = PENDING_REWARDS
+            .may_load(deps.storage, (&operator_addr, &staker))?
+            .unwrap_or_default();
+        if rewards.is_zero() {
+            return Err(ContractError::NoRewards);
+        }
+        PENDING_REWARDS.remove(deps.storage, (&operator_addr, &staker));
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: staker.to_string(),
+                amount: coins(rewards.u128(), config.bond_denom),
+            })
+            .add_attribute("method", "claim_rewards")
+            .add_attribute("operator", operator)
+            .add_attribute("staker", staker)
+            .add_attribute("amount", rewards))
+    }
+
+    pub fn slash(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        if !config.slashers.contains(&info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+        if amount.is_zero() {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        // everything staked with the operator is slashed by the same ratio: its own bond, the
+        // delegations, and the tokens being unbonded or undelegated from it
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let old_bond = BONDS
+            .may_load(deps.storage, &operator_addr)?
+            .unwrap_or_default();
+        let old_delegated = DELEGATED
+            .may_load(deps.storage, &operator_addr)?
+            .unwrap_or_default();
+        let delegations = DELEGATIONS
+            .prefix(&operator_addr)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let owners = UNBONDING
+            .prefix(&operator_addr)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let mut unbonding = Vec::with_capacity(owners.len());
+        for owner in owners {
+            let claims = CLAIMS.load(deps.storage, &owner)?;
+            unbonding.push((owner, claims));
+        }
+        let unbonding_amount = unbonding
+            .iter()
+            .flat_map(|(_, claims)| claims)
+            .filter(|claim| claim.operator == operator_addr)
+            .try_fold(Uint128::zero(), |total, claim| {
+                total.checked_add(claim.amount)
+            })?;
+        let slashable = old_bond
+            .checked_add(old_delegated)?
+            .checked_add(unbonding_amount)?;
+        if slashable.is_zero() {
+            return Err(ContractError::NotRegistered(operator));
+        }
+        let ratio = Decimal::checked_from_ratio(amount.min(slashable), slashable)?;
+
+        let from_bond = old_bond.checked_mul_floor(ratio)?;
+        let bond = old_bond - from_bond;
+        settle_rewards(deps.storage, &operator_addr, &operator_addr, old_bond)?;
+        if bond.is_zero() {
+            BONDS.remove(deps.storage, &operator_addr);
+        } else {
+            BONDS.save(deps.storage, &operator_addr, &bond)?;
+        }
+
+        let mut from_delegations = Uint128::zero();
+        for (delegator, old_delegation) in delegations {
+            let cut = old_delegation.checked_mul_floor(ratio)?;
+            settle_rewards(deps.storage, &operator_addr, &delegator, old_delegation)?;
+            let delegation = old_delegation - cut;
+            if delegation.is_zero() {
+                DELEGATIONS.remove(deps.storage, (&operator_addr, &delegator));
+            } else {
+                DELEGATIONS.save(deps.storage, (&operator_addr, &delegator), &delegation)?;
+            }
+            from_delegations = from_delegations.checked_add(cut)?;
+        }
+        DELEGATED.save(
+            deps.storage,
+            &operator_addr,
+            &old_delegated.checked_sub(from_delegations)?,
+        )?;
+        update_power(deps.storage, env.block.height, &operator_addr)?;
+
+        // the owners of the claims may also be unbonding from other operators, those claims are
+        // not this operator's stake
+        let mut from_unbonding = Uint128::zero();
+        for (owner, mut claims) in unbonding {
+            for claim in claims
+                .iter_mut()
+                .filter(|claim| claim.operator == operator_addr)
+            {
+                let cut = claim.amount.checked_mul_floor(ratio)?;
+                claim.amount -= cut;
+                from_unbonding = from_unbonding.checked_add(cut)?;
+            }
+            claims.retain(|claim| !claim.amount.is_zero());
+            if !claims.iter().any(|claim| claim.operator == operator_addr) {
+                UNBONDING.remove(deps.storage, (&operator_addr, &owner));
+            }
+            if claims.is_empty() {
+                CLAIMS.remove(deps.storage, &owner);
+            } else {
+                CLAIMS.save(deps.storage, &owner, &claims)?;
+            }
+        }
+
+        let slashed = from_bond
+            .checked_add(from_delegations)?
+            .checked_add(from_unbonding)?;
+        // the slashed tokens go to the slasher, which decides what to do with them
+        let slashed = coin(slashed.u128(), config.bond_denom);
+        let mut resp = Response::new()
+            .set_data(to_json_binary(&SlashResponse {
+                slashed: slashed.clone(),
+            })?)
+            .add_attribute("method", "slash")
+            .add_attribute("operator", operator)
+            .add_attribute("slasher", info.sender.to_string())
+            .add_attribute("amount", slashed.amount)
+            .add_attribute("bond", bond)
+            .add_attribute("delegated", old_delegated - from_delegations)
+            .add_attribute("unbonding", unbonding_amount - from_unbonding);
+        if !slashed.amount.is_zero() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: info.sender.into_string(),
+                amount: vec![slashed],
+            });
+        }
+        Ok(resp)
+    }
+
+    pub fn update_slashers(
+        deps: DepsMut,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let mut config = CONFIG.load(deps.storage)?;
+        if config.admin.as_ref() != Some(&info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let remove = remove
+            .iter()
+            .map(|slasher| deps.api.addr_validate(slasher))
+            .collect::<StdResult<Vec<_>>>()?;
+        config.slashers.retain(|slasher| !remove.contains(slasher));
+        for slasher in add {
+            let slasher = deps.api.addr_validate(&slasher)?;
+            if !config.slashers.contains(&slasher) {
+                config.slashers.push(slasher);
+            }
+        }
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new().add_attribute("method", "update_slashers"))
+    }
+
+    /// The operator's own bond if the staker is the operator, otherwise the staker's delegation
+    pub(crate) fn staked_amount(
+        storage: &dyn Storage,
+        operator: &Addr,
+        staker: &Addr,
+    ) -> StdResult<Uint128> {
+        let stake = if operator == staker {
+            BONDS.may_load(storage, operator)?
+        } else {
+            DELEGATIONS.may_load(storage, (operator, staker))?
+        };
+        Ok(stake.unwrap_or_default())
+    }
+
+    /// Rewards earned by `stake` since the staker's rewards were last settled
+    pub(crate) fn unsettled_rewards(
+        storage: &dyn Storage,
+        operator: &Addr,
+        staker: &Addr,
+        stake: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        let index = REWARD_INDEX
+            .may_load(storage, operator)?
+            .unwrap_or_default();
+        let last = STAKER_INDEX
+            .may_load(storage, (operator, staker))?
+            .unwrap_or_default();
+        let earned = Uint256::from(stake).checked_mul_floor(index.checked_sub(last)?)?;
+        Ok(Uint128::try_from(earned)?)
+    }
+
+    /// Moves the unsettled rewards to the pending ones, must be called before the stake changes
+    fn settle_rewards(
+        storage: &mut dyn Storage,
+        operator: &Addr,
+        staker: &Addr,
+        stake: Uint128,
+    ) -> Result<(), ContractError> {
+        let earned = unsettled_rewards(storage, operator, staker, stake)?;
+        if !earned.is_zero() {
+            let pending = PENDING_REWARDS
+                .may_load(storage, (operator, staker))?
+                .unwrap_or_default()
+                .checked_add(earned)?;
+            PENDING_REWARDS.save(storage, (operator, staker), &pending)?;
+        }
+        let index = REWARD_INDEX
+            .may_load(storage, operator)?
+            .unwrap_or_default();
+        STAKER_INDEX.save(storage, (operator, staker), &index)?;
+        Ok(())
+    }
+
+    /// Locks the tokens staked with the operator until the end of the unbonding period
+    fn add_claim(
+        storage: &mut dyn Storage,
+        env: &Env,
+        config: &Config,
+        owner: &Addr,
+        operator: &Addr,
+        amount: Uint128,
+    ) -> StdResult<Timestamp> {
+        let release_at = env.block.time.plus_seconds(config.unbonding_period);
+        let mut claims = CLAIMS.may_load(storage, owner)?.unwrap_or_default();
+        claims.push(Claim {
+            operator: operator.clone(),
+            amount,
+            release_at,
+        });
+        CLAIMS.save(storage, owner, &claims)?;
+        UNBONDING.save(storage, (operator, owner), &Empty {})?;
+        Ok(release_at)
+    }
+
+    /// Registered operators get the power of their bond plus delegations, others have none
+    fn update_power(
+        storage: &mut dyn Storage,
+        height: u64,
+        operator: &Addr,
+    ) -> Result<(), ContractError> {
+        let power = match BONDS.may_load(storage, operator)? {
+            Some(bond) => {
+                bond.checked_add(DELEGATED.may_load(storage, operator)?.unwrap_or_default())?
+            }
+            None => Uint128::zero(),
+        };
+
+        let old_power = POWER.may_load(storage, operator)?.unwrap_or_default();
+        if power.is_zero() {
+            POWER.remove(storage, operator, height)?;
+        } else {
+            POWER.save(storage, operator, &power, height)?;
+        }
+
+        let total_power = TOTAL_POWER
+            .load(storage)?
+            .checked_sub(old_power)?
+            .checked_add(power)?;
+        TOTAL_POWER.save(storage, &total_power, height)?;
+        Ok(())
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            to_json_binary(&query::voting_power(deps, env, address, height)?)
+        }
+        QueryMsg::TotalPowerAtHeight { height } => {
+            to_json_binary(&query::total_power(deps, env, height)?)
+        }
+        QueryMsg::AllVoters { start_after, limit } => {
+            to_json_binary(&query::all_voters(deps, start_after, limit)?)
+        }
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Operator { address } => to_json_binary(&query::operator(deps, address)?),
+        QueryMsg::Delegation {
+            operator,
+            delegator,
+        } => to_json_binary(&query::delegation(deps, operator, delegator)?),
+    }
+}
+
+// Powers are returned as they were at the end of the given block, so changes made in that block
+// are included. This matches the behavior of the mock operators contract.
+mod query {
+    use cosmwasm_std::{Order, StdError};
+    use cw_storage_plus::Bound;
+
+    use crate::msg::{
+        AllVotersResponse, DelegationResponse, OperatorResponse, TotalPowerResponse, VoterInfo,
+        VotingPowerResponse,
+    };
+    use crate::state::{BONDS, CLAIMS, COMMISSIONS, DELEGATED, PENDING_REWARDS, POWER};
+
+    use super::*;
+
+    pub fn voting_power(
+        deps: Deps,
+        env: Env,
+        address: String,
+        height: Option<u64>,
+    ) -> StdResult<VotingPowerResponse> {
+        let addr = deps.api.addr_validate(&address)?;
+        let power = match height {
+            Some(height) => {
+                POWER.may_load_at_height(deps.storage, &addr, height.saturating_add(1))?
+            }
+            None => POWER.may_load(deps.storage, &addr)?,
+        }
+        .unwrap_or_default();
+        let height = height.unwrap_or(env.block.height);
+        Ok(VotingPowerResponse { power, height })
+    }
+
+    pub fn total_power(deps: Deps, env: Env, height: Option<u64>) -> StdResult<TotalPowerResponse> {
+        let power = match height {
+            Some(height) => {
+                TOTAL_POWER.may_load_at_height(deps.storage, height.saturating_add(1))?
+            }
+            None => TOTAL_POWER.may_load(deps.storage)?,
+        }
+        .unwrap_or_default();
+        let height = height.unwrap_or(env.block.height);
+        Ok(TotalPowerResponse { power, height })
+    }
+
+    pub fn all_voters(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllVotersResponse> {
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let min = start_after.as_ref().map(Bound::exclusive);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let voters = POWER
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (address, power) = item?;
+                Ok(VoterInfo {
+                    power,
+                    address: address.into_string(),
+                })
+            })
+            .collect::<StdResult<_>>()?;
+        Ok(AllVotersResponse { voters })
+    }
+
+    pub fn operator(deps: Deps, address: String) -> StdResult<OperatorResponse> {
+        let addr = deps.api.addr_validate(&address)?;
+        Ok(OperatorResponse {
+            bond: BONDS.may_load(deps.storage, &addr)?.unwrap_or_default(),
+            delegated: DELEGATED.may_load(deps.storage, &addr)?.unwrap_or_default(),
+            commission: COMMISSIONS
+                .may_load(deps.storage, &addr)?
+                .unwrap_or_default(),
+            power: POWER.may_load(deps.storage, &addr)?.unwrap_or_default(),
+            unbonding: CLAIMS.may_load(deps.storage, &addr)?.unwrap_or_default(),
+        })
+    }
+
+    pub fn delegation(
+        deps: Deps,
+        operator: String,
+        delegator: String,
+    ) -> StdResult<DelegationResponse> {
+        let operator = deps.api.addr_validate(&operator)?;
+        let delegator = deps.api.addr_validate(&delegator)?;
+        let amount = execute::staked_amount(deps.storage, &operator, &delegator)?;
+        let unsettled = execute::unsettled_rewards(deps.storage, &operator, &delegator, amount)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        let rewards = PENDING_REWARDS
+            .may_load(deps.storage, (&operator, &delegator))?
+            .unwrap_or_default()
+            .checked_add(unsettled)?;
+        Ok(DelegationResponse { amount, rewards })
+    }
+}
@@ -0,0 +1,63 @@
+use cosmwasm_std::{
+    CheckedFromRatioError, CheckedMultiplyFractionError, ConversionOverflowError, Decimal,
+    OverflowError, StdError, Uint128,
+};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    ConversionOverflow(#[from] ConversionOverflowError),
+
+    #[error("{0}")]
+    CheckedFromRatio(#[from] CheckedFromRatioError),
+
+    #[error("{0}")]
+    CheckedMultiplyFraction(#[from] CheckedMultiplyFractionError),
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Bond denom cannot be empty")]
+    EmptyDenom,
+
+    #[error("Amount cannot be zero")]
+    ZeroAmount,
+
+    #[error("Bond must be at least {0}")]
+    BelowMinimumBond(Uint128),
+
+    #[error("Cannot unbond {0}, only {1} bonded")]
+    InsufficientBond(Uint128, Uint128),
+
+    #[error("{0} is not a registered operator")]
+    NotRegistered(String),
+
+    #[error("No unbonded tokens ready to be claimed")]
+    NothingToClaim,
+
+    #[error("Cannot undelegate {0}, only {1} delegated")]
+    InsufficientDelegation(Uint128, Uint128),
+
+    #[error("Operators cannot delegate to themselves, bond instead")]
+    SelfDelegation,
+
+    #[error("Invalid commission {0}, must be at most 100%")]
+    InvalidCommission(Decimal),
+
+    #[error("{0} has no stake to distribute rewards to")]
+    NoStake(String),
+
+    #[error("No rewards to claim")]
+    NoRewards,
+}
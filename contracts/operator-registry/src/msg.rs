@@ -0,0 +1,112 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Coin, Decimal, Uint128};
+
+use crate::state::{Claim, Config};
+
+// Same responses as the voting interface, so verifiers can use this contract as operator set
+pub use lavs_apis::interfaces::voting::{
+    AllVotersResponse, TotalPowerResponse, VoterInfo, VotingPowerResponse,
+};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The native token operators bond to get voting power
+    pub bond_denom: String,
+    /// Minimum bond to be registered as an operator
+    pub min_bond: Uint128,
+    /// How long unbonded tokens stay slashable before they can be claimed, in seconds
+    pub unbonding_period: u64,
+    /// Contracts (usually verifiers) allowed to slash operators
+    pub slashers: Vec<String>,
+    /// Allowed to update the slashers
+    pub admin: Option<String>,
+}
+
+#[cw_serde]
+#[derive(cw_orch::ExecuteFns)]
+#[cw_orch(disable_fields_sorting)]
+pub enum ExecuteMsg {
+    /// Registers the sender as operator, or increases its bond, with the attached funds
+    #[cw_orch(payable)]
+    Bond {},
+    /// Removes voting power right away, the tokens can be claimed after the unbonding period
+    Unbond { amount: Uint128 },
+    /// Sends all the tokens whose unbonding period is over back to the sender
+    Claim {},
+    /// Delegates the attached funds to a registered operator, adding to its voting power
+    #[cw_orch(payable)]
+    Delegate { operator: String },
+    /// Removes a delegation, the tokens can be claimed after the unbonding period
+    Undelegate { operator: String, amount: Uint128 },
+    /// Sets the share of the rewards the sender keeps as operator
+    SetCommission { commission: Decimal },
+    /// Splits the attached rewards between the operator (commission and self-bond) and its delegators
+    #[cw_orch(payable)]
+    DistributeRewards { operator: String },
+    /// Sends the sender's rewards from staking with the operator (itself included)
+    ClaimRewards { operator: String },
+    /// Takes up to `amount` of the tokens staked with the operator and sends them to the slasher
+    /// (slashers only). The bond, the delegations and the tokens being unbonded or undelegated from
+    /// the operator all lose the same share. Returns `SlashResponse` as data.
+    Slash { operator: String, amount: Uint128 },
+    /// Adds and removes slashers (admin only)
+    UpdateSlashers {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+}
+
+#[cw_serde]
+#[derive(cw_orch::QueryFns)]
+#[cw_orch(disable_fields_sorting)]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the voting power of an operator at the end of the given block (latest if not set)
+    #[returns(VotingPowerResponse)]
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    /// Returns the total voting power at the end of the given block (latest if not set)
+    #[returns(TotalPowerResponse)]
+    TotalPowerAtHeight { height: Option<u64> },
+    /// Returns the operators with voting power, ordered by address.
+    /// Without pagination this is the first page, which keeps `AllVoters {}` working for small sets.
+    #[returns(AllVotersResponse)]
+    AllVoters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(Config)]
+    Config {},
+    #[returns(OperatorResponse)]
+    Operator { address: String },
+    /// The operator itself can be queried as delegator to get its self-bond and rewards
+    #[returns(DelegationResponse)]
+    Delegation { operator: String, delegator: String },
+}
+
+#[cw_serde]
+pub struct OperatorResponse {
+    pub bond: Uint128,
+    /// Sum of all the delegations to this operator
+    pub delegated: Uint128,
+    pub commission: Decimal,
+    pub power: Uint128,
+    /// Tokens being unbonded, still slashable until released
+    pub unbonding: Vec<Claim>,
+}
+
+#[cw_serde]
+pub struct DelegationResponse {
+    pub amount: Uint128,
+    /// Rewards that can be claimed right away
+    pub rewards: Uint128,
+}
+
+/// Data returned by `Slash`
+#[cw_serde]
+pub struct SlashResponse {
+    /// Tokens actually taken from the operator and sent to the slasher, may be less than asked
+    pub slashed: Coin,
+}
@@ -0,0 +1,67 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Decimal256, Empty, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Tokens bonded by every operator
+pub const BONDS: Map<&Addr, Uint128> = Map::new("bonds");
+
+/// Tokens being unbonded or undelegated by every address, from any operator
+pub const CLAIMS: Map<&Addr, Vec<Claim>> = Map::new("claims");
+
+/// Addresses with tokens being unbonded or undelegated from an operator - indexed by (operator, owner),
+/// so slashing the operator can find their claims
+pub const UNBONDING: Map<(&Addr, &Addr), Empty> = Map::new("unbonding");
+
+/// Tokens delegated to an operator - indexed by (operator, delegator)
+pub const DELEGATIONS: Map<(&Addr, &Addr), Uint128> = Map::new("delegations");
+
+/// Sum of all the tokens delegated to every operator
+pub const DELEGATED: Map<&Addr, Uint128> = Map::new("delegated");
+
+/// Share of the rewards every operator keeps before passing them through (zero if not set)
+pub const COMMISSIONS: Map<&Addr, Decimal> = Map::new("commissions");
+
+/// Rewards distributed to every operator so far, per staked token
+pub const REWARD_INDEX: Map<&Addr, Decimal256> = Map::new("reward_index");
+
+/// `REWARD_INDEX` when the rewards of a staker were last settled - indexed by (operator, staker).
+/// The operator itself is the staker of its own bond.
+pub const STAKER_INDEX: Map<(&Addr, &Addr), Decimal256> = Map::new("staker_index");
+
+/// Settled rewards not claimed yet - indexed by (operator, staker)
+pub const PENDING_REWARDS: Map<(&Addr, &Addr), Uint128> = Map::new("pending_rewards");
+
+/// Voting power of every operator, with history so it can be queried at past heights
+pub const POWER: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "power",
+    "power__checkpoints",
+    "power__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Sum of all the operators' voting power, with history like `POWER`
+pub const TOTAL_POWER: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_power",
+    "total_power__checkpoints",
+    "total_power__changelog",
+    Strategy::EveryBlock,
+);
+
+#[cw_serde]
+pub struct Config {
+    pub bond_denom: String,
+    pub min_bond: Uint128,
+    pub unbonding_period: u64,
+    pub slashers: Vec<Addr>,
+    pub admin: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct Claim {
+    /// Operator the tokens were bonded or delegated to, only its slashing cuts the claim
+    pub operator: Addr,
+    pub amount: Uint128,
+    pub release_at: Timestamp,
+}
@@ -0,0 +1,510 @@
+use cosmwasm_std::{coin, coins, from_json, Decimal, Uint128};
+use cw_orch::environment::CwEnv;
+use cw_orch::prelude::*;
+
+use lavs_orch::{Addressable, AltSigner};
+
+use crate::interface::Contract;
+use crate::msg::{ExecuteMsgFns, InstantiateMsg, QueryMsgFns, SlashResponse};
+
+pub const BECH_PREFIX: &str = "layer";
+pub const BOND_DENOM: &str = "ustake";
+pub const MIN_BOND: u128 = 100;
+pub const UNBONDING_PERIOD: u64 = 1_000;
+
+pub fn setup<Chain: CwEnv>(chain: Chain, msg: InstantiateMsg) -> Contract<Chain> {
+    let contract = Contract::new(chain);
+    contract.upload().unwrap();
+    contract.instantiate(&msg, None, &[]).unwrap();
+    contract
+}
+
+fn default_msg(slashers: Vec<String>, admin: Option<String>) -> InstantiateMsg {
+    InstantiateMsg {
+        bond_denom: BOND_DENOM.to_string(),
+        min_bond: Uint128::new(MIN_BOND),
+        unbonding_period: UNBONDING_PERIOD,
+        slashers,
+        admin,
+    }
+}
+
+/// Operators 1 and 2 must be funded with 1000 of `BOND_DENOM`
+pub fn bond_and_unbond<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op1 = chain.alt_signer(1);
+    let op2 = chain.alt_signer(2);
+
+    let contract = setup(chain.clone(), default_msg(vec![], None));
+
+    // below the minimum bond
+    contract
+        .call_as(&op1)
+        .bond(&coins(MIN_BOND - 1, BOND_DENOM))
+        .unwrap_err();
+    // wrong denom
+    contract
+        .call_as(&op1)
+        .bond(&coins(MIN_BOND, "uother"))
+        .unwrap_err();
+
+    contract
+        .call_as(&op1)
+        .bond(&coins(1_000, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&op2)
+        .bond(&coins(400, BOND_DENOM))
+        .unwrap();
+    // topping up keeps a single entry
+    contract
+        .call_as(&op2)
+        .bond(&coins(100, BOND_DENOM))
+        .unwrap();
+
+    let power = contract
+        .voting_power_at_height(op1.addr().into_string(), None)
+        .unwrap();
+    assert_eq!(power.power, Uint128::new(1_000));
+    let total_power = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total_power.power, Uint128::new(1_500));
+    let voters = contract.all_voters(None, None).unwrap().voters;
+    assert_eq!(voters.len(), 2);
+
+    // must leave either enough to stay registered or nothing
+    contract
+        .call_as(&op1)
+        .unbond(Uint128::new(1_000 - MIN_BOND + 1))
+        .unwrap_err();
+    contract
+        .call_as(&op1)
+        .unbond(Uint128::new(1_001))
+        .unwrap_err();
+    contract.call_as(&op1).unbond(Uint128::new(1_000)).unwrap();
+
+    // power is gone right away
+    let power = contract
+        .voting_power_at_height(op1.addr().into_string(), None)
+        .unwrap();
+    assert_eq!(power.power, Uint128::zero());
+    let total_power = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total_power.power, Uint128::new(500));
+    let voters = contract.all_voters(None, None).unwrap().voters;
+    assert_eq!(voters.len(), 1);
+
+    // but the tokens are locked until the unbonding period is over
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert_eq!(operator.bond, Uint128::zero());
+    assert_eq!(operator.unbonding.len(), 1);
+    assert_eq!(operator.unbonding[0].amount, Uint128::new(1_000));
+    contract.call_as(&op1).claim().unwrap_err();
+
+    chain.wait_seconds(UNBONDING_PERIOD).unwrap();
+    contract.call_as(&op1).claim().unwrap();
+    contract.call_as(&op1).claim().unwrap_err();
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert!(operator.unbonding.is_empty());
+
+    // the claimed tokens are back and can be bonded again
+    contract
+        .call_as(&op1)
+        .bond(&coins(1_000, BOND_DENOM))
+        .unwrap();
+}
+
+/// The operator must be funded with 1000 of `BOND_DENOM`
+pub fn slashing<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let admin = chain.alt_signer(1);
+    let operator = chain.alt_signer(2);
+    let verifier = chain.alt_signer(3);
+    let other_verifier = chain.alt_signer(4);
+
+    let contract = setup(
+        chain.clone(),
+        default_msg(
+            vec![verifier.addr().to_string()],
+            Some(admin.addr().to_string()),
+        ),
+    );
+
+    contract
+        .call_as(&operator)
+        .bond(&coins(1_000, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&operator)
+        .unbond(Uint128::new(400))
+        .unwrap();
+
+    // only authorized slashers
+    contract
+        .call_as(&other_verifier)
+        .slash(operator.addr().to_string(), Uint128::new(100))
+        .unwrap_err();
+    contract
+        .call_as(&operator)
+        .slash(operator.addr().to_string(), Uint128::new(100))
+        .unwrap_err();
+
+    // the bond and the unbonding tokens lose the same share, and the slasher gets them
+    contract
+        .call_as(&verifier)
+        .slash(operator.addr().to_string(), Uint128::new(800))
+        .unwrap();
+    let balance = chain
+        .bank_querier()
+        .balance(&verifier.addr(), Some(BOND_DENOM.to_string()))
+        .unwrap();
+    assert_eq!(balance, coins(800, BOND_DENOM));
+    let info = contract.operator(operator.addr().into_string()).unwrap();
+    assert_eq!(info.bond, Uint128::new(120));
+    assert_eq!(info.power, Uint128::new(120));
+    assert_eq!(info.unbonding.len(), 1);
+    assert_eq!(info.unbonding[0].amount, Uint128::new(80));
+    let total_power = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total_power.power, Uint128::new(120));
+
+    // the admin can authorize more slashers
+    contract
+        .call_as(&verifier)
+        .update_slashers(vec![other_verifier.addr().to_string()], vec![])
+        .unwrap_err();
+    contract
+        .call_as(&admin)
+        .update_slashers(
+            vec![other_verifier.addr().to_string()],
+            vec![verifier.addr().to_string()],
+        )
+        .unwrap();
+    contract
+        .call_as(&verifier)
+        .slash(operator.addr().to_string(), Uint128::new(50))
+        .unwrap_err();
+    let res = contract
+        .call_as(&other_verifier)
+        .slash(operator.addr().to_string(), Uint128::new(50))
+        .unwrap();
+    let slashed: SlashResponse = from_json(res.data().unwrap()).unwrap();
+    assert_eq!(slashed.slashed, coin(50, BOND_DENOM));
+
+    let info = contract.operator(operator.addr().into_string()).unwrap();
+    assert_eq!(info.bond, Uint128::new(90));
+    assert_eq!(info.unbonding[0].amount, Uint128::new(60));
+
+    // only what is left can be claimed
+    chain.wait_seconds(UNBONDING_PERIOD).unwrap();
+    contract.call_as(&operator).claim().unwrap();
+    contract
+        .call_as(&operator)
+        .bond(&coins(61, BOND_DENOM))
+        .unwrap_err();
+    contract
+        .call_as(&operator)
+        .bond(&coins(60, BOND_DENOM))
+        .unwrap();
+}
+
+/// Operators 1 and 2 must be funded with 1000 of `BOND_DENOM`
+pub fn slashing_spares_other_operators<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let honest = chain.alt_signer(1);
+    let operator = chain.alt_signer(2);
+    let verifier = chain.alt_signer(3);
+
+    let contract = setup(
+        chain.clone(),
+        default_msg(vec![verifier.addr().to_string()], None),
+    );
+
+    contract
+        .call_as(&honest)
+        .bond(&coins(500, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&operator)
+        .bond(&coins(500, BOND_DENOM))
+        .unwrap();
+
+    // the operator is also undelegating from the honest one, then unbonds part of its own bond
+    contract
+        .call_as(&operator)
+        .delegate(honest.addr().to_string(), &coins(300, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&operator)
+        .undelegate(honest.addr().to_string(), Uint128::new(300))
+        .unwrap();
+    contract
+        .call_as(&operator)
+        .unbond(Uint128::new(200))
+        .unwrap();
+
+    // only its own stake is slashed, whatever the amount
+    contract
+        .call_as(&verifier)
+        .slash(operator.addr().to_string(), Uint128::new(1_000))
+        .unwrap();
+    let info = contract.operator(operator.addr().into_string()).unwrap();
+    assert_eq!(info.bond, Uint128::zero());
+    assert_eq!(info.unbonding.len(), 1);
+    assert_eq!(info.unbonding[0].operator, honest.addr());
+    assert_eq!(info.unbonding[0].amount, Uint128::new(300));
+
+    chain.wait_seconds(UNBONDING_PERIOD).unwrap();
+    contract.call_as(&operator).claim().unwrap();
+    let balance = chain
+        .bank_querier()
+        .balance(&operator.addr(), Some(BOND_DENOM.to_string()))
+        .unwrap();
+    assert_eq!(balance, coins(500, BOND_DENOM));
+}
+
+/// The operator and delegators 2 and 3 must be funded with 1000 of `BOND_DENOM`
+pub fn slashing_delegations<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator = chain.alt_signer(1);
+    let delegator = chain.alt_signer(2);
+    let leaving = chain.alt_signer(3);
+    let verifier = chain.alt_signer(4);
+
+    let contract = setup(
+        chain.clone(),
+        default_msg(vec![verifier.addr().to_string()], None),
+    );
+
+    contract
+        .call_as(&operator)
+        .bond(&coins(100, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&delegator)
+        .delegate(operator.addr().to_string(), &coins(300, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&leaving)
+        .delegate(operator.addr().to_string(), &coins(200, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&leaving)
+        .undelegate(operator.addr().to_string(), Uint128::new(100))
+        .unwrap();
+
+    // more than the self-bond: 600 are staked with the operator, everyone loses half
+    let res = contract
+        .call_as(&verifier)
+        .slash(operator.addr().to_string(), Uint128::new(300))
+        .unwrap();
+    let slashed: SlashResponse = from_json(res.data().unwrap()).unwrap();
+    assert_eq!(slashed.slashed, coin(300, BOND_DENOM));
+    let balance = chain
+        .bank_querier()
+        .balance(&verifier.addr(), Some(BOND_DENOM.to_string()))
+        .unwrap();
+    assert_eq!(balance, coins(300, BOND_DENOM));
+
+    let info = contract.operator(operator.addr().into_string()).unwrap();
+    assert_eq!(info.bond, Uint128::new(50));
+    assert_eq!(info.delegated, Uint128::new(200));
+    assert_eq!(info.power, Uint128::new(250));
+    let total_power = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total_power.power, Uint128::new(250));
+    let delegation = contract
+        .delegation(operator.addr().to_string(), delegator.addr().to_string())
+        .unwrap();
+    assert_eq!(delegation.amount, Uint128::new(150));
+    let delegation = contract
+        .delegation(operator.addr().to_string(), leaving.addr().to_string())
+        .unwrap();
+    assert_eq!(delegation.amount, Uint128::new(50));
+    let claims = contract.operator(leaving.addr().into_string()).unwrap();
+    assert_eq!(claims.unbonding.len(), 1);
+    assert_eq!(claims.unbonding[0].amount, Uint128::new(50));
+
+    // more than everything staked only takes what is there
+    let res = contract
+        .call_as(&verifier)
+        .slash(operator.addr().to_string(), Uint128::new(10_000))
+        .unwrap();
+    let slashed: SlashResponse = from_json(res.data().unwrap()).unwrap();
+    assert_eq!(slashed.slashed, coin(300, BOND_DENOM));
+
+    let info = contract.operator(operator.addr().into_string()).unwrap();
+    assert_eq!(info.bond, Uint128::zero());
+    assert_eq!(info.delegated, Uint128::zero());
+    assert_eq!(info.power, Uint128::zero());
+    let total_power = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total_power.power, Uint128::zero());
+    let claims = contract.operator(leaving.addr().into_string()).unwrap();
+    assert!(claims.unbonding.is_empty());
+
+    chain.wait_seconds(UNBONDING_PERIOD).unwrap();
+    contract.call_as(&leaving).claim().unwrap_err();
+    contract
+        .call_as(&verifier)
+        .slash(operator.addr().to_string(), Uint128::new(100))
+        .unwrap_err();
+}
+
+pub fn historical_power<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator = chain.alt_signer(1);
+
+    let contract = setup(chain.clone(), default_msg(vec![], None));
+    let contract = contract.call_as(&operator);
+    let start = chain.block_info().unwrap().height;
+
+    chain.wait_blocks(5).unwrap();
+    contract.bond(&coins(600, BOND_DENOM)).unwrap();
+    let bonded = chain.block_info().unwrap().height;
+
+    chain.wait_blocks(5).unwrap();
+    contract.unbond(Uint128::new(200)).unwrap();
+    let unbonded = chain.block_info().unwrap().height;
+
+    chain.wait_blocks(1).unwrap();
+
+    let power_at = |height: u64| {
+        contract
+            .voting_power_at_height(operator.addr().into_string(), Some(height))
+            .unwrap()
+            .power
+            .u128()
+    };
+    let total_at = |height: u64| {
+        contract
+            .total_power_at_height(Some(height))
+            .unwrap()
+            .power
+            .u128()
+    };
+
+    assert_eq!(power_at(start), 0);
+    assert_eq!(power_at(bonded - 1), 0);
+    assert_eq!(power_at(bonded), 600);
+    assert_eq!(power_at(unbonded - 1), 600);
+    assert_eq!(power_at(unbonded), 400);
+
+    assert_eq!(total_at(start), 0);
+    assert_eq!(total_at(bonded), 600);
+    assert_eq!(total_at(unbonded), 400);
+}
+
+/// The operator, delegator and distributor must be funded with 1000 of `BOND_DENOM` each
+pub fn delegation<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator = chain.alt_signer(1);
+    let delegator = chain.alt_signer(2);
+    let distributor = chain.alt_signer(3);
+
+    let contract = setup(chain.clone(), default_msg(vec![], None));
+
+    // only registered operators can get delegations
+    contract
+        .call_as(&delegator)
+        .delegate(operator.addr().to_string(), &coins(500, BOND_DENOM))
+        .unwrap_err();
+
+    contract
+        .call_as(&operator)
+        .bond(&coins(500, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&operator)
+        .delegate(operator.addr().to_string(), &coins(100, BOND_DENOM))
+        .unwrap_err();
+    contract
+        .call_as(&delegator)
+        .delegate(operator.addr().to_string(), &coins(500, BOND_DENOM))
+        .unwrap();
+
+    // power is the self-bond plus delegations
+    let info = contract.operator(operator.addr().into_string()).unwrap();
+    assert_eq!(info.bond, Uint128::new(500));
+    assert_eq!(info.delegated, Uint128::new(500));
+    assert_eq!(info.power, Uint128::new(1_000));
+    let total_power = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total_power.power, Uint128::new(1_000));
+
+    // 10% commission, then the rest is split by stake
+    contract
+        .call_as(&operator)
+        .set_commission(Decimal::percent(101))
+        .unwrap_err();
+    contract
+        .call_as(&operator)
+        .set_commission(Decimal::percent(10))
+        .unwrap();
+    contract
+        .call_as(&distributor)
+        .distribute_rewards(operator.addr().to_string(), &coins(1_000, BOND_DENOM))
+        .unwrap();
+
+    let operator_share = contract
+        .delegation(operator.addr().to_string(), operator.addr().to_string())
+        .unwrap();
+    assert_eq!(operator_share.amount, Uint128::new(500));
+    assert_eq!(operator_share.rewards, Uint128::new(550));
+    let delegator_share = contract
+        .delegation(operator.addr().to_string(), delegator.addr().to_string())
+        .unwrap();
+    assert_eq!(delegator_share.amount, Uint128::new(500));
+    assert_eq!(delegator_share.rewards, Uint128::new(450));
+
+    // rewards are paid out, which the delegator can delegate again
+    contract
+        .call_as(&delegator)
+        .claim_rewards(operator.addr().to_string())
+        .unwrap();
+    contract
+        .call_as(&delegator)
+        .claim_rewards(operator.addr().to_string())
+        .unwrap_err();
+    contract
+        .call_as(&delegator)
+        .delegate(operator.addr().to_string(), &coins(951, BOND_DENOM))
+        .unwrap_err();
+    contract
+        .call_as(&delegator)
+        .delegate(operator.addr().to_string(), &coins(950, BOND_DENOM))
+        .unwrap();
+    contract
+        .call_as(&operator)
+        .claim_rewards(operator.addr().to_string())
+        .unwrap();
+
+    // undelegating removes the power right away, the tokens are released later
+    contract
+        .call_as(&delegator)
+        .undelegate(operator.addr().to_string(), Uint128::new(1_451))
+        .unwrap_err();
+    contract
+        .call_as(&delegator)
+        .undelegate(operator.addr().to_string(), Uint128::new(1_450))
+        .unwrap();
+    let info = contract.operator(operator.addr().into_string()).unwrap();
+    assert_eq!(info.delegated, Uint128::zero());
+    assert_eq!(info.power, Uint128::new(500));
+
+    contract.call_as(&delegator).claim().unwrap_err();
+    chain.wait_seconds(UNBONDING_PERIOD).unwrap();
+    contract.call_as(&delegator).claim().unwrap();
+}
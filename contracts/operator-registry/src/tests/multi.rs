@@ -0,0 +1,51 @@
+use cosmwasm_std::coins;
+use cw_orch::prelude::MockBech32;
+use lavs_orch::{Addressable, AltSigner};
+
+use super::common::{BECH_PREFIX, BOND_DENOM};
+
+fn funded_chain(signers: &[u32]) -> MockBech32 {
+    let chain = MockBech32::new(BECH_PREFIX);
+    for signer in signers {
+        chain
+            .add_balance(&chain.alt_signer(*signer).addr(), coins(1_000, BOND_DENOM))
+            .unwrap();
+    }
+    chain
+}
+
+#[test]
+fn bond_and_unbond() {
+    let chain = funded_chain(&[1, 2]);
+    super::common::bond_and_unbond(chain);
+}
+
+#[test]
+fn slashing() {
+    let chain = funded_chain(&[2]);
+    super::common::slashing(chain);
+}
+
+#[test]
+fn slashing_spares_other_operators() {
+    let chain = funded_chain(&[1, 2]);
+    super::common::slashing_spares_other_operators(chain);
+}
+
+#[test]
+fn slashing_delegations() {
+    let chain = funded_chain(&[1, 2, 3]);
+    super::common::slashing_delegations(chain);
+}
+
+#[test]
+fn historical_power() {
+    let chain = funded_chain(&[1]);
+    super::common::historical_power(chain);
+}
+
+#[test]
+fn delegation() {
+    let chain = funded_chain(&[1, 2, 3]);
+    super::common::delegation(chain);
+}
@@ -1,20 +1,29 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdResult,
+    from_json, to_json_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Reply, Response, StdResult, SubMsgResult,
 };
 use cw2::set_contract_version;
+use cw_utils::parse_reply_execute_data;
+use lavs_apis::id::TaskId;
 use lavs_apis::verifier_simple::OperatorVoteInfoResponse;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG, SLASHED_OPERATORS, VOTES};
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SlashResponse};
+use crate::state::{Config, CrossCheck, DisputeConfig, CONFIG, PENDING, SLASHED_OPERATORS, VOTES};
 
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Reply to a failed `TaskExecuteMsg::Complete` sent when settling or resolving a dispute
+pub const COMPLETE_REPLY_ID: u64 = 1;
+/// Reply to the slashing of an operator backing an upheld result, to pay the challenger
+pub const SLASH_REPLY_ID: u64 = 2;
+/// Reply to a failed `CompleteBatch` sent when settling several results
+pub const COMPLETE_BATCH_REPLY_ID: u64 = 3;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -45,12 +54,62 @@ pub fn instantiate(
         ));
     }
     let op_addr = deps.api.addr_validate(&msg.operator_contract)?;
+
+    let cross_check = match msg.cross_check {
+        Some(cross_check) => {
+            if cross_check.tolerance > Decimal::percent(100) {
+                return Err(ContractError::InvalidPercentage(
+                    "tolerance".to_string(),
+                    cross_check.tolerance,
+                ));
+            }
+            let operator_contracts = cross_check
+                .operator_contracts
+                .iter()
+                .map(|addr| deps.api.addr_validate(addr))
+                .collect::<StdResult<Vec<_>>>()?;
+
+            // every operator set must be a different contract
+            let mut all_sets: Vec<&Addr> = operator_contracts.iter().collect();
+            all_sets.push(&op_addr);
+            all_sets.sort();
+            all_sets.dedup();
+            if operator_contracts.is_empty() || all_sets.len() != operator_contracts.len() + 1 {
+                return Err(ContractError::InvalidOperatorSets);
+            }
+
+            Some(CrossCheck {
+                operator_contracts,
+                tolerance: cross_check.tolerance,
+            })
+        }
+        None => None,
+    };
+
+    let dispute = match msg.dispute {
+        Some(dispute) => {
+            if dispute.period == 0 || dispute.resolution_period == 0 {
+                return Err(ContractError::InvalidDisputePeriod);
+            }
+            Some(DisputeConfig {
+                period: dispute.period,
+                arbiter: deps.api.addr_validate(&dispute.arbiter)?,
+                bond: dispute.bond,
+                slash_amount: dispute.slash_amount,
+                resolution_period: dispute.resolution_period,
+            })
+        }
+        None => None,
+    };
+
     let config = Config {
         operator_contract: op_addr,
         threshold_percent: msg.threshold_percentage,
         allowed_spread: msg.allowed_spread,
         slashable_spread: msg.slashable_spread,
         required_percentage: msg.required_percentage,
+        cross_check,
+        dispute,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -73,6 +132,86 @@ pub fn execute(
             task_id,
             result,
         } => execute::executed_task(deps, env, info, task_queue_contract, task_id, result),
+        ExecuteMsg::Dispute {
+            task_queue_contract,
+            task_id,
+            evidence,
+        } => execute::dispute(deps, env, info, task_queue_contract, task_id, evidence),
+        ExecuteMsg::Settle {
+            task_queue_contract,
+            task_id,
+        } => execute::settle(deps, env, info, task_queue_contract, task_id),
+        ExecuteMsg::SettleBatch {
+            task_queue_contract,
+            task_ids,
+        } => execute::settle_batch(deps, env, info, task_queue_contract, task_ids),
+        ExecuteMsg::ResolveDispute {
+            task_queue_contract,
+            task_id,
+            upheld,
+            price,
+        } => execute::resolve_dispute(deps, info, task_queue_contract, task_id, upheld, price),
+        ExecuteMsg::ExpireDispute {
+            task_queue_contract,
+            task_id,
+        } => execute::expire_dispute(deps, env, info, task_queue_contract, task_id),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        // the task queue rejected the result (e.g. the task expired while disputed),
+        // the pending result is cleared anyway
+        COMPLETE_REPLY_ID => {
+            let task_id: TaskId = from_json(&msg.payload)?;
+            let error = match msg.result {
+                SubMsgResult::Err(error) => error,
+                SubMsgResult::Ok(_) => return Ok(Response::new()),
+            };
+            Ok(Response::new()
+                .add_attribute("method", "complete_failed")
+                .add_attribute("task_id", task_id.to_string())
+                .add_attribute("error", error))
+        }
+        COMPLETE_BATCH_REPLY_ID => {
+            let task_ids: Vec<TaskId> = from_json(&msg.payload)?;
+            let error = match msg.result {
+                SubMsgResult::Err(error) => error,
+                SubMsgResult::Ok(_) => return Ok(Response::new()),
+            };
+            Ok(Response::new()
+                .add_attribute("method", "complete_failed")
+                .add_attributes(task_ids.iter().map(|id| ("task_id", id.to_string())))
+                .add_attribute("error", error))
+        }
+        // the slashed stake was sent to this contract, it goes to the challenger. An operator
+        // that can't be slashed (e.g. it left) doesn't block the resolution
+        SLASH_REPLY_ID => {
+            let challenger: Addr = from_json(&msg.payload)?;
+            if let SubMsgResult::Err(error) = &msg.result {
+                return Ok(Response::new()
+                    .add_attribute("method", "slash_failed")
+                    .add_attribute("error", error));
+            }
+            let data = parse_reply_execute_data(msg)?
+                .data
+                .ok_or(ContractError::MissingSlashData)?;
+            let SlashResponse { slashed } = from_json(&data)?;
+
+            let mut resp = Response::new()
+                .add_attribute("method", "slash_reward")
+                .add_attribute("challenger", challenger.to_string())
+                .add_attribute("amount", slashed.to_string());
+            if !slashed.amount.is_zero() {
+                resp = resp.add_message(BankMsg::Send {
+                    to_address: challenger.into_string(),
+                    amount: vec![slashed],
+                });
+            }
+            Ok(resp)
+        }
+        id => Err(ContractError::UnknownReplyId(id)),
     }
 }
 
@@ -108,20 +247,38 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 .collect::<StdResult<Vec<_>>>()?;
             to_json_binary(&slashed_operators)
         }
+        QueryMsg::Pending {
+            task_contract,
+            task_id,
+        } => {
+            let task_contract = deps.api.addr_validate(&task_contract)?;
+            to_json_binary(&PENDING.may_load(deps.storage, (&task_contract, task_id))?)
+        }
+        QueryMsg::CrossCheck {
+            task_contract,
+            task_id,
+        } => to_json_binary(&query::cross_check(deps, task_contract, task_id)?),
     }
 }
 
 mod execute {
 
-    use cosmwasm_std::{to_json_binary, Decimal, Order, Uint128, WasmMsg};
-    use cw_utils::nonpayable;
+    use cosmwasm_std::{
+        to_json_binary, Coin, Decimal, Decimal256, Order, SubMsg, Uint128, WasmMsg,
+    };
+    use cw_utils::{must_pay, nonpayable};
     use lavs_apis::{
-        id::TaskId,
-        tasks::{TaskExecuteMsg, TaskStatus},
+        interfaces::voting::{QueryMsg as VotingQueryMsg, TotalPowerResponse, VotingPowerResponse},
+        tasks::{TaskExecuteMsg, TaskQueryMsg, TaskStatus, TaskStatusResponse},
     };
     use lavs_helpers::verifier::ensure_valid_vote;
+    use lavs_task_queue::msg::{CustomExecuteMsg, TaskResult};
 
-    use crate::state::{record_vote, OperatorVote, SLASHED_OPERATORS, TASKS, VOTES};
+    use crate::msg::SlashExecuteMsg;
+    use crate::state::{
+        record_vote, CrossCheckStatus, Dispute, OperatorVote, PendingResult, CROSS_CHECKS,
+        SET_RESULTS, SLASHED_OPERATORS, TASKS, VOTES, VOTE_SETS,
+    };
 
     use super::*;
 
@@ -140,6 +297,11 @@ mod execute {
         let operator = info.sender;
 
         let config = CONFIG.load(deps.storage)?;
+        if config.cross_check.is_some() {
+            return executed_cross_checked_task(
+                deps, env, &config, task_queue, task_id, operator, result,
+            );
+        }
 
         // operator allowed to vote and hasn't voted yet
         let (mut task_data, power) = match ensure_valid_vote(
@@ -170,7 +332,7 @@ mod execute {
             .range(deps.storage, None, None, Order::Ascending)
             .collect::<StdResult<Vec<_>>>()?;
 
-        let total_power: Uint128 = all_votes.iter().map(|(_, vote)| vote.power).sum();
+        let total_power = sum_power(all_votes.iter().map(|(_, vote)| vote))?;
 
         let mut resp = Response::new().add_attribute("method", "executed_task");
         if total_power < task_data.power_required {
@@ -190,59 +352,593 @@ mod execute {
             task_data.status = TaskStatus::Completed;
             TASKS.save(deps.storage, (&task_queue, task_id), &task_data)?;
 
-            let response = serde_json::json!(crate::state::PriceResult {
-                price: median.to_string()
-            });
+            let backers = backing_operators(&all_votes, median, &config)?;
+            resp = match finalize_result(
+                deps.branch(),
+                &env,
+                &config,
+                &task_queue,
+                task_id,
+                median,
+                backers,
+            )? {
+                Some(msg) => resp.add_message(msg),
+                None => resp.add_attribute("status", "pending"),
+            }
+            .add_attribute("new_price", median.to_string());
+        } else {
+            resp = resp.add_attribute("status", "threshold_not_met");
+        }
+
+        resp = resp
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("task_queue_contract", task_queue_contract);
 
+        // NOTE: If we ever want to optimize the storage we can remove the votes for the completed
+        // tasks.
+
+        Ok(resp)
+    }
+
+    /// Every operator set finalizes the task independently, like a verifier of its own.
+    /// The result is only sent to the task queue once all sets agree within the tolerance.
+    fn executed_cross_checked_task(
+        mut deps: DepsMut,
+        env: Env,
+        config: &Config,
+        task_queue: Addr,
+        task_id: TaskId,
+        operator: Addr,
+        result: String,
+    ) -> Result<Response, ContractError> {
+        let tolerance = match &config.cross_check {
+            Some(cross_check) => cross_check.tolerance,
+            None => return Err(ContractError::InvalidOperatorSets),
+        };
+
+        match CROSS_CHECKS.may_load(deps.storage, (&task_queue, task_id))? {
+            Some(CrossCheckStatus::Completed) => return Err(ContractError::TaskAlreadyCompleted),
+            Some(CrossCheckStatus::Disputed) => return Err(ContractError::TaskDisputed),
+            None => {}
+        }
+
+        let task_status: TaskStatusResponse = deps.querier.query_wasm_smart(
+            task_queue.to_string(),
+            &TaskQueryMsg::TaskStatus { id: task_id },
+        )?;
+        match task_status.status {
+            TaskStatus::Open => {}
+            TaskStatus::Completed => return Err(ContractError::TaskAlreadyCompleted),
+            TaskStatus::Expired => return Err(ContractError::TaskExpired),
+        }
+        let height = task_status.created_height;
+
+        // the operator votes in the set where they had power when the task was created. The sets
+        // must stay independent, an operator in several of them could sway all of them
+        let operator_sets = config.operator_sets();
+        let mut membership = None;
+        for (set, operator_contract) in operator_sets.iter().enumerate() {
+            let power = voting_power_at(deps.as_ref(), operator_contract, &operator, height)?;
+            if power.is_zero() {
+                continue;
+            }
+            if membership.is_some() {
+                return Err(ContractError::OperatorInSeveralSets(operator.to_string()));
+            }
+            membership = Some((set as u32, *operator_contract, power));
+        }
+        let (set, operator_contract, power) = membership.ok_or(ContractError::Unauthorized)?;
+
+        if VOTES.has(deps.storage, (&task_queue, task_id, &operator)) {
+            return Err(ContractError::OperatorAlreadyVoted(operator.to_string()));
+        }
+        if SET_RESULTS.has(deps.storage, (&task_queue, task_id, set)) {
+            return Err(ContractError::OperatorSetFinalized(set));
+        }
+
+        record_vote(
+            deps.storage,
+            &task_queue,
+            task_id,
+            &operator,
+            &result,
+            power,
+        )?;
+        VOTE_SETS.save(deps.storage, (&task_queue, task_id, &operator), &set)?;
+
+        let all_votes: Vec<(Addr, OperatorVote)> = VOTES
+            .prefix((&task_queue, task_id))
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let mut set_votes = Vec::with_capacity(all_votes.len());
+        for (voter, vote) in all_votes.iter() {
+            if VOTE_SETS.load(deps.storage, (&task_queue, task_id, voter))? == set {
+                set_votes.push((voter.clone(), vote.clone()));
+            }
+        }
+
+        let voted_power = sum_power(set_votes.iter().map(|(_, vote)| vote))?;
+        let power_required = total_power_at(deps.as_ref(), operator_contract, height)?
+            .multiply_ratio(config.required_percentage, 100u32);
+
+        let mut resp = Response::new()
+            .add_attribute("method", "executed_task")
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("task_queue_contract", task_queue.to_string())
+            .add_attribute("operator_set", set.to_string());
+        if voted_power < power_required {
+            return Ok(resp.add_attribute("status", "vote_stored"));
+        }
+
+        let (median, slashable_operators, is_threshold_met) =
+            process_votes(&set_votes, voted_power, config)?;
+        if !is_threshold_met {
+            return Ok(resp.add_attribute("status", "threshold_not_met"));
+        }
+
+        for operator in slashable_operators {
+            noop_slash_validator(&mut deps, &operator)?;
+        }
+        SET_RESULTS.save(deps.storage, (&task_queue, task_id, set), &median)?;
+        resp = resp.add_attribute("set_price", median.to_string());
+
+        let mut set_prices: Vec<Decimal256> = SET_RESULTS
+            .prefix((&task_queue, task_id))
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|r| r.map(|(_, price)| price))
+            .collect::<StdResult<Vec<_>>>()?;
+        if set_prices.len() < operator_sets.len() {
+            return Ok(resp.add_attribute("status", "awaiting_operator_sets"));
+        }
+
+        // calculate_median sorts the prices, so the extremes are the first and last ones
+        let agreed_price = calculate_median(&mut set_prices)?;
+        let (minimum, maximum) = calculate_allowed_range(agreed_price, tolerance)?;
+        if set_prices[0] < minimum || set_prices[set_prices.len() - 1] > maximum {
+            CROSS_CHECKS.save(
+                deps.storage,
+                (&task_queue, task_id),
+                &CrossCheckStatus::Disputed,
+            )?;
+            return Ok(resp.add_attribute("status", "disputed"));
+        }
+
+        CROSS_CHECKS.save(
+            deps.storage,
+            (&task_queue, task_id),
+            &CrossCheckStatus::Completed,
+        )?;
+        let backers = backing_operators(&all_votes, agreed_price, config)?;
+        let resp = match finalize_result(
+            deps.branch(),
+            &env,
+            config,
+            &task_queue,
+            task_id,
+            agreed_price,
+            backers,
+        )? {
+            Some(msg) => resp.add_message(msg),
+            None => resp.add_attribute("status", "pending"),
+        };
+        Ok(resp.add_attribute("new_price", agreed_price.to_string()))
+    }
+
+    /// Returns the message completing the task, unless a dispute window is configured.
+    /// In that case the result is stored as pending until it is settled or resolved.
+    /// The verifier is not tied to one task queue, so the window is checked against the
+    /// expiration of each task: a task timing out before it closes is completed right away,
+    /// as its result could never be settled.
+    fn finalize_result(
+        deps: DepsMut,
+        env: &Env,
+        config: &Config,
+        task_queue: &Addr,
+        task_id: TaskId,
+        price: Decimal256,
+        backers: Vec<Addr>,
+    ) -> Result<Option<WasmMsg>, ContractError> {
+        match &config.dispute {
+            Some(dispute) => {
+                let settles_at = env.block.time.seconds() + dispute.period;
+                let task_status: TaskStatusResponse = deps.querier.query_wasm_smart(
+                    task_queue.to_string(),
+                    &TaskQueryMsg::TaskStatus { id: task_id },
+                )?;
+                if settles_at >= task_status.expires_time {
+                    return Ok(Some(complete_task_msg(task_queue, task_id, price)?));
+                }
+
+                let pending = PendingResult {
+                    price,
+                    settles_at,
+                    backers,
+                    dispute: None,
+                };
+                PENDING.save(deps.storage, (task_queue, task_id), &pending)?;
+                Ok(None)
+            }
+            None => Ok(Some(complete_task_msg(task_queue, task_id, price)?)),
+        }
+    }
+
+    /// Operators whose vote is within the allowed spread of the final price
+    fn backing_operators(
+        votes: &[(Addr, OperatorVote)],
+        price: Decimal256,
+        config: &Config,
+    ) -> Result<Vec<Addr>, ContractError> {
+        let (allowed_minimum, allowed_maximum) =
+            calculate_allowed_range(price, config.allowed_spread)?;
+        Ok(filter_valid_votes(votes, allowed_minimum, allowed_maximum)
+            .into_iter()
+            .map(|(operator, _)| operator.clone())
+            .collect())
+    }
+
+    pub fn dispute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_id: TaskId,
+        evidence: String,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let dispute_config = config.dispute.ok_or(ContractError::DisputesDisabled)?;
+        let bond = must_pay(&info, &dispute_config.bond.denom)?;
+        if bond < dispute_config.bond.amount {
+            return Err(ContractError::InsufficientBond(
+                dispute_config.bond.amount.u128(),
+                dispute_config.bond.denom,
+            ));
+        }
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let mut pending = PENDING
+            .may_load(deps.storage, (&task_queue, task_id))?
+            .ok_or(ContractError::NoPendingResult)?;
+        if env.block.time.seconds() >= pending.settles_at {
+            return Err(ContractError::DisputeWindowClosed);
+        }
+        if pending.dispute.is_some() {
+            return Err(ContractError::AlreadyDisputed);
+        }
+
+        pending.dispute = Some(Dispute {
+            challenger: info.sender.clone(),
+            evidence,
+            bond: Coin::new(bond, dispute_config.bond.denom),
+            resolve_by: env.block.time.seconds() + dispute_config.resolution_period,
+        });
+        PENDING.save(deps.storage, (&task_queue, task_id), &pending)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "dispute")
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("task_queue_contract", task_queue_contract)
+            .add_attribute("challenger", info.sender))
+    }
+
+    pub fn settle(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let pending = PENDING
+            .may_load(deps.storage, (&task_queue, task_id))?
+            .ok_or(ContractError::NoPendingResult)?;
+        if pending.dispute.is_some() {
+            return Err(ContractError::AlreadyDisputed);
+        }
+        if env.block.time.seconds() < pending.settles_at {
+            return Err(ContractError::DisputeWindowOpen(pending.settles_at));
+        }
+        PENDING.remove(deps.storage, (&task_queue, task_id));
+
+        Ok(Response::new()
+            .add_submessage(complete_task_submsg(&task_queue, task_id, pending.price)?)
+            .add_attribute("method", "settle")
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("task_queue_contract", task_queue_contract)
+            .add_attribute("new_price", pending.price.to_string()))
+    }
+
+    pub fn settle_batch(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_ids: Vec<TaskId>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let mut results = vec![];
+        let mut dropped = vec![];
+        for task_id in task_ids {
+            let pending = PENDING
+                .may_load(deps.storage, (&task_queue, task_id))?
+                .ok_or(ContractError::NoPendingResult)?;
+            if pending.dispute.is_some() {
+                return Err(ContractError::AlreadyDisputed);
+            }
+            if env.block.time.seconds() < pending.settles_at {
+                return Err(ContractError::DisputeWindowOpen(pending.settles_at));
+            }
+            PENDING.remove(deps.storage, (&task_queue, task_id));
+
+            // the batch fails as a whole, so the tasks that can't be completed anymore
+            // (e.g. expired) are dropped instead of failing the others
+            let task_status: TaskStatusResponse = deps.querier.query_wasm_smart(
+                task_queue.to_string(),
+                &TaskQueryMsg::TaskStatus { id: task_id },
+            )?;
+            match task_status.status {
+                TaskStatus::Open => results.push(TaskResult {
+                    task_id,
+                    response: price_response(pending.price),
+                }),
+                TaskStatus::Completed | TaskStatus::Expired => dropped.push(task_id),
+            }
+        }
+
+        let task_ids: Vec<_> = results.iter().map(|result| result.task_id).collect();
+        let mut resp = Response::new()
+            .add_attribute("method", "settle_batch")
+            .add_attribute("task_queue_contract", task_queue_contract)
+            .add_attributes(task_ids.iter().map(|id| ("task_id", id.to_string())))
+            .add_attributes(dropped.iter().map(|id| ("dropped_task_id", id.to_string())));
+        if !results.is_empty() {
             let msg = WasmMsg::Execute {
                 contract_addr: task_queue.to_string(),
-                msg: to_json_binary(&TaskExecuteMsg::Complete { task_id, response })?,
+                msg: to_json_binary(&CustomExecuteMsg::CompleteBatch { results })?,
                 funds: vec![],
             };
+            resp = resp.add_submessage(
+                SubMsg::reply_on_error(msg, COMPLETE_BATCH_REPLY_ID)
+                    .with_payload(to_json_binary(&task_ids)?),
+            );
+        }
+        Ok(resp)
+    }
+
+    pub fn resolve_dispute(
+        mut deps: DepsMut,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_id: TaskId,
+        upheld: bool,
+        price: Option<Decimal256>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let dispute_config = config
+            .dispute
+            .as_ref()
+            .ok_or(ContractError::DisputesDisabled)?;
+        if info.sender != dispute_config.arbiter {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let pending = PENDING
+            .may_load(deps.storage, (&task_queue, task_id))?
+            .ok_or(ContractError::NoPendingResult)?;
+        let dispute = pending.dispute.ok_or(ContractError::NotDisputed)?;
+        if upheld && price.is_none() {
+            return Err(ContractError::MissingCorrectedPrice);
+        }
+        PENDING.remove(deps.storage, (&task_queue, task_id));
+
+        let mut resp = Response::new()
+            .add_attribute("method", "resolve_dispute")
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("task_queue_contract", task_queue_contract)
+            .add_attribute("upheld", upheld.to_string());
+
+        if upheld {
+            // the bond is returned, and the stake slashed from the backers is paid to the
+            // challenger as it comes back from each operator contract
+            resp = resp.add_message(BankMsg::Send {
+                to_address: dispute.challenger.to_string(),
+                amount: vec![dispute.bond],
+            });
+            let operator_sets = config.operator_sets();
+            for operator in pending.backers.iter() {
+                noop_slash_validator(&mut deps, operator)?;
+                if dispute_config.slash_amount.is_zero() {
+                    continue;
+                }
+                // in cross-check mode the operator is slashed in the set it voted in
+                let set = VOTE_SETS
+                    .may_load(deps.storage, (&task_queue, task_id, operator))?
+                    .unwrap_or_default();
+                resp = resp.add_submessage(slash_submsg(
+                    operator_sets[set as usize],
+                    operator,
+                    dispute_config.slash_amount,
+                    &dispute.challenger,
+                )?);
+            }
 
+            let price = price.ok_or(ContractError::MissingCorrectedPrice)?;
             resp = resp
-                .add_message(msg)
-                .add_attribute("new_price", median.to_string());
+                .add_submessage(complete_task_submsg(&task_queue, task_id, price)?)
+                .add_attribute("new_price", price.to_string());
         } else {
-            resp = resp.add_attribute("status", "threshold_not_met");
+            // the forfeited bond is burned rather than paid to anyone involved in the dispute
+            resp = resp
+                .add_message(BankMsg::Burn {
+                    amount: vec![dispute.bond],
+                })
+                .add_submessage(complete_task_submsg(&task_queue, task_id, pending.price)?)
+                .add_attribute("new_price", pending.price.to_string());
         }
 
-        resp = resp
+        Ok(resp)
+    }
+
+    pub fn expire_dispute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let pending = PENDING
+            .may_load(deps.storage, (&task_queue, task_id))?
+            .ok_or(ContractError::NoPendingResult)?;
+        let dispute = pending.dispute.ok_or(ContractError::NotDisputed)?;
+        if env.block.time.seconds() < dispute.resolve_by {
+            return Err(ContractError::ResolutionPeriodOpen(dispute.resolve_by));
+        }
+        // a contested result is not sent without a decision, the task will time out instead
+        PENDING.remove(deps.storage, (&task_queue, task_id));
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: dispute.challenger.to_string(),
+                amount: vec![dispute.bond],
+            })
+            .add_attribute("method", "expire_dispute")
             .add_attribute("task_id", task_id.to_string())
-            .add_attribute("task_queue_contract", task_queue_contract);
+            .add_attribute("task_queue_contract", task_queue_contract)
+            .add_attribute("challenger", dispute.challenger))
+    }
 
-        // NOTE: If we ever want to optimize the storage we can remove the votes for the completed
-        // tasks.
+    /// Slashes an operator through its operator contract, the reply pays the challenger
+    fn slash_submsg(
+        operator_contract: &Addr,
+        operator: &Addr,
+        amount: Uint128,
+        challenger: &Addr,
+    ) -> Result<SubMsg, ContractError> {
+        let msg = WasmMsg::Execute {
+            contract_addr: operator_contract.to_string(),
+            msg: to_json_binary(&SlashExecuteMsg::Slash {
+                operator: operator.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_always(msg, SLASH_REPLY_ID).with_payload(to_json_binary(challenger)?))
+    }
 
-        Ok(resp)
+    /// Completes the task without reverting if the task queue rejects it, so the pending
+    /// result and the bond are settled either way
+    fn complete_task_submsg(
+        task_queue: &Addr,
+        task_id: TaskId,
+        price: Decimal256,
+    ) -> Result<SubMsg, ContractError> {
+        let msg = complete_task_msg(task_queue, task_id, price)?;
+        Ok(SubMsg::reply_on_error(msg, COMPLETE_REPLY_ID).with_payload(to_json_binary(&task_id)?))
+    }
+
+    fn complete_task_msg(
+        task_queue: &Addr,
+        task_id: TaskId,
+        price: Decimal256,
+    ) -> Result<WasmMsg, ContractError> {
+        Ok(WasmMsg::Execute {
+            contract_addr: task_queue.to_string(),
+            msg: to_json_binary(&TaskExecuteMsg::Complete {
+                task_id,
+                response: price_response(price),
+            })?,
+            funds: vec![],
+        })
+    }
+
+    /// The result sent to the task queue
+    fn price_response(price: Decimal256) -> serde_json::Value {
+        serde_json::json!(crate::state::PriceResult {
+            price: price.to_string()
+        })
+    }
+
+    fn voting_power_at(
+        deps: Deps,
+        operator_contract: &Addr,
+        operator: &Addr,
+        height: u64,
+    ) -> StdResult<Uint128> {
+        let res: VotingPowerResponse = deps.querier.query_wasm_smart(
+            operator_contract.to_string(),
+            &VotingQueryMsg::VotingPowerAtHeight {
+                address: operator.to_string(),
+                height: Some(height),
+            },
+        )?;
+        Ok(res.power)
     }
 
-    pub(crate) fn calculate_median(values: &mut [Decimal]) -> Decimal {
+    fn total_power_at(deps: Deps, operator_contract: &Addr, height: u64) -> StdResult<Uint128> {
+        let res: TotalPowerResponse = deps.querier.query_wasm_smart(
+            operator_contract.to_string(),
+            &VotingQueryMsg::TotalPowerAtHeight {
+                height: Some(height),
+            },
+        )?;
+        Ok(res.power)
+    }
+
+    pub(crate) fn calculate_median(values: &mut [Decimal256]) -> Result<Decimal256, ContractError> {
         if values.is_empty() {
-            return Decimal::zero();
+            return Ok(Decimal256::zero());
         }
 
         values.sort();
 
         if values.len() % 2 == 0 {
-            // first half                 + // second half              // divided by 2
-            (values[values.len() / 2 - 1] + values[values.len() / 2]) / Uint128::new(2u128)
+            // halfway between the two middle values, which unlike adding them up cannot overflow
+            let lower = values[values.len() / 2 - 1];
+            let upper = values[values.len() / 2];
+            let half_difference = upper
+                .checked_sub(lower)?
+                .checked_mul(Decimal256::percent(50))?;
+            Ok(lower.checked_add(half_difference)?)
         } else {
             // take the middle value
-            values[values.len() / 2]
+            Ok(values[values.len() / 2])
         }
     }
 
-    pub(crate) fn calculate_allowed_range(median: Decimal, spread: Decimal) -> (Decimal, Decimal) {
-        let allowed_minimum = median * (Decimal::one() - spread);
-        let allowed_maximum = median * (Decimal::one() + spread);
-        (allowed_minimum, allowed_maximum)
+    pub(crate) fn calculate_allowed_range(
+        median: Decimal256,
+        spread: Decimal,
+    ) -> Result<(Decimal256, Decimal256), ContractError> {
+        let spread = Decimal256::from(spread);
+        let allowed_minimum = median.checked_mul(Decimal256::one().checked_sub(spread)?)?;
+        let allowed_maximum = median.checked_mul(Decimal256::one().checked_add(spread)?)?;
+        Ok((allowed_minimum, allowed_maximum))
+    }
+
+    /// Sums up the voting power, erroring instead of panicking on overflow
+    pub(crate) fn sum_power<'a>(
+        votes: impl IntoIterator<Item = &'a OperatorVote>,
+    ) -> Result<Uint128, ContractError> {
+        votes
+            .into_iter()
+            .try_fold(Uint128::zero(), |total, vote| -> Result<_, ContractError> {
+                Ok(total.checked_add(vote.power)?)
+            })
     }
 
     pub(crate) fn filter_valid_votes(
         votes: &[(Addr, OperatorVote)],
-        allowed_minimum_price: Decimal,
-        allowed_maximum_price: Decimal,
+        allowed_minimum_price: Decimal256,
+        allowed_maximum_price: Decimal256,
     ) -> Vec<&(Addr, OperatorVote)> {
         votes
             .iter()
@@ -256,15 +952,15 @@ mod execute {
         valid_power: Uint128,
         total_power: Uint128,
         threshold_percent: Decimal,
-    ) -> bool {
-        let valid_ratio = Decimal::from_ratio(valid_power, total_power);
-        valid_ratio >= threshold_percent
+    ) -> Result<bool, ContractError> {
+        let valid_ratio = Decimal::checked_from_ratio(valid_power, total_power)?;
+        Ok(valid_ratio >= threshold_percent)
     }
 
     pub(crate) fn identify_slashable_operators(
         votes: &[(Addr, OperatorVote)],
-        slashable_minimum: Decimal,
-        slashable_maximum: Decimal,
+        slashable_minimum: Decimal256,
+        slashable_maximum: Decimal256,
     ) -> Vec<Addr> {
         votes
             .iter()
@@ -289,22 +985,23 @@ mod execute {
         votes: &[(Addr, OperatorVote)],
         total_power: Uint128,
         config: &Config,
-    ) -> Result<(Decimal, Vec<Addr>, bool), ContractError> {
-        let mut all_prices: Vec<Decimal> = votes.iter().map(|(_, vote)| vote.result).collect();
+    ) -> Result<(Decimal256, Vec<Addr>, bool), ContractError> {
+        let mut all_prices: Vec<Decimal256> = votes.iter().map(|(_, vote)| vote.result).collect();
 
-        let median = calculate_median(&mut all_prices);
+        let median = calculate_median(&mut all_prices)?;
 
         let (allowed_minimum, allowed_maximum) =
-            calculate_allowed_range(median, config.allowed_spread);
+            calculate_allowed_range(median, config.allowed_spread)?;
 
         let valid_votes = filter_valid_votes(votes, allowed_minimum, allowed_maximum);
 
-        let valid_power: Uint128 = valid_votes.iter().map(|(_, vote)| vote.power).sum();
+        let valid_power = sum_power(valid_votes.iter().map(|(_, vote)| vote))?;
 
-        let is_threshold_met = is_threshold_met(valid_power, total_power, config.threshold_percent);
+        let is_threshold_met =
+            is_threshold_met(valid_power, total_power, config.threshold_percent)?;
 
         let (slashable_minimum, slashable_maximum) =
-            calculate_allowed_range(median, config.slashable_spread);
+            calculate_allowed_range(median, config.slashable_spread)?;
 
         let slashable_operators =
             identify_slashable_operators(votes, slashable_minimum, slashable_maximum);
@@ -320,10 +1017,37 @@ mod query {
         verifier_simple::{TaskInfoResponse, TaskTally},
     };
 
-    use crate::state::{OPTIONS, TASKS};
+    use crate::msg::{CrossCheckResponse, OperatorSetResult};
+    use crate::state::{CROSS_CHECKS, OPTIONS, SET_RESULTS, TASKS};
 
     use super::*;
 
+    pub fn cross_check(
+        deps: Deps,
+        task_contract: String,
+        task_id: TaskId,
+    ) -> StdResult<CrossCheckResponse> {
+        let task_contract = deps.api.addr_validate(&task_contract)?;
+        let config = CONFIG.load(deps.storage)?;
+        let operator_sets = config.operator_sets();
+
+        let results = SET_RESULTS
+            .prefix((&task_contract, task_id))
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|r| {
+                r.map(|(set, price)| OperatorSetResult {
+                    operator_contract: operator_sets[set as usize].clone(),
+                    price,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(CrossCheckResponse {
+            status: CROSS_CHECKS.may_load(deps.storage, (&task_contract, task_id))?,
+            results,
+        })
+    }
+
     pub(crate) fn query_operator_vote(
         deps: Deps,
         task_contract: String,
@@ -357,9 +1081,10 @@ mod query {
             };
             // Collect the running tallies on the options
             let tallies: Result<Vec<_>, _> = OPTIONS
+                .prefix((&task_contract, task_id))
                 .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
                 .map(|r| {
-                    r.map(|((_, _, result), v)| TaskTally {
+                    r.map(|(result, v)| TaskTally {
                         result,
                         power: v.power,
                     })
@@ -382,10 +1107,10 @@ mod tests {
     use crate::state::OperatorVote;
 
     use super::*;
-    use cosmwasm_std::{Decimal, Uint128};
+    use cosmwasm_std::{Decimal, Decimal256, Uint128};
     use execute::{
         calculate_allowed_range, calculate_median, filter_valid_votes,
-        identify_slashable_operators, is_threshold_met, process_votes,
+        identify_slashable_operators, is_threshold_met, process_votes, sum_power,
     };
 
     mod calculate_median {
@@ -394,116 +1119,136 @@ mod tests {
 
         #[test]
         fn calculate_median_odd_length() {
-            let mut values = vec![Decimal::one(), Decimal::percent(300), Decimal::percent(500)];
-            let median = calculate_median(&mut values);
+            let mut values = vec![
+                Decimal256::one(),
+                Decimal256::percent(300),
+                Decimal256::percent(500),
+            ];
+            let median = calculate_median(&mut values).unwrap();
             // we have 1, 3 and 5, so median should be 3
-            assert_eq!(median, Decimal::percent(300));
+            assert_eq!(median, Decimal256::percent(300));
         }
 
         #[test]
         fn calculate_median_even_length() {
             let mut values = vec![
-                Decimal::one(),
-                Decimal::percent(300),
-                Decimal::percent(500),
-                Decimal::percent(700),
+                Decimal256::one(),
+                Decimal256::percent(300),
+                Decimal256::percent(500),
+                Decimal256::percent(700),
             ];
-            let median = calculate_median(&mut values);
+            let median = calculate_median(&mut values).unwrap();
             // this time we have 1, 3, 5 and 7 so median should be (3 + 5) / 2 = 4
-            assert_eq!(median, Decimal::percent(400));
+            assert_eq!(median, Decimal256::percent(400));
         }
 
         #[test]
         fn calculate_median_unsorted() {
-            let mut values = vec![Decimal::percent(500), Decimal::one(), Decimal::percent(300)];
-            let median = calculate_median(&mut values);
+            let mut values = vec![
+                Decimal256::percent(500),
+                Decimal256::one(),
+                Decimal256::percent(300),
+            ];
+            let median = calculate_median(&mut values).unwrap();
             // same as `calculate_median_odd_length` but unsorted
-            assert_eq!(median, Decimal::percent(300));
+            assert_eq!(median, Decimal256::percent(300));
         }
 
         #[test]
         fn calculate_median_single_element() {
-            let mut values = vec![Decimal::percent(42)];
-            let median = calculate_median(&mut values);
-            assert_eq!(median, Decimal::percent(42));
+            let mut values = vec![Decimal256::percent(42)];
+            let median = calculate_median(&mut values).unwrap();
+            assert_eq!(median, Decimal256::percent(42));
         }
 
         #[test]
         fn calculate_median_fractional_values_odd() {
             let mut values = vec![
-                Decimal::percent(11),
-                Decimal::percent(12),
-                Decimal::percent(13),
+                Decimal256::percent(11),
+                Decimal256::percent(12),
+                Decimal256::percent(13),
             ];
-            let median = calculate_median(&mut values);
+            let median = calculate_median(&mut values).unwrap();
             // median should be 0.12
-            assert_eq!(median, Decimal::percent(12));
+            assert_eq!(median, Decimal256::percent(12));
         }
 
         #[test]
         fn calculate_median_fractional_values_even() {
             let mut values = vec![
-                Decimal::percent(110),
-                Decimal::percent(120),
-                Decimal::percent(130),
-                Decimal::percent(140),
+                Decimal256::percent(110),
+                Decimal256::percent(120),
+                Decimal256::percent(130),
+                Decimal256::percent(140),
             ];
-            let median = calculate_median(&mut values);
+            let median = calculate_median(&mut values).unwrap();
             // (1.2 + 1.3) / 2 = 1.25
-            assert_eq!(median, Decimal::percent(125));
+            assert_eq!(median, Decimal256::percent(125));
         }
 
         #[test]
         fn calculate_median_identical_values() {
             let mut values = vec![
-                Decimal::percent(500),
-                Decimal::percent(500),
-                Decimal::percent(500),
-                Decimal::percent(500),
+                Decimal256::percent(500),
+                Decimal256::percent(500),
+                Decimal256::percent(500),
+                Decimal256::percent(500),
             ];
-            let median = calculate_median(&mut values);
-            assert_eq!(median, Decimal::percent(500));
+            let median = calculate_median(&mut values).unwrap();
+            assert_eq!(median, Decimal256::percent(500));
         }
 
         #[test]
         fn calculate_median_large_numbers() {
             let mut values = vec![
-                Decimal::percent(1_000_000_000_000u64),
-                Decimal::percent(2_000_000_000_000u64),
-                Decimal::percent(3_000_000_000_000u64),
+                Decimal256::percent(1_000_000_000_000u64),
+                Decimal256::percent(2_000_000_000_000u64),
+                Decimal256::percent(3_000_000_000_000u64),
             ];
-            let median = calculate_median(&mut values);
-            assert_eq!(median, Decimal::percent(2_000_000_000_000u64));
+            let median = calculate_median(&mut values).unwrap();
+            assert_eq!(median, Decimal256::percent(2_000_000_000_000u64));
         }
 
         #[test]
         fn calculate_median_of_fib_unsorted() {
             let mut values = vec![
-                Decimal::percent(340),
-                Decimal::percent(20),
-                Decimal::percent(550),
-                Decimal::percent(50),
-                Decimal::percent(80),
-                Decimal::percent(130),
-                Decimal::percent(30),
-                Decimal::percent(210),
-                Decimal::percent(1440),
-                Decimal::percent(10),
-                Decimal::percent(890),
-                Decimal::percent(80),
+                Decimal256::percent(340),
+                Decimal256::percent(20),
+                Decimal256::percent(550),
+                Decimal256::percent(50),
+                Decimal256::percent(80),
+                Decimal256::percent(130),
+                Decimal256::percent(30),
+                Decimal256::percent(210),
+                Decimal256::percent(1440),
+                Decimal256::percent(10),
+                Decimal256::percent(890),
+                Decimal256::percent(80),
             ];
 
             // This will be sorted to:
             // 0.10, 0.20, 0.30, 0.50, 0.80, 0.80, 1.30, 2.10, 3.40, 5.50, 8.90, 14.40
-            let median = calculate_median(&mut values);
-            assert_eq!(median, Decimal::percent(105)) // 1.05
+            let median = calculate_median(&mut values).unwrap();
+            assert_eq!(median, Decimal256::percent(105)) // 1.05
+        }
+
+        #[test]
+        fn calculate_median_extreme_values() {
+            // adding these two up would overflow
+            let mut values = vec![Decimal256::MAX, Decimal256::MAX - Decimal256::raw(2)];
+            let median = calculate_median(&mut values).unwrap();
+            assert_eq!(median, Decimal256::MAX - Decimal256::raw(1));
+
+            let mut values = vec![Decimal256::raw(1), Decimal256::raw(3)];
+            let median = calculate_median(&mut values).unwrap();
+            assert_eq!(median, Decimal256::raw(2));
         }
 
         #[test]
         fn calculate_median_empty() {
-            let mut values: Vec<Decimal> = vec![];
-            let median = calculate_median(&mut values);
-            assert_eq!(median, Decimal::zero())
+            let mut values: Vec<Decimal256> = vec![];
+            let median = calculate_median(&mut values).unwrap();
+            assert_eq!(median, Decimal256::zero())
         }
     }
 
@@ -512,24 +1257,26 @@ mod tests {
 
         #[test]
         fn calculate_allowed_range_normal() {
-            let median = Decimal::one();
+            let median = Decimal256::one();
             let spread = Decimal::percent(10);
 
-            let (allowed_minimum, allowed_maximum) = calculate_allowed_range(median, spread);
+            let (allowed_minimum, allowed_maximum) =
+                calculate_allowed_range(median, spread).unwrap();
 
             // allowed_minimum = 100 * (1 - 0.10) = 90
             // allowed_maximum = 100 * (1 + 0.10) = 110
 
-            assert_eq!(allowed_minimum, Decimal::percent(90));
-            assert_eq!(allowed_maximum, Decimal::percent(110));
+            assert_eq!(allowed_minimum, Decimal256::percent(90));
+            assert_eq!(allowed_maximum, Decimal256::percent(110));
         }
 
         #[test]
         fn calculate_allowed_range_zero_spread() {
-            let median = Decimal::one();
+            let median = Decimal256::one();
             let spread = Decimal::zero();
 
-            let (allowed_minimum, allowed_maximum) = calculate_allowed_range(median, spread);
+            let (allowed_minimum, allowed_maximum) =
+                calculate_allowed_range(median, spread).unwrap();
 
             // allowed_minimum = 100 * (1 - 0) = 100
             // allowed_maximum = 100 * (1 + 0) = 100
@@ -540,70 +1287,83 @@ mod tests {
 
         #[test]
         fn calculate_allowed_range_full_spread() {
-            let median = Decimal::one();
+            let median = Decimal256::one();
             let spread = Decimal::one();
 
-            let (allowed_minimum, allowed_maximum) = calculate_allowed_range(median, spread);
+            let (allowed_minimum, allowed_maximum) =
+                calculate_allowed_range(median, spread).unwrap();
 
             // allowed_minimum = 100 * (1 - 1) = 0
             // allowed_maximum = 100 * (1 + 1) = 200
 
-            assert_eq!(allowed_minimum, Decimal::zero());
-            assert_eq!(allowed_maximum, Decimal::percent(200));
+            assert_eq!(allowed_minimum, Decimal256::zero());
+            assert_eq!(allowed_maximum, Decimal256::percent(200));
         }
 
         #[test]
         fn calculate_allowed_range_zero_median() {
-            let median = Decimal::zero();
+            let median = Decimal256::zero();
             let spread = Decimal::percent(10);
 
-            let (allowed_minimum, allowed_maximum) = calculate_allowed_range(median, spread);
+            let (allowed_minimum, allowed_maximum) =
+                calculate_allowed_range(median, spread).unwrap();
 
-            assert_eq!(allowed_minimum, Decimal::zero());
-            assert_eq!(allowed_maximum, Decimal::zero());
+            assert_eq!(allowed_minimum, Decimal256::zero());
+            assert_eq!(allowed_maximum, Decimal256::zero());
         }
 
         #[test]
         fn calculate_allowed_range_fractional_median() {
-            let median = Decimal::percent(1500);
+            let median = Decimal256::percent(1500);
             let spread = Decimal::percent(10);
 
-            let (allowed_minimum, allowed_maximum) = calculate_allowed_range(median, spread);
+            let (allowed_minimum, allowed_maximum) =
+                calculate_allowed_range(median, spread).unwrap();
 
             // allowed_minimum = 15.0 * (1 - 0.10) = 13.5
             // allowed_maximum = 15.0 * (1 + 0.10) = 16.5
 
-            assert_eq!(allowed_minimum, Decimal::percent(1350)); // 13.5
-            assert_eq!(allowed_maximum, Decimal::percent(1650)); // 16.5
+            assert_eq!(allowed_minimum, Decimal256::percent(1350)); // 13.5
+            assert_eq!(allowed_maximum, Decimal256::percent(1650)); // 16.5
         }
 
         #[test]
         fn calculate_allowed_range_fractional_spread() {
-            let median = Decimal::one();
+            let median = Decimal256::one();
             // 0.15 or %15
             let spread = Decimal::percent(15);
 
-            let (allowed_minimum, allowed_maximum) = calculate_allowed_range(median, spread);
+            let (allowed_minimum, allowed_maximum) =
+                calculate_allowed_range(median, spread).unwrap();
 
             // allowed_minimum = 100 * (1 - 0.15) = 85
             // allowed_maximum = 100 * (1 + 0.15) = 115
 
-            assert_eq!(allowed_minimum, Decimal::percent(85));
-            assert_eq!(allowed_maximum, Decimal::percent(115));
+            assert_eq!(allowed_minimum, Decimal256::percent(85));
+            assert_eq!(allowed_maximum, Decimal256::percent(115));
+        }
+
+        #[test]
+        fn calculate_allowed_range_overflow() {
+            let median = Decimal256::MAX;
+            let spread = Decimal::percent(10);
+
+            calculate_allowed_range(median, spread).unwrap_err();
         }
 
         #[test]
         fn calculate_allowed_range_large_numbers() {
-            let median = Decimal::percent(1_000_000_000_000u64);
+            let median = Decimal256::percent(1_000_000_000_000u64);
             let spread = Decimal::percent(10);
 
-            let (allowed_minimum, allowed_maximum) = calculate_allowed_range(median, spread);
+            let (allowed_minimum, allowed_maximum) =
+                calculate_allowed_range(median, spread).unwrap();
 
             // allowed_minimum = 1,000,000,000,000 * (1 - 0.1) = 900,000,000,000
             // allowed_maximum = 1,000,000,000,000 * (1 + 0.1) = 1,100,000,000,000
 
-            assert_eq!(allowed_minimum, Decimal::percent(900_000_000_000u64));
-            assert_eq!(allowed_maximum, Decimal::percent(1_100_000_000_000u64));
+            assert_eq!(allowed_minimum, Decimal256::percent(900_000_000_000u64));
+            assert_eq!(allowed_maximum, Decimal256::percent(1_100_000_000_000u64));
         }
     }
 
@@ -618,15 +1378,15 @@ mod tests {
 
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
-                result: Decimal::percent(150),
+                result: Decimal256::percent(150),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
-                result: Decimal::percent(200),
+                result: Decimal256::percent(200),
             };
             let vote3 = OperatorVote {
                 power: Uint128::new(300),
-                result: Decimal::percent(250),
+                result: Decimal256::percent(250),
             };
 
             let votes = vec![
@@ -636,8 +1396,8 @@ mod tests {
             ];
 
             // Allowed ranges
-            let min_price = Decimal::percent(150);
-            let max_price = Decimal::percent(250);
+            let min_price = Decimal256::percent(150);
+            let max_price = Decimal256::percent(250);
 
             let result = filter_valid_votes(&votes, min_price, max_price);
 
@@ -648,7 +1408,7 @@ mod tests {
                     op1,
                     OperatorVote {
                         power: Uint128::new(100),
-                        result: Decimal::percent(150)
+                        result: Decimal256::percent(150)
                     }
                 )
             );
@@ -658,7 +1418,7 @@ mod tests {
                     op2,
                     OperatorVote {
                         power: Uint128::new(200),
-                        result: Decimal::percent(200)
+                        result: Decimal256::percent(200)
                     }
                 )
             );
@@ -668,7 +1428,7 @@ mod tests {
                     op3,
                     OperatorVote {
                         power: Uint128::new(300),
-                        result: Decimal::percent(250)
+                        result: Decimal256::percent(250)
                     }
                 )
             );
@@ -682,15 +1442,15 @@ mod tests {
 
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
-                result: Decimal::one(),
+                result: Decimal256::one(),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
-                result: Decimal::percent(200),
+                result: Decimal256::percent(200),
             };
             let vote3 = OperatorVote {
                 power: Uint128::new(300),
-                result: Decimal::percent(300),
+                result: Decimal256::percent(300),
             };
 
             let votes = vec![
@@ -699,8 +1459,8 @@ mod tests {
                 (op3.clone(), vote3),
             ];
 
-            let min_price = Decimal::percent(150);
-            let max_price = Decimal::percent(250);
+            let min_price = Decimal256::percent(150);
+            let max_price = Decimal256::percent(250);
 
             let result = filter_valid_votes(&votes, min_price, max_price);
 
@@ -711,7 +1471,7 @@ mod tests {
                     op2,
                     OperatorVote {
                         power: Uint128::new(200),
-                        result: Decimal::percent(200)
+                        result: Decimal256::percent(200)
                     }
                 )
             );
@@ -725,15 +1485,15 @@ mod tests {
 
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
-                result: Decimal::percent(50),
+                result: Decimal256::percent(50),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
-                result: Decimal::percent(400),
+                result: Decimal256::percent(400),
             };
             let vote3 = OperatorVote {
                 power: Uint128::new(300),
-                result: Decimal::percent(500),
+                result: Decimal256::percent(500),
             };
 
             let votes = vec![
@@ -742,8 +1502,8 @@ mod tests {
                 (op3.clone(), vote3),
             ];
 
-            let min_price = Decimal::percent(150);
-            let max_price = Decimal::percent(250);
+            let min_price = Decimal256::percent(150);
+            let max_price = Decimal256::percent(250);
 
             let result = filter_valid_votes(&votes, min_price, max_price);
 
@@ -757,17 +1517,17 @@ mod tests {
 
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
-                result: Decimal::percent(150),
+                result: Decimal256::percent(150),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
-                result: Decimal::percent(250),
+                result: Decimal256::percent(250),
             };
 
             let votes = vec![(op1.clone(), vote1), (op2.clone(), vote2)];
 
-            let min_price = Decimal::percent(150);
-            let max_price = Decimal::percent(250);
+            let min_price = Decimal256::percent(150);
+            let max_price = Decimal256::percent(250);
 
             let result = filter_valid_votes(&votes, min_price, max_price);
 
@@ -778,7 +1538,7 @@ mod tests {
                     op1,
                     OperatorVote {
                         power: Uint128::new(100),
-                        result: Decimal::percent(150)
+                        result: Decimal256::percent(150)
                     }
                 )
             );
@@ -788,7 +1548,7 @@ mod tests {
                     op2,
                     OperatorVote {
                         power: Uint128::new(200),
-                        result: Decimal::percent(250)
+                        result: Decimal256::percent(250)
                     }
                 )
             );
@@ -804,7 +1564,7 @@ mod tests {
             let total_power = Uint128::new(100);
             let threshold_percent = Decimal::percent(50);
 
-            let result = is_threshold_met(valid_power, total_power, threshold_percent);
+            let result = is_threshold_met(valid_power, total_power, threshold_percent).unwrap();
             assert!(
                 result,
                 "threshold should be met when valid is %50 of total power"
@@ -817,7 +1577,7 @@ mod tests {
             let total_power = Uint128::new(100);
             let threshold_percent = Decimal::percent(50);
 
-            let result = is_threshold_met(valid_power, total_power, threshold_percent);
+            let result = is_threshold_met(valid_power, total_power, threshold_percent).unwrap();
             assert!(!result, "threshold should be not met when not enough power");
         }
 
@@ -827,7 +1587,7 @@ mod tests {
             let total_power = Uint128::new(100);
             let threshold_percent = Decimal::percent(50);
 
-            let result = is_threshold_met(valid_power, total_power, threshold_percent);
+            let result = is_threshold_met(valid_power, total_power, threshold_percent).unwrap();
             assert!(result, "should return true when threshold met over %50");
         }
 
@@ -837,7 +1597,7 @@ mod tests {
             let total_power = Uint128::new(100);
             let threshold_percent = Decimal::one();
 
-            let result = is_threshold_met(valid_power, total_power, threshold_percent);
+            let result = is_threshold_met(valid_power, total_power, threshold_percent).unwrap();
             assert!(
                 result,
                 "should return true when valid power is equal total power"
@@ -850,12 +1610,21 @@ mod tests {
             let total_power = Uint128::new(100);
             let threshold_percent = Decimal::percent(1);
 
-            let result = is_threshold_met(valid_power, total_power, threshold_percent);
+            let result = is_threshold_met(valid_power, total_power, threshold_percent).unwrap();
             assert!(
                 result,
                 "should return true when valid power is over the threshold"
             );
         }
+
+        #[test]
+        fn threshold_zero_total_power() {
+            let valid_power = Uint128::zero();
+            let total_power = Uint128::zero();
+            let threshold_percent = Decimal::percent(50);
+
+            is_threshold_met(valid_power, total_power, threshold_percent).unwrap_err();
+        }
     }
 
     mod identify_slashable_operators {
@@ -869,15 +1638,15 @@ mod tests {
 
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
-                result: Decimal::percent(150),
+                result: Decimal256::percent(150),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
-                result: Decimal::percent(200),
+                result: Decimal256::percent(200),
             };
             let vote3 = OperatorVote {
                 power: Uint128::new(300),
-                result: Decimal::percent(250),
+                result: Decimal256::percent(250),
             };
 
             let votes = vec![
@@ -886,8 +1655,8 @@ mod tests {
                 (op3.clone(), vote3),
             ];
 
-            let slashable_minimum = Decimal::percent(150);
-            let slashable_maximum = Decimal::percent(250);
+            let slashable_minimum = Decimal256::percent(150);
+            let slashable_maximum = Decimal256::percent(250);
 
             let result = identify_slashable_operators(&votes, slashable_minimum, slashable_maximum);
             assert_eq!(result.len(), 0, "there should be no slashable operators");
@@ -901,15 +1670,15 @@ mod tests {
 
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
-                result: Decimal::one(),
+                result: Decimal256::one(),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
-                result: Decimal::percent(200),
+                result: Decimal256::percent(200),
             };
             let vote3 = OperatorVote {
                 power: Uint128::new(300),
-                result: Decimal::percent(300),
+                result: Decimal256::percent(300),
             };
 
             let votes = vec![
@@ -918,8 +1687,8 @@ mod tests {
                 (op3.clone(), vote3),
             ];
 
-            let slashable_minimum = Decimal::percent(150);
-            let slashable_maximum = Decimal::percent(250);
+            let slashable_minimum = Decimal256::percent(150);
+            let slashable_maximum = Decimal256::percent(250);
 
             let result = identify_slashable_operators(&votes, slashable_minimum, slashable_maximum);
             assert_eq!(result.len(), 2, "we must have 2 slashable operators");
@@ -934,15 +1703,15 @@ mod tests {
 
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
-                result: Decimal::percent(50),
+                result: Decimal256::percent(50),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
-                result: Decimal::percent(300),
+                result: Decimal256::percent(300),
             };
             let vote3 = OperatorVote {
                 power: Uint128::new(300),
-                result: Decimal::percent(400),
+                result: Decimal256::percent(400),
             };
 
             let votes = vec![
@@ -951,8 +1720,8 @@ mod tests {
                 (op3.clone(), vote3),
             ];
 
-            let slashable_minimum = Decimal::percent(150);
-            let slashable_maximum = Decimal::percent(250);
+            let slashable_minimum = Decimal256::percent(150);
+            let slashable_maximum = Decimal256::percent(250);
 
             let result = identify_slashable_operators(&votes, slashable_minimum, slashable_maximum);
             assert_eq!(result.len(), 3, "all operators should be slashed");
@@ -967,18 +1736,18 @@ mod tests {
             let vote1 = OperatorVote {
                 power: Uint128::new(100),
                 // low blound
-                result: Decimal::percent(150),
+                result: Decimal256::percent(150),
             };
             let vote2 = OperatorVote {
                 power: Uint128::new(200),
                 // upper bound
-                result: Decimal::percent(250),
+                result: Decimal256::percent(250),
             };
 
             let votes = vec![(op1.clone(), vote1), (op2.clone(), vote2)];
 
-            let slashable_minimum = Decimal::percent(150);
-            let slashable_maximum = Decimal::percent(250);
+            let slashable_minimum = Decimal256::percent(150);
+            let slashable_maximum = Decimal256::percent(250);
 
             let result = identify_slashable_operators(&votes, slashable_minimum, slashable_maximum);
             assert_eq!(result.len(), 0, "operators shouldn't be slashed");
@@ -988,8 +1757,8 @@ mod tests {
         fn empty_votes() {
             let votes: Vec<(Addr, OperatorVote)> = vec![];
 
-            let slashable_minimum = Decimal::percent(150);
-            let slashable_maximum = Decimal::percent(250);
+            let slashable_minimum = Decimal256::percent(150);
+            let slashable_maximum = Decimal256::percent(250);
 
             let result = identify_slashable_operators(&votes, slashable_minimum, slashable_maximum);
             assert_eq!(result.len(), 0, "there should be none from an empty list");
@@ -1009,14 +1778,14 @@ mod tests {
                     op1.clone(),
                     OperatorVote {
                         power: Uint128::new(100),
-                        result: Decimal::one(),
+                        result: Decimal256::one(),
                     },
                 ),
                 (
                     op2.clone(),
                     OperatorVote {
                         power: Uint128::new(100),
-                        result: Decimal::one(),
+                        result: Decimal256::one(),
                     },
                 ),
             ];
@@ -1027,12 +1796,14 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                cross_check: None,
+                dispute: None,
             };
 
             // mocking the power
             let result = process_votes(&votes, Uint128::new(100), &config).unwrap();
 
-            let expected_median = Decimal::one();
+            let expected_median = Decimal256::one();
             let expected_slashable_operators: Vec<Addr> = vec![];
             let expected_is_threshold_met = true;
 
@@ -1051,14 +1822,14 @@ mod tests {
                     op1.clone(),
                     OperatorVote {
                         power: Uint128::new(20),
-                        result: Decimal::one(),
+                        result: Decimal256::one(),
                     },
                 ),
                 (
                     op2.clone(),
                     OperatorVote {
                         power: Uint128::new(90),
-                        result: Decimal::percent(300),
+                        result: Decimal256::percent(300),
                     },
                 ),
             ];
@@ -1069,12 +1840,14 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                cross_check: None,
+                dispute: None,
             };
 
             // mocking the power
             let result = process_votes(&votes, Uint128::new(100), &config).unwrap();
 
-            let expected_median = Decimal::percent(200);
+            let expected_median = Decimal256::percent(200);
             let expected_slashable_operators = vec![op1.clone(), op2.clone()];
             let expected_is_threshold_met = false;
 
@@ -1094,21 +1867,21 @@ mod tests {
                     op1.clone(),
                     OperatorVote {
                         power: Uint128::new(50),
-                        result: Decimal::percent(150),
+                        result: Decimal256::percent(150),
                     },
                 ),
                 (
                     op2.clone(),
                     OperatorVote {
                         power: Uint128::new(50),
-                        result: Decimal::percent(200),
+                        result: Decimal256::percent(200),
                     },
                 ),
                 (
                     op3.clone(),
                     OperatorVote {
                         power: Uint128::new(50),
-                        result: Decimal::percent(350),
+                        result: Decimal256::percent(350),
                     },
                 ),
             ];
@@ -1119,12 +1892,14 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                cross_check: None,
+                dispute: None,
             };
 
             // mocking the power
             let result = process_votes(&votes, Uint128::new(100), &config).unwrap();
 
-            let expected_median = Decimal::percent(200);
+            let expected_median = Decimal256::percent(200);
             let expected_slashable_operators = vec![op1.clone(), op3.clone()];
             let expected_is_threshold_met = true;
 
@@ -1147,6 +1922,8 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                cross_check: None,
+                dispute: None,
             };
 
             // submitted are 100.00 and 102.00
@@ -1154,14 +1931,14 @@ mod tests {
                 (
                     operator1.clone(),
                     OperatorVote {
-                        result: Decimal::percent(10000),
+                        result: Decimal256::percent(10000),
                         power: Uint128::new(20),
                     },
                 ),
                 (
                     operator2.clone(),
                     OperatorVote {
-                        result: Decimal::percent(10200),
+                        result: Decimal256::percent(10200),
                         power: Uint128::new(20),
                     },
                 ),
@@ -1172,7 +1949,7 @@ mod tests {
 
             assert!(!is_threshold_met);
             // 101
-            assert_eq!(median, Decimal::percent(10100));
+            assert_eq!(median, Decimal256::percent(10100));
             assert_eq!(slashed_operators.len(), 0);
 
             let votes_with_op3 = vec![
@@ -1182,7 +1959,7 @@ mod tests {
                     operator3.clone(),
                     OperatorVote {
                         // 98
-                        result: Decimal::percent(9800),
+                        result: Decimal256::percent(9800),
                         power: Uint128::new(60),
                     },
                 ),
@@ -1193,7 +1970,7 @@ mod tests {
 
             assert!(is_threshold_met);
             // 100
-            assert_eq!(median, Decimal::percent(10000));
+            assert_eq!(median, Decimal256::percent(10000));
             assert_eq!(slashed_operators.len(), 0);
         }
 
@@ -1211,6 +1988,8 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                cross_check: None,
+                dispute: None,
             };
 
             // submited are 1.0 1.3 and 0.7
@@ -1218,21 +1997,21 @@ mod tests {
                 (
                     operator1.clone(),
                     OperatorVote {
-                        result: Decimal::percent(100),
+                        result: Decimal256::percent(100),
                         power: Uint128::new(50),
                     },
                 ),
                 (
                     operator2.clone(),
                     OperatorVote {
-                        result: Decimal::percent(130),
+                        result: Decimal256::percent(130),
                         power: Uint128::new(30),
                     },
                 ),
                 (
                     operator3.clone(),
                     OperatorVote {
-                        result: Decimal::percent(70),
+                        result: Decimal256::percent(70),
                         power: Uint128::new(20),
                     },
                 ),
@@ -1242,7 +2021,7 @@ mod tests {
                 process_votes(&votes, total_power, &config).unwrap();
 
             assert!(!is_threshold_met);
-            assert_eq!(median, Decimal::percent(100));
+            assert_eq!(median, Decimal256::percent(100));
             assert_eq!(slashed_operators.len(), 2);
         }
 
@@ -1260,6 +2039,8 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                cross_check: None,
+                dispute: None,
             };
 
             // submited are 1.0 1.05 and 1.5
@@ -1267,14 +2048,14 @@ mod tests {
                 (
                     operator1.clone(),
                     OperatorVote {
-                        result: Decimal::percent(100),
+                        result: Decimal256::percent(100),
                         power: Uint128::new(50),
                     },
                 ),
                 (
                     operator2.clone(),
                     OperatorVote {
-                        result: Decimal::percent(105),
+                        result: Decimal256::percent(105),
                         power: Uint128::new(30),
                     },
                 ),
@@ -1282,7 +2063,7 @@ mod tests {
                     operator3.clone(),
                     OperatorVote {
                         // Outlier
-                        result: Decimal::percent(150),
+                        result: Decimal256::percent(150),
                         power: Uint128::new(20),
                     },
                 ),
@@ -1292,7 +2073,7 @@ mod tests {
                 process_votes(&votes, total_power, &config).unwrap();
 
             assert!(is_threshold_met);
-            assert_eq!(median, Decimal::percent(105));
+            assert_eq!(median, Decimal256::percent(105));
             assert_eq!(slashed_operators, vec![operator3.clone()]);
         }
 
@@ -1310,6 +2091,8 @@ mod tests {
                 allowed_spread: Decimal::percent(50),
                 slashable_spread: Decimal::percent(60),
                 required_percentage: 70,
+                cross_check: None,
+                dispute: None,
             };
 
             // submitted are 1.0 1.1 and 1.2
@@ -1317,21 +2100,21 @@ mod tests {
                 (
                     operator1.clone(),
                     OperatorVote {
-                        result: Decimal::percent(100),
+                        result: Decimal256::percent(100),
                         power: Uint128::new(50),
                     },
                 ),
                 (
                     operator2.clone(),
                     OperatorVote {
-                        result: Decimal::percent(110),
+                        result: Decimal256::percent(110),
                         power: Uint128::new(30),
                     },
                 ),
                 (
                     operator3.clone(),
                     OperatorVote {
-                        result: Decimal::percent(120),
+                        result: Decimal256::percent(120),
                         power: Uint128::new(20),
                     },
                 ),
@@ -1340,9 +2123,161 @@ mod tests {
             let (median, slashed_operators, is_threshold_met) =
                 process_votes(&votes, total_power, &config).unwrap();
 
-            assert_eq!(median, Decimal::percent(110));
+            assert_eq!(median, Decimal256::percent(110));
             assert!(is_threshold_met);
             assert_eq!(slashed_operators.len(), 0);
         }
     }
+
+    mod process_votes_fuzz {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn config() -> Config {
+            Config {
+                operator_contract: Addr::unchecked("operator_contract"),
+                threshold_percent: Decimal::percent(50),
+                allowed_spread: Decimal::percent(10),
+                slashable_spread: Decimal::percent(20),
+                required_percentage: 70,
+                cross_check: None,
+                dispute: None,
+            }
+        }
+
+        /// Prices from tiny fractions up to values close to `Decimal256::MAX`
+        fn price() -> impl Strategy<Value = Decimal256> {
+            prop_oneof![
+                (1u128..1_000_000).prop_map(Decimal256::raw),
+                any::<u128>().prop_map(Decimal256::raw),
+                (0u128..1_000_000).prop_map(|offset| Decimal256::MAX - Decimal256::raw(offset)),
+            ]
+        }
+
+        fn votes() -> impl Strategy<Value = Vec<(Addr, OperatorVote)>> {
+            prop::collection::vec((price(), any::<u128>()), 1..10).prop_map(|votes| {
+                votes
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (result, power))| {
+                        (
+                            Addr::unchecked(format!("operator{i}")),
+                            OperatorVote {
+                                power: Uint128::new(power),
+                                result,
+                            },
+                        )
+                    })
+                    .collect()
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn never_panics(votes in votes(), total_power in any::<u128>()) {
+                let total_power = Uint128::new(total_power);
+                let result = process_votes(&votes, total_power, &config());
+
+                // the slashable range is the widest, it can't overflow if it fits the highest price
+                let max_price = votes.iter().map(|(_, vote)| vote.result).max().unwrap();
+                let range_fits = max_price.checked_mul(Decimal256::percent(120)).is_ok();
+                let power_fits = matches!(
+                    sum_power(votes.iter().map(|(_, vote)| vote)),
+                    Ok(power) if !total_power.is_zero() && power <= total_power
+                );
+                if range_fits && power_fits {
+                    prop_assert!(result.is_ok());
+                }
+                if total_power.is_zero() {
+                    prop_assert!(result.is_err());
+                }
+                if let Err(err) = result {
+                    prop_assert!(matches!(
+                        err,
+                        ContractError::Overflow(_) | ContractError::CheckedFromRatio(_)
+                    ));
+                }
+            }
+
+            #[test]
+            fn median_within_vote_range(votes in votes()) {
+                let mut prices: Vec<Decimal256> = votes.iter().map(|(_, vote)| vote.result).collect();
+                let median = calculate_median(&mut prices).unwrap();
+                prop_assert!(median >= prices[0]);
+                prop_assert!(median <= prices[prices.len() - 1]);
+            }
+
+            #[test]
+            fn honest_majority_never_slashed(
+                honest in prop::collection::vec((9_700u64..=10_300, any::<u64>()), 2..8),
+                colluders in prop::collection::vec((any::<u64>(), any::<u64>()), 0..8),
+            ) {
+                // the median is not weighted by power, so the majority is by count
+                let colluders = &colluders[..colluders.len().min(honest.len() - 1)];
+                let votes: Vec<(Addr, OperatorVote)> = honest
+                    .iter()
+                    .chain(colluders)
+                    .enumerate()
+                    .map(|(i, (price, power))| {
+                        (
+                            Addr::unchecked(format!("operator{i}")),
+                            OperatorVote {
+                                power: Uint128::from(*power),
+                                result: Decimal256::percent(*price),
+                            },
+                        )
+                    })
+                    .collect();
+
+                let (_, slashed, _) = process_votes(&votes, Uint128::MAX, &config()).unwrap();
+                for (operator, _) in &votes[..honest.len()] {
+                    prop_assert!(!slashed.contains(operator));
+                }
+            }
+
+            #[test]
+            fn identical_votes_agree(price in price(), powers in prop::collection::vec(1u64..u64::MAX, 1..10)) {
+                let votes: Vec<(Addr, OperatorVote)> = powers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, power)| {
+                        (
+                            Addr::unchecked(format!("operator{i}")),
+                            OperatorVote {
+                                power: Uint128::from(*power),
+                                result: price,
+                            },
+                        )
+                    })
+                    .collect();
+                let total_power = sum_power(votes.iter().map(|(_, vote)| vote)).unwrap();
+
+                let result = process_votes(&votes, total_power, &config());
+
+                // the slashable range overflows for prices close to the maximum
+                if price.checked_mul(Decimal256::percent(120)).is_ok() {
+                    let (median, slashed, is_threshold_met) = result.unwrap();
+                    prop_assert_eq!(median, price);
+                    prop_assert!(slashed.is_empty());
+                    prop_assert!(is_threshold_met);
+                } else {
+                    prop_assert!(matches!(result, Err(ContractError::Overflow(_))));
+                }
+            }
+
+            #[test]
+            fn votes_near_median_not_slashed(votes in votes()) {
+                let config = config();
+                if let Ok((median, slashed, _)) = process_votes(&votes, Uint128::MAX, &config) {
+                    let (minimum, maximum) =
+                        calculate_allowed_range(median, config.allowed_spread).unwrap();
+                    for (operator, vote) in &votes {
+                        if vote.result >= minimum && vote.result <= maximum {
+                            prop_assert!(!slashed.contains(operator));
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,5 +1,5 @@
-use cosmwasm_std::{Decimal, StdError};
-use cw_utils::PaymentError;
+use cosmwasm_std::{CheckedFromRatioError, Decimal, OverflowError, StdError};
+use cw_utils::{ParseReplyError, PaymentError};
 use lavs_helpers::verifier::VerifierError;
 use thiserror::Error;
 #[derive(Error, Debug)]
@@ -10,6 +10,15 @@ pub enum ContractError {
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    CheckedFromRatio(#[from] CheckedFromRatioError),
+
     #[error("{0}")]
     ConversionError(#[from] serde_json::Error),
 
@@ -42,4 +51,52 @@ pub enum ContractError {
 
     #[error("Invalid price provided")]
     InvalidPrice,
+
+    #[error("Cross-check operator contracts must be non-empty and distinct from each other and the primary set")]
+    InvalidOperatorSets,
+
+    #[error("Operator {0} has power in more than one operator set")]
+    OperatorInSeveralSets(String),
+
+    #[error("Operator set {0} already finalized this task")]
+    OperatorSetFinalized(u32),
+
+    #[error("Task disputed. Operator sets did not agree on a result")]
+    TaskDisputed,
+
+    #[error("Dispute and resolution periods must be greater than zero")]
+    InvalidDisputePeriod,
+
+    #[error("Disputes are not enabled on this verifier")]
+    DisputesDisabled,
+
+    #[error("No pending result for this task")]
+    NoPendingResult,
+
+    #[error("You need to bond at least {0} {1} to dispute a result")]
+    InsufficientBond(u128, String),
+
+    #[error("Dispute window is closed")]
+    DisputeWindowClosed,
+
+    #[error("Dispute window is open until {0}")]
+    DisputeWindowOpen(u64),
+
+    #[error("Result is already disputed")]
+    AlreadyDisputed,
+
+    #[error("Result is not disputed")]
+    NotDisputed,
+
+    #[error("The arbiter can resolve the dispute until {0}")]
+    ResolutionPeriodOpen(u64),
+
+    #[error("The operator contract didn't return the slashed stake")]
+    MissingSlashData,
+
+    #[error("A corrected price is required to uphold a dispute")]
+    MissingCorrectedPrice,
+
+    #[error("Unknown reply id {0}")]
+    UnknownReplyId(u64),
 }
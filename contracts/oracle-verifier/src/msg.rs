@@ -1,12 +1,12 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Uint128};
 use cw_orch::ExecuteFns;
 use lavs_apis::{
     id::TaskId,
     verifier_simple::{OperatorVoteInfoResponse, TaskInfoResponse},
 };
 
-use crate::state::Config;
+use crate::state::{Config, CrossCheckStatus, PendingResult};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -20,6 +20,35 @@ pub struct InstantiateMsg {
     pub slashable_spread: Decimal,
     /// The percentage of voting power needed to agree in order to complete a task
     pub required_percentage: u32,
+    /// Optionally require additional operator sets to independently agree on each result
+    pub cross_check: Option<CrossCheckMsg>,
+    /// Optionally hold finalized results for a dispute window before completing the task
+    pub dispute: Option<DisputeMsg>,
+}
+
+#[cw_serde]
+pub struct CrossCheckMsg {
+    /// Additional operator contracts, each of them must finalize the task on its own
+    pub operator_contracts: Vec<String>,
+    /// Maximum allowed difference between the results of the operator sets
+    pub tolerance: Decimal,
+}
+
+#[cw_serde]
+pub struct DisputeMsg {
+    /// How long a finalized result can be disputed, in seconds.
+    /// Tasks expiring before the window closes are completed right away
+    pub period: u64,
+    /// The address resolving disputes
+    pub arbiter: String,
+    /// The bond a challenger must send to dispute a result
+    pub bond: Coin,
+    /// Stake slashed from each operator backing an upheld result, paid to the challenger.
+    /// The operator contract must implement `SlashExecuteMsg`
+    pub slash_amount: Uint128,
+    /// How long the arbiter has to resolve a dispute before the challenger can be refunded,
+    /// in seconds
+    pub resolution_period: u64,
 }
 
 #[cw_serde]
@@ -35,6 +64,56 @@ pub enum ExecuteMsg {
         /// It is serialized to allow for easy comparison and to avoid field sorting issues when verifying signatures
         result: String,
     },
+    /// Challenge a pending result, the configured bond must be sent along
+    #[cw_orch(payable)]
+    Dispute {
+        task_queue_contract: String,
+        task_id: TaskId,
+        /// Anything supporting the claim, for the arbiter to review
+        evidence: String,
+    },
+    /// Send a pending result to the task queue once its dispute window passed.
+    /// Can be called by anyone.
+    Settle {
+        task_queue_contract: String,
+        task_id: TaskId,
+    },
+    /// Send several pending results to the task queue at once, in a single `CompleteBatch`.
+    /// The tasks the queue can't complete anymore are left out. Can be called by anyone.
+    SettleBatch {
+        task_queue_contract: String,
+        task_ids: Vec<TaskId>,
+    },
+    /// Arbiter decision on a disputed result
+    ResolveDispute {
+        task_queue_contract: String,
+        task_id: TaskId,
+        /// If true, the backing operators are slashed and the challenger is rewarded.
+        /// Otherwise the challenger loses the bond and the result is sent to the task queue.
+        upheld: bool,
+        /// The correct price to complete the task with, required if the dispute is upheld
+        price: Option<Decimal256>,
+    },
+    /// Refund the challenger of a dispute the arbiter didn't resolve in time, dropping the
+    /// result. Can be called by anyone.
+    ExpireDispute {
+        task_queue_contract: String,
+        task_id: TaskId,
+    },
+}
+
+/// Slashing interface of the operator contract, used when a dispute is upheld
+#[cw_serde]
+pub enum SlashExecuteMsg {
+    /// Takes up to `amount` of the operator's stake and sends it to the caller, returning
+    /// `SlashResponse` as data
+    Slash { operator: String, amount: Uint128 },
+}
+
+#[cw_serde]
+pub struct SlashResponse {
+    /// What was actually taken from the operator
+    pub slashed: Coin,
 }
 
 #[cw_serde]
@@ -63,4 +142,32 @@ pub enum QueryMsg {
     },
     #[returns(Vec<Addr>)]
     SlashableOperators {},
+    #[returns(Option<PendingResult>)]
+    Pending {
+        /// The task contract we are interested in
+        task_contract: String,
+        /// The ID of the task we are interested in
+        task_id: TaskId,
+    },
+    #[returns(CrossCheckResponse)]
+    CrossCheck {
+        /// The task contract we are interested in
+        task_contract: String,
+        /// The ID of the task we are interested in
+        task_id: TaskId,
+    },
+}
+
+#[cw_serde]
+pub struct CrossCheckResponse {
+    /// Set once every operator set has finalized the task
+    pub status: Option<CrossCheckStatus>,
+    /// The results finalized so far, one per operator set
+    pub results: Vec<OperatorSetResult>,
+}
+
+#[cw_serde]
+pub struct OperatorSetResult {
+    pub operator_contract: Addr,
+    pub price: Decimal256,
 }
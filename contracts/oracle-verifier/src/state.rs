@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, StdError, Storage, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, StdError, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
 use lavs_apis::{id::TaskId, verifier_simple::TaskMetadata};
 
@@ -10,6 +10,14 @@ pub const VOTES: Map<(&Addr, TaskId, &Addr), OperatorVote> = Map::new("operator_
 pub const TASKS: Map<(&Addr, TaskId), TaskMetadata> = Map::new("tasks");
 pub const OPTIONS: Map<(&Addr, TaskId, &str), TaskOption> = Map::new("task_options");
 pub const SLASHED_OPERATORS: Map<&Addr, bool> = Map::new("slashed_operators");
+/// Index of the operator set each vote was counted in (cross-check mode only)
+pub const VOTE_SETS: Map<(&Addr, TaskId, &Addr), u32> = Map::new("vote_sets");
+/// Median finalized by each operator set - indexed by (task_queue, task_id, operator_set)
+pub const SET_RESULTS: Map<(&Addr, TaskId, u32), Decimal256> = Map::new("set_results");
+/// Outcome of the cross-check once every operator set has finalized
+pub const CROSS_CHECKS: Map<(&Addr, TaskId), CrossCheckStatus> = Map::new("cross_checks");
+/// Finalized results waiting for their dispute window to pass
+pub const PENDING: Map<(&Addr, TaskId), PendingResult> = Map::new("pending_results");
 
 #[cw_serde]
 pub struct Config {
@@ -18,12 +26,79 @@ pub struct Config {
     pub allowed_spread: Decimal,
     pub slashable_spread: Decimal,
     pub required_percentage: u32,
+    pub cross_check: Option<CrossCheck>,
+    pub dispute: Option<DisputeConfig>,
+}
+
+impl Config {
+    /// All operator sets voting on a task, the primary `operator_contract` first.
+    /// The position in this list is the index used in `VOTE_SETS` and `SET_RESULTS`.
+    pub fn operator_sets(&self) -> Vec<&Addr> {
+        let mut sets = vec![&self.operator_contract];
+        if let Some(cross_check) = &self.cross_check {
+            sets.extend(cross_check.operator_contracts.iter());
+        }
+        sets
+    }
+}
+
+/// Requires independent operator sets to agree before a result is sent to the task queue
+#[cw_serde]
+pub struct CrossCheck {
+    /// Additional operator contracts, each finalizing the task on its own
+    pub operator_contracts: Vec<Addr>,
+    /// Maximum difference allowed between each set's median and the median of all sets
+    pub tolerance: Decimal,
+}
+
+#[cw_serde]
+pub enum CrossCheckStatus {
+    /// All operator sets agreed and the result was sent to the task queue
+    Completed,
+    /// The operator sets finalized results too far apart, the task is left open
+    Disputed,
+}
+
+/// Holds finalized results back so they can be challenged before reaching the task queue
+#[cw_serde]
+pub struct DisputeConfig {
+    /// How long a finalized result stays pending, in seconds
+    pub period: u64,
+    /// The only address that can resolve disputes
+    pub arbiter: Addr,
+    /// What a challenger must send along with a dispute
+    pub bond: Coin,
+    /// Stake slashed from every operator backing a result when a dispute is upheld, it is paid
+    /// to the challenger on top of the returned bond
+    pub slash_amount: Uint128,
+    /// How long the arbiter has to resolve a dispute, in seconds. Past that the challenger can
+    /// be refunded
+    pub resolution_period: u64,
+}
+
+#[cw_serde]
+pub struct PendingResult {
+    pub price: Decimal256,
+    /// UNIX seconds after which the result can be settled
+    pub settles_at: u64,
+    /// Operators whose votes backed this result, slashed if a dispute is upheld
+    pub backers: Vec<Addr>,
+    pub dispute: Option<Dispute>,
+}
+
+#[cw_serde]
+pub struct Dispute {
+    pub challenger: Addr,
+    pub evidence: String,
+    pub bond: Coin,
+    /// UNIX seconds after which the dispute can expire if the arbiter didn't resolve it
+    pub resolve_by: u64,
 }
 
 #[cw_serde]
 pub struct OperatorVote {
     pub power: Uint128,
-    pub result: Decimal,
+    pub result: Decimal256,
 }
 
 /// Metadata for a task option with some votes - indexed by (task_queue, task_id, result)
@@ -48,22 +123,20 @@ pub fn record_vote(
     power: Uint128,
 ) -> Result<Uint128, StdError> {
     let price_result: PriceResult =
-        serde_json::from_str(result).expect("Record vote: Invalid result input provided");
+        serde_json::from_str(result).map_err(|e| StdError::parse_err("PriceResult", e))?;
 
     let vote = OperatorVote {
         power,
-        result: Decimal::from_str(&price_result.price)?,
+        result: Decimal256::from_str(&price_result.price)?,
     };
 
-    VOTES
-        .save(storage, (task_queue, task_id, operator), &vote)
-        .unwrap();
+    VOTES.save(storage, (task_queue, task_id, operator), &vote)?;
 
     // Update the option and get the running tally of power in favor of this result
     let tally = OPTIONS.update::<_, StdError>(storage, (task_queue, task_id, result), |old| {
         let old_power = old.map_or(Uint128::zero(), |v| v.power);
         Ok(TaskOption {
-            power: old_power + power,
+            power: old_power.checked_add(power)?,
         })
     })?;
     Ok(tally.power)
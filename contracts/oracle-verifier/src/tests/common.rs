@@ -1,9 +1,8 @@
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{coin, coins, Decimal, Decimal256, Uint128};
 use cw_orch::environment::{ChainState, CwEnv};
 use cw_orch::prelude::*;
 
 use lavs_apis::id::TaskId;
-use lavs_apis::tasks::{Requestor, Status, TimeoutInfo};
 use lavs_orch::{Addressable, AltSigner};
 use serde_json::json;
 
@@ -11,7 +10,7 @@ use lavs_task_queue::{
     interface::Contract as TasksContract,
     msg::{
         CustomExecuteMsgFns as TasksExecuteMsgFns, CustomQueryMsgFns as TasksQueryMsgFns,
-        InstantiateMsg as TasksInstantiateMsg,
+        InstantiateMsg as TasksInstantiateMsg, Requestor, Status, TimeoutInfo,
     },
 };
 
@@ -20,10 +19,24 @@ use lavs_mock_operators::msg::{
     InstantiateMsg as MockOperatorsInstantiateMsg, InstantiateOperator,
 };
 
+use lavs_operator_registry::interface::Contract as RegistryContract;
+use lavs_operator_registry::msg::{
+    ExecuteMsgFns as RegistryExecuteMsgFns, InstantiateMsg as RegistryInstantiateMsg,
+};
+
+use crate::error::ContractError;
 use crate::interface::Contract;
-use crate::msg::{ExecuteMsgFns, InstantiateMsg, QueryMsgFns};
+use crate::msg::{CrossCheckMsg, DisputeMsg, ExecuteMsgFns, InstantiateMsg, QueryMsgFns};
+use crate::state::CrossCheckStatus;
 
 pub const BECH_PREFIX: &str = "slay3r";
+pub const DISPUTE_DENOM: &str = "ustake";
+pub const DISPUTE_BOND: u128 = 1_000;
+pub const SLASH_AMOUNT: u128 = 20;
+/// Bonded by each of the `dispute_operators` in the operator registry
+pub const OPERATOR_STAKE: u128 = 50;
+const DISPUTE_PERIOD: u64 = 100;
+const RESOLUTION_PERIOD: u64 = 200;
 
 pub fn setup<Chain: CwEnv>(chain: Chain, msg: InstantiateMsg) -> Contract<Chain> {
     let contract = Contract::new(chain);
@@ -65,6 +78,8 @@ where
         allowed_spread: Decimal::percent(10),
         slashable_spread: Decimal::percent(20),
         required_percentage: 100,
+        cross_check: None,
+        dispute: None,
     };
     let oracle_verifier = setup(chain.clone(), msg);
 
@@ -100,7 +115,7 @@ where
         }
     );
 
-    let median_price = Decimal::percent(10000);
+    let median_price = Decimal256::percent(10000);
     let task_result = status.result.unwrap();
     assert_eq!(task_result, json!({"price": median_price.to_string()}));
 
@@ -139,6 +154,8 @@ where
         allowed_spread: Decimal::percent(5),
         slashable_spread: Decimal::percent(10),
         required_percentage: 70,
+        cross_check: None,
+        dispute: None,
     };
     let verifier = setup(chain.clone(), msg);
 
@@ -164,6 +181,1054 @@ where
     assert_eq!(status.status, Status::Open {});
 }
 
+pub fn cross_check<C>(chain: C, second_set_price: &str, expect_completed: bool)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    // two independent operator sets
+    let operator1 = chain.alt_signer(3);
+    let operator2 = chain.alt_signer(4);
+    let operator3 = chain.alt_signer(5);
+    let operator4 = chain.alt_signer(6);
+
+    let first_set = setup_mock_operators(
+        chain.clone(),
+        vec![
+            InstantiateOperator {
+                addr: operator1.addr().to_string(),
+                voting_power: 50u32,
+            },
+            InstantiateOperator {
+                addr: operator2.addr().to_string(),
+                voting_power: 50u32,
+            },
+        ],
+    );
+    let second_set = setup_mock_operators(
+        chain.clone(),
+        vec![
+            InstantiateOperator {
+                addr: operator3.addr().to_string(),
+                voting_power: 60u32,
+            },
+            InstantiateOperator {
+                addr: operator4.addr().to_string(),
+                voting_power: 40u32,
+            },
+        ],
+    );
+
+    let msg = InstantiateMsg {
+        operator_contract: first_set.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(90),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        cross_check: Some(CrossCheckMsg {
+            operator_contracts: vec![second_set.addr_str().unwrap()],
+            tolerance: Decimal::percent(5),
+        }),
+        dispute: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_price"});
+    let task_id = make_task(&tasker, "Get Price Task", None, &payload);
+
+    // the first set finalizes on its own, but the task stays open
+    for operator in [&operator1, &operator2] {
+        verifier
+            .call_as(operator)
+            .executed_task(
+                tasker.addr_str().unwrap(),
+                task_id,
+                r#"{"price": "100"}"#.to_string(),
+            )
+            .unwrap();
+    }
+    assert_eq!(tasker.task(task_id).unwrap().status, Status::Open {});
+    let cross_check = verifier
+        .cross_check(tasker.addr_str().unwrap(), task_id)
+        .unwrap();
+    assert_eq!(cross_check.status, None);
+    assert_eq!(cross_check.results.len(), 1);
+    assert_eq!(cross_check.results[0].price, Decimal256::percent(10000));
+
+    // an operator of a finalized set cannot vote again
+    verifier
+        .call_as(&operator1)
+        .executed_task(
+            tasker.addr_str().unwrap(),
+            task_id,
+            r#"{"price": "100"}"#.to_string(),
+        )
+        .unwrap_err();
+
+    let result = format!(r#"{{"price": "{second_set_price}"}}"#);
+    for operator in [&operator3, &operator4] {
+        verifier
+            .call_as(operator)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.clone())
+            .unwrap();
+    }
+
+    let cross_check = verifier
+        .cross_check(tasker.addr_str().unwrap(), task_id)
+        .unwrap();
+    assert_eq!(cross_check.results.len(), 2);
+
+    let status = tasker.task(task_id).unwrap();
+    if expect_completed {
+        assert_eq!(cross_check.status, Some(CrossCheckStatus::Completed));
+        assert_eq!(
+            status.status,
+            Status::Completed {
+                completed: chain.block_info().unwrap().time.seconds()
+            }
+        );
+    } else {
+        assert_eq!(cross_check.status, Some(CrossCheckStatus::Disputed));
+        assert_eq!(status.status, Status::Open {});
+    }
+}
+
+pub fn cross_check_overlapping_operator<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    // operator1 is listed in both sets
+    let operator1 = chain.alt_signer(3);
+    let operator2 = chain.alt_signer(4);
+    let operator3 = chain.alt_signer(5);
+
+    let first_set = setup_mock_operators(
+        chain.clone(),
+        vec![
+            InstantiateOperator {
+                addr: operator1.addr().to_string(),
+                voting_power: 50u32,
+            },
+            InstantiateOperator {
+                addr: operator2.addr().to_string(),
+                voting_power: 50u32,
+            },
+        ],
+    );
+    let second_set = setup_mock_operators(
+        chain.clone(),
+        vec![
+            InstantiateOperator {
+                addr: operator1.addr().to_string(),
+                voting_power: 50u32,
+            },
+            InstantiateOperator {
+                addr: operator3.addr().to_string(),
+                voting_power: 50u32,
+            },
+        ],
+    );
+
+    let msg = InstantiateMsg {
+        operator_contract: first_set.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(90),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        // each set can finalize without the shared operator
+        required_percentage: 50,
+        cross_check: Some(CrossCheckMsg {
+            operator_contracts: vec![second_set.addr_str().unwrap()],
+            tolerance: Decimal::percent(5),
+        }),
+        dispute: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_price"});
+    let task_id = make_task(&tasker, "Get Price Task", None, &payload);
+
+    // the shared operator can't vote in either set
+    let err = verifier
+        .call_as(&operator1)
+        .executed_task(
+            tasker.addr_str().unwrap(),
+            task_id,
+            r#"{"price": "100"}"#.to_string(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root().to_string(),
+        ContractError::OperatorInSeveralSets(operator1.addr().to_string()).to_string()
+    );
+    let vote = verifier
+        .operator_vote(
+            tasker.addr_str().unwrap(),
+            task_id,
+            operator1.addr().to_string(),
+        )
+        .unwrap();
+    assert!(vote.is_none());
+
+    // the others still finalize their own set
+    for operator in [&operator2, &operator3] {
+        verifier
+            .call_as(operator)
+            .executed_task(
+                tasker.addr_str().unwrap(),
+                task_id,
+                r#"{"price": "100"}"#.to_string(),
+            )
+            .unwrap();
+    }
+    let cross_check = verifier
+        .cross_check(tasker.addr_str().unwrap(), task_id)
+        .unwrap();
+    assert_eq!(cross_check.status, Some(CrossCheckStatus::Completed));
+    assert_eq!(cross_check.results.len(), 2);
+    assert_eq!(
+        tasker.task(task_id).unwrap().status,
+        Status::Completed {
+            completed: chain.block_info().unwrap().time.seconds()
+        }
+    );
+}
+
+/// Operators voting on the results challenged in the dispute tests, the operator contract
+/// must give them this power
+pub fn dispute_operators<C>(chain: &C) -> Vec<InstantiateOperator>
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    [chain.alt_signer(3), chain.alt_signer(4)]
+        .iter()
+        .map(|operator| InstantiateOperator {
+            addr: operator.addr().to_string(),
+            voting_power: OPERATOR_STAKE as u32,
+        })
+        .collect()
+}
+
+/// Instantiates an operator registry where the `dispute_operators` bond `OPERATOR_STAKE`
+fn setup_operator_registry<C>(chain: C, admin: &C::Sender) -> RegistryContract<C>
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let registry = RegistryContract::new(chain.clone());
+    registry.upload().unwrap();
+    let msg = RegistryInstantiateMsg {
+        bond_denom: DISPUTE_DENOM.to_string(),
+        min_bond: Uint128::new(OPERATOR_STAKE),
+        unbonding_period: RESOLUTION_PERIOD,
+        slashers: vec![],
+        admin: Some(admin.addr().to_string()),
+    };
+    registry.instantiate(&msg, None, &[]).unwrap();
+    for operator in [chain.alt_signer(3), chain.alt_signer(4)] {
+        registry
+            .call_as(&operator)
+            .bond(&coins(OPERATOR_STAKE, DISPUTE_DENOM))
+            .unwrap();
+    }
+    registry
+}
+
+/// Instantiates a verifier with a dispute window over the `dispute_operators`
+fn setup_dispute_verifier<C>(
+    chain: C,
+    arbiter: &C::Sender,
+    operator_contract: String,
+) -> (Contract<C>, TasksContract<C>)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let msg = InstantiateMsg {
+        operator_contract,
+        threshold_percentage: Decimal::percent(90),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        cross_check: None,
+        dispute: Some(DisputeMsg {
+            period: DISPUTE_PERIOD,
+            arbiter: arbiter.addr().to_string(),
+            bond: coin(DISPUTE_BOND, DISPUTE_DENOM),
+            slash_amount: Uint128::new(SLASH_AMOUNT),
+            resolution_period: RESOLUTION_PERIOD,
+        }),
+    };
+    let verifier = setup(chain.clone(), msg);
+    let tasker = setup_task_queue(chain, &verifier.addr_str().unwrap());
+    (verifier, tasker)
+}
+
+/// Creates a task and has all the `dispute_operators` agree on its price
+fn vote_task<C>(
+    chain: &C,
+    verifier: &Contract<C>,
+    tasker: &TasksContract<C>,
+    timeout: Option<u64>,
+) -> TaskId
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let payload = json!({"action": "get_price"});
+    let task_id = make_task(tasker, "Get Price Task", timeout, &payload);
+    for operator in [chain.alt_signer(3), chain.alt_signer(4)] {
+        verifier
+            .call_as(&operator)
+            .executed_task(
+                tasker.addr_str().unwrap(),
+                task_id,
+                r#"{"price": "100"}"#.to_string(),
+            )
+            .unwrap();
+    }
+    task_id
+}
+
+/// Instantiates a verifier with a dispute window and votes until the result is pending
+fn setup_pending_result<C>(
+    chain: C,
+    arbiter: &C::Sender,
+    operator_contract: String,
+) -> (Contract<C>, TasksContract<C>, TaskId, Vec<Addr>)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (verifier, tasker) = setup_dispute_verifier(chain.clone(), arbiter, operator_contract);
+    let task_id = vote_task(&chain, &verifier, &tasker, None);
+
+    let pending = verifier
+        .pending(tasker.addr_str().unwrap(), task_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(pending.price, Decimal256::percent(10000));
+    assert_eq!(pending.dispute, None);
+    assert_eq!(tasker.task(task_id).unwrap().status, Status::Open {});
+
+    let backers = [chain.alt_signer(3), chain.alt_signer(4)]
+        .iter()
+        .map(|operator| operator.addr())
+        .collect();
+    (verifier, tasker, task_id, backers)
+}
+
+fn stake_balance<C: CwEnv>(chain: &C, addr: &Addr) -> u128 {
+    chain
+        .bank_querier()
+        .balance(addr, Some(DISPUTE_DENOM.to_string()))
+        .unwrap()
+        .iter()
+        .map(|coin| coin.amount.u128())
+        .sum()
+}
+
+pub fn dispute_window_settles<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let arbiter = chain.alt_signer(8);
+    let operators = setup_mock_operators(chain.clone(), dispute_operators(&chain));
+    let (verifier, tasker, task_id, _) =
+        setup_pending_result(chain.clone(), &arbiter, operators.addr_str().unwrap());
+
+    // cannot settle while the window is open
+    verifier
+        .settle(tasker.addr_str().unwrap(), task_id)
+        .unwrap_err();
+
+    // anyone can settle afterwards
+    chain.wait_seconds(DISPUTE_PERIOD).unwrap();
+    verifier
+        .settle(tasker.addr_str().unwrap(), task_id)
+        .unwrap();
+
+    let status = tasker.task(task_id).unwrap();
+    assert_eq!(
+        status.status,
+        Status::Completed {
+            completed: chain.block_info().unwrap().time.seconds()
+        }
+    );
+    assert_eq!(
+        status.result.unwrap(),
+        json!({"price": Decimal256::percent(10000).to_string()})
+    );
+    assert_eq!(
+        verifier
+            .pending(tasker.addr_str().unwrap(), task_id)
+            .unwrap(),
+        None
+    );
+}
+
+pub fn dispute_window_settles_in_batch<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let arbiter = chain.alt_signer(8);
+    let operators = setup_mock_operators(chain.clone(), dispute_operators(&chain));
+    let (verifier, tasker, one, _) =
+        setup_pending_result(chain.clone(), &arbiter, operators.addr_str().unwrap());
+    let two = vote_task(&chain, &verifier, &tasker, None);
+    // expires after the window closes, but before the batch is sent
+    let late = vote_task(&chain, &verifier, &tasker, Some(DISPUTE_PERIOD + 10));
+
+    let err = verifier
+        .settle_batch(tasker.addr_str().unwrap(), vec![one, two])
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .starts_with("Dispute window is open until"));
+
+    // one `CompleteBatch` for the tasks that can still be completed
+    chain.wait_seconds(DISPUTE_PERIOD + 20).unwrap();
+    let res = verifier
+        .settle_batch(tasker.addr_str().unwrap(), vec![one, two, late])
+        .unwrap();
+    assert_eq!(
+        res.event_attr_values("wasm", "task_id"),
+        vec![one.to_string(), two.to_string()]
+    );
+    assert_eq!(
+        res.event_attr_values("wasm", "dropped_task_id"),
+        vec![late.to_string()]
+    );
+    assert!(!res
+        .event_attr_values("wasm", "method")
+        .contains(&"complete_failed".to_string()));
+
+    let completed = Status::Completed {
+        completed: chain.block_info().unwrap().time.seconds(),
+    };
+    for task_id in [one, two] {
+        let status = tasker.task(task_id).unwrap();
+        assert_eq!(status.status, completed);
+        assert_eq!(
+            status.result.unwrap(),
+            json!({"price": Decimal256::percent(10000).to_string()})
+        );
+    }
+    assert_eq!(tasker.task(late).unwrap().status, Status::Expired {});
+    for task_id in [one, two, late] {
+        assert_eq!(
+            verifier
+                .pending(tasker.addr_str().unwrap(), task_id)
+                .unwrap(),
+            None
+        );
+    }
+
+    // settled results are gone
+    let err = verifier
+        .settle_batch(tasker.addr_str().unwrap(), vec![one])
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NoPendingResult.to_string()));
+}
+
+pub fn dispute_window_skipped_for_short_tasks<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let arbiter = chain.alt_signer(8);
+    let operators = setup_mock_operators(chain.clone(), dispute_operators(&chain));
+    let (verifier, tasker) =
+        setup_dispute_verifier(chain.clone(), &arbiter, operators.addr_str().unwrap());
+
+    // the task would expire before the window closes, so it is completed right away
+    let task_id = vote_task(&chain, &verifier, &tasker, Some(DISPUTE_PERIOD / 2));
+
+    let status = tasker.task(task_id).unwrap();
+    assert_eq!(
+        status.status,
+        Status::Completed {
+            completed: chain.block_info().unwrap().time.seconds()
+        }
+    );
+    assert_eq!(
+        status.result.unwrap(),
+        json!({"price": Decimal256::percent(10000).to_string()})
+    );
+    assert_eq!(
+        verifier
+            .pending(tasker.addr_str().unwrap(), task_id)
+            .unwrap(),
+        None
+    );
+}
+
+/// The challenger must hold exactly `DISPUTE_BOND` of `DISPUTE_DENOM`, and the `dispute_operators`
+/// `OPERATOR_STAKE` of it
+pub fn dispute_upheld<C>(chain: C, challenger: C::Sender)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let arbiter = chain.alt_signer(8);
+    let registry = setup_operator_registry(chain.clone(), &arbiter);
+    let (verifier, tasker, task_id, backers) =
+        setup_pending_result(chain.clone(), &arbiter, registry.addr_str().unwrap());
+    registry
+        .call_as(&arbiter)
+        .update_slashers(vec![verifier.addr_str().unwrap()], vec![])
+        .unwrap();
+    let operator_contract = registry.address().unwrap();
+    let staked = stake_balance(&chain, &operator_contract);
+
+    // the bond is required
+    verifier
+        .call_as(&challenger)
+        .dispute(
+            tasker.addr_str().unwrap(),
+            task_id,
+            "price feed was stale".to_string(),
+            &[],
+        )
+        .unwrap_err();
+    verifier
+        .call_as(&challenger)
+        .dispute(
+            tasker.addr_str().unwrap(),
+            task_id,
+            "price feed was stale".to_string(),
+            &coins(DISPUTE_BOND, DISPUTE_DENOM),
+        )
+        .unwrap();
+    assert_eq!(stake_balance(&chain, &challenger.addr()), 0);
+
+    // a disputed result cannot be settled, even after the window
+    chain.wait_seconds(DISPUTE_PERIOD).unwrap();
+    verifier
+        .settle(tasker.addr_str().unwrap(), task_id)
+        .unwrap_err();
+
+    // only the arbiter resolves, and upholding requires the corrected price
+    let corrected = Decimal256::percent(9900);
+    verifier
+        .resolve_dispute(tasker.addr_str().unwrap(), task_id, true, Some(corrected))
+        .unwrap_err();
+    let err = verifier
+        .call_as(&arbiter)
+        .resolve_dispute(tasker.addr_str().unwrap(), task_id, true, None)
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::MissingCorrectedPrice.to_string()));
+    let res = verifier
+        .call_as(&arbiter)
+        .resolve_dispute(tasker.addr_str().unwrap(), task_id, true, Some(corrected))
+        .unwrap();
+    assert!(!res
+        .event_attr_values("wasm", "method")
+        .contains(&"slash_failed".to_string()));
+
+    let status = tasker.task(task_id).unwrap();
+    assert_eq!(
+        status.result.unwrap(),
+        json!({"price": corrected.to_string()})
+    );
+
+    // the challenger gets the bond back plus the stake slashed from every backer, which
+    // leaves the operator contract
+    let slashed = SLASH_AMOUNT * backers.len() as u128;
+    assert_eq!(
+        stake_balance(&chain, &challenger.addr()),
+        DISPUTE_BOND + slashed
+    );
+    assert_eq!(stake_balance(&chain, &operator_contract), staked - slashed);
+    assert_eq!(stake_balance(&chain, &verifier.address().unwrap()), 0);
+
+    let slashed_operators: Vec<Addr> = verifier.slashable_operators().unwrap();
+    for backer in backers {
+        assert!(slashed_operators.contains(&backer));
+    }
+}
+
+/// The challenger must hold exactly `DISPUTE_BOND` of `DISPUTE_DENOM`
+pub fn dispute_rejected_after_expiry<C>(chain: C, challenger: C::Sender)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let arbiter = chain.alt_signer(8);
+    let operators = setup_mock_operators(chain.clone(), dispute_operators(&chain));
+    let (verifier, tasker, task_id, _) =
+        setup_pending_result(chain.clone(), &arbiter, operators.addr_str().unwrap());
+    let undisputed = vote_task(&chain, &verifier, &tasker, None);
+
+    verifier
+        .call_as(&challenger)
+        .dispute(
+            tasker.addr_str().unwrap(),
+            task_id,
+            "price feed was stale".to_string(),
+            &coins(DISPUTE_BOND, DISPUTE_DENOM),
+        )
+        .unwrap();
+
+    // the tasks time out on the queue while the dispute is open and the other result unsettled
+    chain.wait_seconds(601).unwrap();
+    assert_eq!(tasker.task(task_id).unwrap().status, Status::Expired {});
+
+    // settling still clears the pending result
+    let res = verifier
+        .settle(tasker.addr_str().unwrap(), undisputed)
+        .unwrap();
+    assert!(res
+        .event_attr_values("wasm", "method")
+        .contains(&"complete_failed".to_string()));
+    assert_eq!(
+        verifier
+            .pending(tasker.addr_str().unwrap(), undisputed)
+            .unwrap(),
+        None
+    );
+
+    // the resolution still goes through, the bond is forfeited even if the task can't be completed
+    let res = verifier
+        .call_as(&arbiter)
+        .resolve_dispute(tasker.addr_str().unwrap(), task_id, false, None)
+        .unwrap();
+    assert!(res
+        .event_attr_values("wasm", "method")
+        .contains(&"complete_failed".to_string()));
+    assert_eq!(tasker.task(task_id).unwrap().status, Status::Expired {});
+    assert_eq!(
+        verifier
+            .pending(tasker.addr_str().unwrap(), task_id)
+            .unwrap(),
+        None
+    );
+    assert_eq!(stake_balance(&chain, &challenger.addr()), 0);
+    assert_eq!(stake_balance(&chain, &verifier.address().unwrap()), 0);
+}
+
+/// The challenger must hold exactly `DISPUTE_BOND` of `DISPUTE_DENOM`
+pub fn dispute_resolution_expires<C>(chain: C, challenger: C::Sender)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let arbiter = chain.alt_signer(8);
+    let operators = setup_mock_operators(chain.clone(), dispute_operators(&chain));
+    let (verifier, tasker, task_id, _) =
+        setup_pending_result(chain.clone(), &arbiter, operators.addr_str().unwrap());
+
+    // an undisputed result can't expire
+    verifier
+        .expire_dispute(tasker.addr_str().unwrap(), task_id)
+        .unwrap_err();
+
+    verifier
+        .call_as(&challenger)
+        .dispute(
+            tasker.addr_str().unwrap(),
+            task_id,
+            "price feed was stale".to_string(),
+            &coins(DISPUTE_BOND, DISPUTE_DENOM),
+        )
+        .unwrap();
+
+    // the arbiter still has time
+    chain.wait_seconds(RESOLUTION_PERIOD - 1).unwrap();
+    let err = verifier
+        .expire_dispute(tasker.addr_str().unwrap(), task_id)
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .starts_with("The arbiter can resolve the dispute until"));
+
+    // then anyone refunds the challenger, and the contested result is dropped
+    chain.wait_seconds(1).unwrap();
+    verifier
+        .expire_dispute(tasker.addr_str().unwrap(), task_id)
+        .unwrap();
+    assert_eq!(stake_balance(&chain, &challenger.addr()), DISPUTE_BOND);
+    assert_eq!(
+        verifier
+            .pending(tasker.addr_str().unwrap(), task_id)
+            .unwrap(),
+        None
+    );
+    assert_eq!(tasker.task(task_id).unwrap().status, Status::Open {});
+    verifier
+        .call_as(&arbiter)
+        .resolve_dispute(tasker.addr_str().unwrap(), task_id, false, None)
+        .unwrap_err();
+}
+
+/// An operator taking part in the adversarial scenarios, with its voting power and reported price
+#[derive(Debug, Clone)]
+pub struct SimOperator {
+    pub power: u32,
+    pub price: u64,
+}
+
+/// Aggregates only once every operator voted
+fn simulation_msg(operator_contract: String) -> InstantiateMsg {
+    InstantiateMsg {
+        operator_contract,
+        threshold_percentage: Decimal::percent(50),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        cross_check: None,
+        dispute: None,
+    }
+}
+
+/// Instantiates a verifier over the given operators and opens a single price task.
+/// Operators are signed for by `alt_signer(10..)` in order.
+fn setup_simulation<C>(
+    chain: &C,
+    operators: &[SimOperator],
+) -> (Vec<C::Sender>, Contract<C>, TasksContract<C>, TaskId)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let signers: Vec<C::Sender> = (0..operators.len() as u32)
+        .map(|i| chain.alt_signer(10 + i))
+        .collect();
+    let mock_operators = setup_mock_operators(
+        chain.clone(),
+        signers
+            .iter()
+            .zip(operators)
+            .map(|(signer, op)| InstantiateOperator {
+                addr: signer.addr().to_string(),
+                voting_power: op.power,
+            })
+            .collect(),
+    );
+    let verifier = setup(
+        chain.clone(),
+        simulation_msg(mock_operators.addr_str().unwrap()),
+    );
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+    let task_id = make_task(
+        &tasker,
+        "Get Price Task",
+        None,
+        &json!({"action": "get_price"}),
+    );
+    (signers, verifier, tasker, task_id)
+}
+
+fn price_result(price: impl ToString) -> String {
+    json!({"price": price.to_string()}).to_string()
+}
+
+/// Sum of the running tallies the verifier reports for the task
+fn tallied_power<C: CwEnv>(
+    verifier: &Contract<C>,
+    tasker: &TasksContract<C>,
+    task_id: TaskId,
+) -> Uint128 {
+    verifier
+        .task_info(tasker.addr_str().unwrap(), task_id)
+        .unwrap()
+        .map(|info| info.tallies.iter().map(|tally| tally.power).sum())
+        .unwrap_or_default()
+}
+
+fn completed_price<C: CwEnv>(tasker: &TasksContract<C>, task_id: TaskId) -> Option<Decimal256> {
+    let status = tasker.task(task_id).unwrap();
+    match status.status {
+        Status::Completed { .. } => {
+            let result = status.result.unwrap();
+            Some(result["price"].as_str().unwrap().parse().unwrap())
+        }
+        _ => None,
+    }
+}
+
+/// Honest operators report prices close to each other while a colluding minority (by count)
+/// reports arbitrary ones. The median is taken over the votes, not weighted by power, so an
+/// honest majority of operators must pin it inside the honest range.
+pub fn honest_majority<C>(chain: C, honest: Vec<SimOperator>, colluders: Vec<SimOperator>)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    assert!(colluders.len() < honest.len());
+    let honest_count = honest.len();
+    let operators: Vec<SimOperator> = honest.into_iter().chain(colluders).collect();
+    let (signers, verifier, tasker, task_id) = setup_simulation(&chain, &operators);
+
+    let mut voted_power = Uint128::zero();
+    for (signer, op) in signers.iter().zip(&operators) {
+        verifier
+            .call_as(signer)
+            .executed_task(tasker.addr_str().unwrap(), task_id, price_result(op.price))
+            .unwrap();
+        voted_power += Uint128::from(op.power);
+        assert_eq!(tallied_power(&verifier, &tasker, task_id), voted_power);
+    }
+
+    if let Some(price) = completed_price(&tasker, task_id) {
+        let min = operators.iter().map(|op| op.price).min().unwrap();
+        let max = operators.iter().map(|op| op.price).max().unwrap();
+        assert!(price >= Decimal256::from_atomics(min, 0).unwrap());
+        assert!(price <= Decimal256::from_atomics(max, 0).unwrap());
+    }
+
+    let slashed: Vec<Addr> = verifier.slashable_operators().unwrap();
+    for signer in signers.iter().take(honest_count) {
+        assert!(!slashed.contains(&signer.addr()));
+    }
+}
+
+pub fn colluding_minority<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operators = [
+        SimOperator {
+            power: 20,
+            price: 100,
+        },
+        SimOperator {
+            power: 20,
+            price: 101,
+        },
+        SimOperator {
+            power: 20,
+            price: 99,
+        },
+        // colluders agree on a price far off the market
+        SimOperator {
+            power: 20,
+            price: 150,
+        },
+        SimOperator {
+            power: 20,
+            price: 150,
+        },
+    ];
+    let (signers, verifier, tasker, task_id) = setup_simulation(&chain, &operators);
+
+    for (signer, op) in signers.iter().zip(&operators) {
+        verifier
+            .call_as(signer)
+            .executed_task(tasker.addr_str().unwrap(), task_id, price_result(op.price))
+            .unwrap();
+    }
+
+    assert_eq!(
+        completed_price(&tasker, task_id),
+        Some(Decimal256::percent(10100))
+    );
+    assert_eq!(
+        tallied_power(&verifier, &tasker, task_id),
+        Uint128::new(100)
+    );
+
+    let mut slashed: Vec<Addr> = verifier.slashable_operators().unwrap();
+    slashed.sort();
+    let mut colluders = vec![signers[3].addr(), signers[4].addr()];
+    colluders.sort();
+    assert_eq!(slashed, colluders);
+}
+
+pub fn identical_votes<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operators = [
+        SimOperator {
+            power: 1,
+            price: 100,
+        },
+        SimOperator {
+            power: 50,
+            price: 100,
+        },
+        SimOperator {
+            power: 1000,
+            price: 100,
+        },
+    ];
+    let (signers, verifier, tasker, task_id) = setup_simulation(&chain, &operators);
+
+    for signer in &signers {
+        verifier
+            .call_as(signer)
+            .executed_task(tasker.addr_str().unwrap(), task_id, price_result(100))
+            .unwrap();
+    }
+
+    assert_eq!(
+        completed_price(&tasker, task_id),
+        Some(Decimal256::percent(10000))
+    );
+    let info = verifier
+        .task_info(tasker.addr_str().unwrap(), task_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.tallies.len(), 1);
+    assert_eq!(info.tallies[0].power, Uint128::new(1051));
+
+    let slashed: Vec<Addr> = verifier.slashable_operators().unwrap();
+    assert!(slashed.is_empty());
+}
+
+pub fn zero_power_operator<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operators = [
+        SimOperator {
+            power: 50,
+            price: 100,
+        },
+        SimOperator {
+            power: 50,
+            price: 100,
+        },
+        SimOperator { power: 0, price: 1 },
+    ];
+    let (signers, verifier, tasker, task_id) = setup_simulation(&chain, &operators);
+
+    // the zero power vote comes first, so it would drag the median if it was counted
+    let err = verifier
+        .call_as(&signers[2])
+        .executed_task(tasker.addr_str().unwrap(), task_id, price_result(1))
+        .unwrap_err();
+    assert!(err.root().to_string().contains("Unauthorized"));
+    let vote = verifier
+        .operator_vote(
+            tasker.addr_str().unwrap(),
+            task_id,
+            signers[2].addr().to_string(),
+        )
+        .unwrap();
+    assert!(vote.is_none());
+    assert_eq!(tallied_power(&verifier, &tasker, task_id), Uint128::zero());
+
+    for signer in &signers[..2] {
+        verifier
+            .call_as(signer)
+            .executed_task(tasker.addr_str().unwrap(), task_id, price_result(100))
+            .unwrap();
+    }
+    assert_eq!(
+        completed_price(&tasker, task_id),
+        Some(Decimal256::percent(10000))
+    );
+}
+
+pub fn double_vote<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operators = [
+        SimOperator {
+            power: 40,
+            price: 100,
+        },
+        SimOperator {
+            power: 60,
+            price: 100,
+        },
+    ];
+    let (signers, verifier, tasker, task_id) = setup_simulation(&chain, &operators);
+
+    verifier
+        .call_as(&signers[0])
+        .executed_task(tasker.addr_str().unwrap(), task_id, price_result(100))
+        .unwrap();
+
+    // neither the same nor a different result may be counted twice
+    verifier
+        .call_as(&signers[0])
+        .executed_task(tasker.addr_str().unwrap(), task_id, price_result(100))
+        .unwrap_err();
+    verifier
+        .call_as(&signers[0])
+        .executed_task(tasker.addr_str().unwrap(), task_id, price_result(200))
+        .unwrap_err();
+    assert_eq!(tallied_power(&verifier, &tasker, task_id), Uint128::new(40));
+    assert_eq!(completed_price(&tasker, task_id), None);
+
+    verifier
+        .call_as(&signers[1])
+        .executed_task(tasker.addr_str().unwrap(), task_id, price_result(100))
+        .unwrap();
+    assert_eq!(
+        tallied_power(&verifier, &tasker, task_id),
+        Uint128::new(100)
+    );
+    assert_eq!(
+        completed_price(&tasker, task_id),
+        Some(Decimal256::percent(10000))
+    );
+}
+
+pub fn expired_task<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operators = [
+        SimOperator {
+            power: 50,
+            price: 100,
+        },
+        SimOperator {
+            power: 50,
+            price: 100,
+        },
+    ];
+    let (signers, verifier, tasker, task_id) = setup_simulation(&chain, &operators);
+
+    verifier
+        .call_as(&signers[0])
+        .executed_task(tasker.addr_str().unwrap(), task_id, price_result(100))
+        .unwrap();
+
+    // default timeout of the task queue
+    chain.wait_seconds(601).unwrap();
+
+    // late votes are ignored, the task is only marked as expired
+    let res = verifier
+        .call_as(&signers[1])
+        .executed_task(tasker.addr_str().unwrap(), task_id, price_result(100))
+        .unwrap();
+    assert!(res.event_attr_value("wasm", "new_price").is_err());
+    let vote = verifier
+        .operator_vote(
+            tasker.addr_str().unwrap(),
+            task_id,
+            signers[1].addr().to_string(),
+        )
+        .unwrap();
+    assert!(vote.is_none());
+    assert_eq!(tallied_power(&verifier, &tasker, task_id), Uint128::new(50));
+    assert_eq!(tasker.task(task_id).unwrap().status, Status::Expired {});
+}
+
 #[track_caller]
 pub fn make_task<C: ChainState + TxHandler>(
     contract: &TasksContract<C>,
@@ -172,7 +1237,15 @@ pub fn make_task<C: ChainState + TxHandler>(
     payload: &serde_json::Value,
 ) -> TaskId {
     let res = contract
-        .create(name.to_string(), timeout.into(), payload.clone(), &[])
+        .create(
+            name.to_string(),
+            timeout.into(),
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
         .unwrap();
     get_task_id(&res)
 }
@@ -196,6 +1269,14 @@ where
         requestor: Requestor::Fixed(chain.sender_addr().into()),
         timeout: TimeoutInfo::new(600),
         verifier: verifier_addr.to_string(),
+        verifiers: vec![],
+        refund: None,
+        owner: None,
+        fees: None,
+        allowed_requestors: vec![],
+        limits: None,
+        callback_gas_limit: None,
+        payload_schema: None,
     };
     let tasker = TasksContract::new(chain);
     tasker.upload().unwrap();
@@ -1,6 +1,9 @@
+use cosmwasm_std::coins;
 use cw_orch::prelude::MockBech32;
+use lavs_orch::{Addressable, AltSigner};
+use proptest::prelude::*;
 
-use super::common::BECH_PREFIX;
+use super::common::{SimOperator, BECH_PREFIX, DISPUTE_BOND, DISPUTE_DENOM, OPERATOR_STAKE};
 
 #[test]
 fn happy_path_works() {
@@ -13,3 +16,132 @@ fn threshold_not_met() {
     let chain = MockBech32::new(BECH_PREFIX);
     super::common::threshold_not_met(chain);
 }
+
+#[test]
+fn cross_check_agreement_completes() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::cross_check(chain, "102", true);
+}
+
+#[test]
+fn cross_check_disagreement_disputes() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::cross_check(chain, "120", false);
+}
+
+#[test]
+fn cross_check_overlapping_operator_refused() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::cross_check_overlapping_operator(chain);
+}
+
+#[test]
+fn dispute_window_settles() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::dispute_window_settles(chain);
+}
+
+#[test]
+fn dispute_window_settles_in_batch() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::dispute_window_settles_in_batch(chain);
+}
+
+#[test]
+fn dispute_window_skipped_for_short_tasks() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::dispute_window_skipped_for_short_tasks(chain);
+}
+
+#[test]
+fn dispute_upheld() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    let challenger = chain.alt_signer(7);
+    chain
+        .add_balance(&challenger.addr(), coins(DISPUTE_BOND, DISPUTE_DENOM))
+        .unwrap();
+    for operator in [chain.alt_signer(3), chain.alt_signer(4)] {
+        chain
+            .add_balance(&operator.addr(), coins(OPERATOR_STAKE, DISPUTE_DENOM))
+            .unwrap();
+    }
+    super::common::dispute_upheld(chain, challenger);
+}
+
+#[test]
+fn dispute_rejected_after_expiry() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    let challenger = chain.alt_signer(7);
+    chain
+        .add_balance(&challenger.addr(), coins(DISPUTE_BOND, DISPUTE_DENOM))
+        .unwrap();
+    super::common::dispute_rejected_after_expiry(chain, challenger);
+}
+
+#[test]
+fn dispute_resolution_expires() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    let challenger = chain.alt_signer(7);
+    chain
+        .add_balance(&challenger.addr(), coins(DISPUTE_BOND, DISPUTE_DENOM))
+        .unwrap();
+    super::common::dispute_resolution_expires(chain, challenger);
+}
+
+#[test]
+fn colluding_minority_slashed() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::colluding_minority(chain);
+}
+
+#[test]
+fn identical_votes() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::identical_votes(chain);
+}
+
+#[test]
+fn zero_power_operator_not_counted() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::zero_power_operator(chain);
+}
+
+#[test]
+fn double_vote_rejected() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::double_vote(chain);
+}
+
+#[test]
+fn expired_task_not_completed() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::expired_task(chain);
+}
+
+fn sim_operators(
+    prices: impl Strategy<Value = u64>,
+    size: impl Into<prop::collection::SizeRange>,
+) -> impl Strategy<Value = Vec<SimOperator>> {
+    prop::collection::vec((1u32..1000, prices), size).prop_map(|operators| {
+        operators
+            .into_iter()
+            .map(|(power, price)| SimOperator { power, price })
+            .collect()
+    })
+}
+
+proptest! {
+    // every case spins up a fresh chain, keep it to a handful
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn honest_majority_randomized(
+        (honest, colluders) in (2usize..6).prop_flat_map(|honest| (
+            sim_operators(97u64..=103, honest),
+            sim_operators(1u64..10_000, 0..honest),
+        ))
+    ) {
+        let chain = MockBech32::new(BECH_PREFIX);
+        super::common::honest_majority(chain, honest, colluders);
+    }
+}
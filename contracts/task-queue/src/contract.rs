@@ -1,29 +1,76 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    from_json, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    SubMsgResult,
+};
 use cw2::set_contract_version;
-
-use lavs_apis::interfaces::tasks as interface;
-use lavs_apis::tasks::{CustomExecuteMsg, CustomQueryMsg, TaskQueryMsg};
+use lavs_apis::id::TaskId;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    CustomExecuteMsg, CustomQueryMsg, ExecuteMsg, InstantiateMsg, QueryMsg, TaskExecuteMsg,
+    TaskQueryMsg,
+};
 use crate::msg::{RequestType, ResponseType, Status};
-use crate::state::{Config, Task, CONFIG, TASKS};
+use crate::state::{
+    Config, RequestorConfig, Task, ALLOWED_REQUESTORS, CONFIG, OPEN_TASKS, TASKS, VERIFIERS,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Most tasks created by a single `Tick`, so it fits in a block
+pub const MAX_TICK_TASKS: usize = 50;
+
+/// Most tasks created or completed in a single batch
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// Most tasks marked as expired by a single `TimeoutBatch`, and the default
+pub const MAX_TIMEOUT_BATCH: u32 = 50;
+
+/// Reply id of the callbacks to the task creators
+pub const CALLBACK_REPLY_ID: u64 = 1;
+
+/// Most tasks a single task can depend on
+pub const MAX_DEPENDENCIES: usize = 10;
+
+/// Page size of the list queries when no limit is given
+pub const DEFAULT_LIMIT: u32 = 100;
+/// Largest page size of the list queries
+pub const MAX_LIMIT: u32 = 250;
+/// Most tasks a list query filtering by status looks at, so pages stay cheap when few tasks match
+pub const MAX_SCANNED_TASKS: usize = 1000;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    let config = Config::validate(deps.as_ref(), msg)?;
+    let allowed = msg.allowed_requestors.clone();
+    let verifiers = msg.verifiers.clone();
+    let config = Config::validate(deps.as_ref(), &info.sender, msg)?;
     CONFIG.save(deps.storage, &config)?;
+    VERIFIERS.save(deps.storage, &config.verifier, &())?;
+    for addr in verifiers {
+        let addr = deps.api.addr_validate(&addr)?;
+        VERIFIERS.save(deps.storage, &addr, &())?;
+    }
+    OPEN_TASKS.save(deps.storage, &0)?;
+    NEXT_SCHEDULE_ID.save(deps.storage, &1)?;
+
+    if !allowed.is_empty() {
+        if !matches!(config.requestor, RequestorConfig::AllowList { .. }) {
+            return Err(ContractError::NoAllowList);
+        }
+        for addr in allowed {
+            let addr = deps.api.addr_validate(&addr)?;
+            ALLOWED_REQUESTORS.save(deps.storage, &addr, &())?;
+        }
+    }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
@@ -39,7 +86,7 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Api(api) => match api {
-            interface::TaskExecuteMsg::Complete { task_id, response } => {
+            TaskExecuteMsg::Complete { task_id, response } => {
                 execute::complete(deps, env, info, task_id, response)
             }
         },
@@ -48,12 +95,102 @@ pub fn execute(
                 description,
                 timeout,
                 payload,
-            } => execute::create(deps, env, info, description, timeout, payload),
+                depends_on,
+                callback,
+                verifier,
+            } => {
+                let payment = CONFIG
+                    .load(deps.storage)?
+                    .requestor
+                    .check_requestor(deps.storage, &info)?;
+                let task = NewTask {
+                    description,
+                    timeout,
+                    payload,
+                    depends_on,
+                    callback,
+                    verifier,
+                };
+                execute::create(deps, env, info.sender, payment, task)
+            }
+            CustomExecuteMsg::CreateBatch { tasks } => {
+                execute::create_batch(deps, env, info, tasks)
+            }
+            CustomExecuteMsg::CompleteBatch { results } => {
+                execute::complete_batch(deps, env, info, results)
+            }
+            CustomExecuteMsg::CreateRecurring {
+                description,
+                timeout,
+                payload,
+                interval_secs,
+                end,
+            } => execute::create_recurring(
+                deps,
+                env,
+                info,
+                description,
+                timeout,
+                payload,
+                interval_secs,
+                end,
+            ),
+            CustomExecuteMsg::CancelRecurring { schedule_id } => {
+                execute::cancel_recurring(deps, info, schedule_id)
+            }
+            CustomExecuteMsg::Tick {} => execute::tick(deps, env, info),
             CustomExecuteMsg::Timeout { task_id } => execute::timeout(deps, env, info, task_id),
+            CustomExecuteMsg::TimeoutBatch { limit } => {
+                execute::timeout_batch(deps, env, info, limit)
+            }
+            CustomExecuteMsg::Cancel { task_id } => execute::cancel(deps, env, info, task_id),
+            CustomExecuteMsg::WithdrawFees { amount, to } => {
+                execute::withdraw_fees(deps, info, amount, to)
+            }
+            CustomExecuteMsg::UpdateCallbackGasLimit { gas_limit } => {
+                execute::update_callback_gas_limit(deps, info, gas_limit)
+            }
+            CustomExecuteMsg::UpdatePayloadSchema { schema } => {
+                execute::update_payload_schema(deps, info, schema)
+            }
+            CustomExecuteMsg::UpdateVerifiers { add, remove } => {
+                execute::update_verifiers(deps, info, add, remove)
+            }
+            CustomExecuteMsg::SetDefaultVerifier { verifier } => {
+                execute::set_default_verifier(deps, info, verifier)
+            }
+            CustomExecuteMsg::UpdateAllowList { add, remove } => {
+                execute::update_allow_list(deps, info, add, remove)
+            }
+            CustomExecuteMsg::Receive {
+                sender,
+                amount,
+                msg,
+            } => execute::receive(deps, env, info, sender, amount, msg),
         },
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        // only failed callbacks reply, they must not revert the task queue
+        CALLBACK_REPLY_ID => {
+            let task_id: TaskId = from_json(&msg.payload)?;
+            let error = match msg.result {
+                SubMsgResult::Err(error) => error,
+                SubMsgResult::Ok(_) => return Ok(Response::new()),
+            };
+            let res = Response::new()
+                .add_attribute("action", "callback_failed")
+                .add_attribute("task_id", task_id.to_string())
+                .add_attribute("error", error);
+            Ok(res)
+        }
+        id => Err(ContractError::UnknownReplyId(id)),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
@@ -64,57 +201,553 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         },
         QueryMsg::Custom(custom) => match custom {
             CustomQueryMsg::Task { id } => Ok(to_json_binary(&query::task(deps, env, id)?)?),
+            CustomQueryMsg::ListTasks {
+                status,
+                start_after,
+                limit,
+                order,
+            } => Ok(to_json_binary(&query::list_tasks(
+                deps,
+                env,
+                status,
+                start_after,
+                limit,
+                order,
+            )?)?),
+            CustomQueryMsg::TasksByRequestor {
+                address,
+                status,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&query::tasks_by_requestor(
+                deps,
+                env,
+                address,
+                status,
+                start_after,
+                limit,
+            )?)?),
+            CustomQueryMsg::TasksCreatedBetween {
+                from,
+                to,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&query::tasks_created_between(
+                deps,
+                env,
+                from,
+                to,
+                start_after,
+                limit,
+            )?)?),
             CustomQueryMsg::ListOpen { start_after, limit } => Ok(to_json_binary(
                 &query::list_open(deps, env, start_after, limit)?,
             )?),
             CustomQueryMsg::ListCompleted { start_after, limit } => Ok(to_json_binary(
                 &query::list_completed(deps, env, start_after, limit)?,
             )?),
+            CustomQueryMsg::ListExpired { start_after, limit } => Ok(to_json_binary(
+                &query::list_expired(deps, env, start_after, limit)?,
+            )?),
             CustomQueryMsg::Config {} => Ok(to_json_binary(&query::config(deps, env)?)?),
+            CustomQueryMsg::PayloadSchema {} => Ok(to_json_binary(&query::payload_schema(deps)?)?),
+            CustomQueryMsg::CollectedFees {} => Ok(to_json_binary(&query::collected_fees(deps)?)?),
+            CustomQueryMsg::TaskGraph { id } => {
+                Ok(to_json_binary(&query::task_graph(deps, env, id)?)?)
+            }
+            CustomQueryMsg::Schedule { id } => Ok(to_json_binary(&query::schedule(deps, id)?)?),
+            CustomQueryMsg::ListSchedules { start_after, limit } => Ok(to_json_binary(
+                &query::list_schedules(deps, start_after, limit)?,
+            )?),
+            CustomQueryMsg::RequestorUsage { address } => Ok(to_json_binary(
+                &query::requestor_usage(deps, env, address)?,
+            )?),
+            CustomQueryMsg::AllowList { start_after, limit } => Ok(to_json_binary(
+                &query::allow_list(deps, start_after, limit)?,
+            )?),
+            CustomQueryMsg::Verifiers { start_after, limit } => Ok(to_json_binary(
+                &query::verifiers(deps, start_after, limit)?,
+            )?),
         },
     }
 }
 
 mod execute {
+    use cosmwasm_std::{
+        from_json, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, Event, Order, StdResult, Storage,
+        SubMsg, Uint128, WasmMsg,
+    };
+    use cw_storage_plus::Bound;
     use cw_utils::nonpayable;
     use lavs_apis::id::TaskId;
 
-    use crate::state::{check_timeout, Timing};
+    use crate::msg::{
+        CallbackMsg, NewTask, PayloadSchema, Payment, ReceiveMsg, ScheduleEnd, TaskResult,
+    };
+    use crate::state::{
+        check_dependencies, check_payload, check_timeout, denom_key, inject_results, schedule_runs,
+        split_funds, track_closed, track_created, validate_payload_schema, Dependencies, Schedule,
+        Timing, COLLECTED_FEES, DEPENDENTS, SCHEDULE_QUEUE,
+    };
 
     use super::*;
 
+    /// Creates a task once the requestor and its payment were checked
     pub fn create(
         deps: DepsMut,
         env: Env,
-        info: MessageInfo,
-        description: String,
-        timeout: Option<u64>,
-        payload: RequestType,
+        creator: Addr,
+        payment: Option<Payment>,
+        task: NewTask,
     ) -> Result<Response, ContractError> {
-        let mut config = CONFIG.load(deps.storage)?;
+        let task_id = create_task(deps.storage, deps.api, &env, creator, payment, task)?;
+
+        let res = Response::new()
+            .add_attribute("action", "create")
+            .add_attribute("task_id", task_id.to_string());
+        Ok(res)
+    }
+
+    fn create_task(
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        env: &Env,
+        creator: Addr,
+        payment: Option<Payment>,
+        task: NewTask,
+    ) -> Result<TaskId, ContractError> {
+        let NewTask {
+            description,
+            timeout,
+            mut payload,
+            mut depends_on,
+            callback,
+            verifier,
+        } = task;
+        let mut config = CONFIG.load(storage)?;
         let timeout = check_timeout(&config.timeout, timeout)?;
-        config.requestor.check_requestor(&info)?;
+        let verifier = match verifier {
+            Some(verifier) => {
+                let addr = api.addr_validate(&verifier)?;
+                if !VERIFIERS.has(storage, &addr) {
+                    return Err(ContractError::NotVerifier(verifier));
+                }
+                addr
+            }
+            None => config.verifier.clone(),
+        };
+        if let Some(schema) = &config.payload_schema {
+            check_payload(schema, &payload)?;
+        }
+
+        // it opens right away unless some of the tasks it depends on are not completed yet
+        let mut status = Status::new();
+        depends_on.sort();
+        depends_on.dedup();
+        if depends_on.len() > MAX_DEPENDENCIES {
+            return Err(ContractError::InvalidDependency(format!(
+                "a task can depend on at most {MAX_DEPENDENCIES} tasks"
+            )));
+        }
+        if !depends_on.is_empty() {
+            match check_dependencies(storage, env, &depends_on)? {
+                Dependencies::Completed(results) => inject_results(&mut payload, results)?,
+                Dependencies::Waiting if payload.is_object() => status = Status::Pending {},
+                Dependencies::Waiting => {
+                    return Err(ContractError::InvalidDependency(
+                        "payload must be an object".to_string(),
+                    ))
+                }
+                Dependencies::Failed(id) => {
+                    return Err(ContractError::InvalidDependency(format!(
+                        "task {id} can't be completed anymore"
+                    )))
+                }
+            }
+        }
+        track_created(storage, env, &config.limits, &creator)?;
 
-        let timing = Timing::new(&env, timeout);
-        let status = Status::new();
+        let priority = payment
+            .as_ref()
+            .map(|paid| config.requestor.priority_fee(paid))
+            .unwrap_or_default();
+
+        let timing = Timing::new(env, timeout);
         let task = Task {
             description,
             status,
             timing,
             payload,
             result: None,
+            creator,
+            payment,
+            priority,
+            depends_on,
+            callback: callback.map(|addr| api.addr_validate(&addr)).transpose()?,
+            verifier,
         };
         let task_id = config.next_id;
-        TASKS.save(deps.storage, task_id, &task)?;
+        TASKS.save(storage, task_id, &task)?;
+        for dependency in &task.depends_on {
+            DEPENDENTS.save(storage, (*dependency, task_id), &())?;
+        }
         config.next_id = TaskId::new(task_id.u64() + 1);
-        CONFIG.save(deps.storage, &config)?;
+        CONFIG.save(storage, &config)?;
+        Ok(task_id)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recurring(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        description: String,
+        timeout: Option<u64>,
+        payload: RequestType,
+        interval_secs: u64,
+        end: ScheduleEnd,
+    ) -> Result<Response, ContractError> {
+        let runs = schedule_runs(&env, interval_secs, &end)?;
+        let (payment, refund) = split_native_payment(deps.storage, &info, runs)?;
+
+        let res = start_schedule(
+            deps,
+            &env,
+            info.sender,
+            payment,
+            description,
+            timeout,
+            payload,
+            interval_secs,
+            runs,
+        )?;
+        Ok(res.add_messages(refund))
+    }
+
+    pub fn create_batch(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tasks: Vec<NewTask>,
+    ) -> Result<Response, ContractError> {
+        let count = check_batch_size(tasks.len())?;
+        let (payment, refund) = split_native_payment(deps.storage, &info, count)?;
+
+        let res = create_tasks(deps, env, info.sender, payment, tasks)?;
+        Ok(res.add_messages(refund))
+    }
+
+    fn create_tasks(
+        deps: DepsMut,
+        env: Env,
+        creator: Addr,
+        payment: Option<Payment>,
+        tasks: Vec<NewTask>,
+    ) -> Result<Response, ContractError> {
+        let mut res = Response::new().add_attribute("action", "create_batch");
+        for task in tasks {
+            let task_id = create_task(
+                deps.storage,
+                deps.api,
+                &env,
+                creator.clone(),
+                payment.clone(),
+                task,
+            )?;
+            res = res.add_attribute("task_id", task_id.to_string());
+        }
+        Ok(res)
+    }
+
+    fn check_batch_size(size: usize) -> Result<u32, ContractError> {
+        if size == 0 || size > MAX_BATCH_SIZE {
+            return Err(ContractError::InvalidBatchSize(MAX_BATCH_SIZE));
+        }
+        Ok(size as u32)
+    }
+
+    /// Splits the native funds evenly between `count` tasks, each of them must pay like a single
+    /// task. Returns the payment of each task, and the refund of the remainder of the split
+    fn split_native_payment(
+        storage: &dyn Storage,
+        info: &MessageInfo,
+        count: u32,
+    ) -> Result<(Option<Payment>, Option<BankMsg>), ContractError> {
+        let non_zero = |coins: Vec<Coin>| -> Vec<Coin> {
+            coins.into_iter().filter(|c| !c.amount.is_zero()).collect()
+        };
+        let (per_task, remainder) = split_funds(&info.funds, count);
+        let mut task_info = info.clone();
+        task_info.funds = non_zero(per_task);
+        let payment = CONFIG
+            .load(storage)?
+            .requestor
+            .check_requestor(storage, &task_info)?;
+
+        let remainder = non_zero(remainder);
+        let refund = (!remainder.is_empty()).then(|| BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: remainder,
+        });
+        Ok((payment, refund))
+    }
+
+    /// Same as `split_native_payment`, for CW20 tokens sent by `creator`
+    fn split_cw20_payment(
+        storage: &dyn Storage,
+        token: &Addr,
+        creator: &Addr,
+        amount: Uint128,
+        count: u32,
+    ) -> Result<(Payment, Option<CosmosMsg>), ContractError> {
+        let count = Uint128::from(count);
+        let per_task = amount / count;
+        let payment = CONFIG
+            .load(storage)?
+            .requestor
+            .check_cw20(token, per_task)?;
+
+        let remainder = payment.with_amount(amount - per_task * count);
+        let refund = match remainder.amount.is_zero() {
+            true => None,
+            false => Some(remainder.transfer_msg(creator)?),
+        };
+        Ok((payment, refund))
+    }
+
+    /// Saves a new schedule and creates its first task
+    #[allow(clippy::too_many_arguments)]
+    fn start_schedule(
+        deps: DepsMut,
+        env: &Env,
+        creator: Addr,
+        payment: Option<Payment>,
+        description: String,
+        timeout: Option<u64>,
+        payload: RequestType,
+        interval: u64,
+        runs: u32,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let timeout = check_timeout(&config.timeout, timeout)?;
+        let schedule_id = NEXT_SCHEDULE_ID.load(deps.storage)?;
+        NEXT_SCHEDULE_ID.save(deps.storage, &(schedule_id + 1))?;
+
+        let mut schedule = Schedule {
+            creator,
+            description,
+            payload,
+            timeout,
+            interval,
+            next_run: env.block.time.seconds(),
+            remaining: runs,
+            payment,
+        };
+        let task_id = run_schedule(deps.storage, deps.api, env, &mut schedule)?;
+        save_schedule(deps.storage, schedule_id, &schedule)?;
 
         let res = Response::new()
-            .add_attribute("action", "create")
+            .add_attribute("action", "create_recurring")
+            .add_attribute("schedule_id", schedule_id.to_string())
             .add_attribute("task_id", task_id.to_string());
         Ok(res)
     }
 
+    /// Creates the next task of the schedule
+    fn run_schedule(
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        env: &Env,
+        schedule: &mut Schedule,
+    ) -> Result<TaskId, ContractError> {
+        let task = NewTask {
+            description: schedule.description.clone(),
+            timeout: Some(schedule.timeout),
+            payload: schedule.payload.clone(),
+            depends_on: vec![],
+            callback: None,
+            verifier: None,
+        };
+        let task_id = create_task(
+            storage,
+            api,
+            env,
+            schedule.creator.clone(),
+            schedule.payment.clone(),
+            task,
+        )?;
+        schedule.remaining -= 1;
+        schedule.next_run += schedule.interval;
+        Ok(task_id)
+    }
+
+    /// Queues the schedule for its next run, or removes it once all the tasks are created
+    fn save_schedule(
+        storage: &mut dyn Storage,
+        schedule_id: u64,
+        schedule: &Schedule,
+    ) -> StdResult<()> {
+        if schedule.remaining == 0 {
+            SCHEDULES.remove(storage, schedule_id);
+        } else {
+            SCHEDULES.save(storage, schedule_id, schedule)?;
+            SCHEDULE_QUEUE.save(storage, (schedule.next_run, schedule_id), &())?;
+        }
+        Ok(())
+    }
+
+    pub fn cancel_recurring(
+        deps: DepsMut,
+        info: MessageInfo,
+        schedule_id: u64,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let schedule = SCHEDULES.load(deps.storage, schedule_id)?;
+        if info.sender != schedule.creator {
+            return Err(ContractError::Unauthorized);
+        }
+        SCHEDULES.remove(deps.storage, schedule_id);
+        SCHEDULE_QUEUE.remove(deps.storage, (schedule.next_run, schedule_id));
+
+        let mut res = Response::new()
+            .add_attribute("action", "cancel_recurring")
+            .add_attribute("schedule_id", schedule_id.to_string());
+        if let Some(refund) = schedule.prepaid() {
+            res = res
+                .add_attribute("refund", refund.to_string())
+                .add_message(refund.transfer_msg(&schedule.creator)?);
+        }
+        Ok(res)
+    }
+
+    pub fn tick(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let now = env.block.time.seconds();
+        let due = SCHEDULE_QUEUE
+            .keys(
+                deps.storage,
+                None,
+                Some(Bound::inclusive((now, u64::MAX))),
+                Order::Ascending,
+            )
+            .take(MAX_TICK_TASKS)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut res = Response::new().add_attribute("action", "tick");
+        for (next_run, schedule_id) in due {
+            let mut schedule = SCHEDULES.load(deps.storage, schedule_id)?;
+            match run_schedule(deps.storage, deps.api, &env, &mut schedule) {
+                Ok(task_id) => {
+                    SCHEDULE_QUEUE.remove(deps.storage, (next_run, schedule_id));
+                    save_schedule(deps.storage, schedule_id, &schedule)?;
+                    res = res.add_event(
+                        Event::new("recurring_task")
+                            .add_attribute("schedule_id", schedule_id.to_string())
+                            .add_attribute("task_id", task_id.to_string()),
+                    );
+                }
+                // nothing was written for this run. It is retried one interval later, so blocked
+                // schedules don't hold back the ones behind them in the queue. Its payload may not
+                // match a schema set after it started, until the creator cancels it
+                Err(
+                    err @ (ContractError::TooManyOpenTasks(_)
+                    | ContractError::RequestorOpenLimit(_)
+                    | ContractError::RequestorRateLimit { .. }
+                    | ContractError::PayloadNotObject
+                    | ContractError::PayloadTooLarge { .. }
+                    | ContractError::MissingPayloadField(_)
+                    | ContractError::PayloadValueNotAllowed { .. }),
+                ) => {
+                    SCHEDULE_QUEUE.remove(deps.storage, (next_run, schedule_id));
+                    schedule.next_run = now + schedule.interval;
+                    save_schedule(deps.storage, schedule_id, &schedule)?;
+                    res = res.add_event(
+                        Event::new("recurring_task_skipped")
+                            .add_attribute("schedule_id", schedule_id.to_string())
+                            .add_attribute("reason", err.to_string()),
+                    );
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(res)
+    }
+
+    /// Creates a task paid with the CW20 tokens sent along
+    pub fn receive(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        sender: String,
+        amount: Uint128,
+        msg: Binary,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        // the sender of the hook is the token contract
+        let token = info.sender;
+        let creator = deps.api.addr_validate(&sender)?;
+
+        match from_json(msg)? {
+            ReceiveMsg::Create {
+                description,
+                timeout,
+                payload,
+                depends_on,
+                callback,
+                verifier,
+            } => {
+                let payment = CONFIG
+                    .load(deps.storage)?
+                    .requestor
+                    .check_cw20(&token, amount)?;
+                let task = NewTask {
+                    description,
+                    timeout,
+                    payload,
+                    depends_on,
+                    callback,
+                    verifier,
+                };
+                create(deps, env, creator, Some(payment), task)
+            }
+            ReceiveMsg::CreateRecurring {
+                description,
+                timeout,
+                payload,
+                interval_secs,
+                end,
+            } => {
+                let runs = schedule_runs(&env, interval_secs, &end)?;
+                let (payment, refund) =
+                    split_cw20_payment(deps.storage, &token, &creator, amount, runs)?;
+
+                let res = start_schedule(
+                    deps,
+                    &env,
+                    creator,
+                    Some(payment),
+                    description,
+                    timeout,
+                    payload,
+                    interval_secs,
+                    runs,
+                )?;
+                Ok(res.add_messages(refund))
+            }
+            ReceiveMsg::CreateBatch { tasks } => {
+                let count = check_batch_size(tasks.len())?;
+                let (payment, refund) =
+                    split_cw20_payment(deps.storage, &token, &creator, amount, count)?;
+
+                let res = create_tasks(deps, env, creator, Some(payment), tasks)?;
+                Ok(res.add_messages(refund))
+            }
+        }
+    }
+
     pub fn complete(
         deps: DepsMut,
         env: Env,
@@ -125,18 +758,156 @@ mod execute {
         nonpayable(&info)?;
 
         let config = CONFIG.load(deps.storage)?;
-        if info.sender != config.verifier {
-            return Err(ContractError::Unauthorized {});
+        let res = Response::new().add_attribute("action", "completed");
+        complete_task(deps, &env, &config, &info.sender, res, task_id, response)
+    }
+
+    pub fn complete_batch(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        results: Vec<TaskResult>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        check_batch_size(results.len())?;
+
+        let mut res = Response::new().add_attribute("action", "complete_batch");
+        for TaskResult { task_id, response } in results {
+            res = complete_task(
+                deps.branch(),
+                &env,
+                &config,
+                &info.sender,
+                res,
+                task_id,
+                response,
+            )?;
         }
+        Ok(res)
+    }
 
+    /// Only the verifier of the task can complete it, even if it was removed from the verifiers
+    /// since the task was created
+    fn complete_task(
+        deps: DepsMut,
+        env: &Env,
+        config: &Config,
+        sender: &Addr,
+        res: Response,
+        task_id: TaskId,
+        response: ResponseType,
+    ) -> Result<Response, ContractError> {
         // ensures it is open and not expired, then store response
         let mut task = TASKS.load(deps.storage, task_id)?;
-        task.complete(&env, response)?;
+        if *sender != task.verifier {
+            return Err(ContractError::Unauthorized {});
+        }
+        task.complete(env, response)?;
         TASKS.save(deps.storage, task_id, &task)?;
+        track_closed(deps.storage, &task.creator)?;
 
-        let res = Response::new()
-            .add_attribute("action", "completed")
-            .add_attribute("task_id", task_id.to_string());
+        let mut res = res.add_attribute("task_id", task_id.to_string());
+        for opened in open_dependents(deps.storage, env, task_id)? {
+            res = res.add_attribute("opened_task_id", opened.to_string());
+        }
+
+        // the whole payment is earned
+        let res = settle_payment(deps, config, res, &task, Decimal::zero())?;
+        let result = task
+            .result
+            .clone()
+            .ok_or(ContractError::MissingResultCompleted { id: task_id })?;
+        let callback = notify(
+            &task,
+            config,
+            task_id,
+            CallbackMsg::TaskCompleted { task_id, result },
+        )?;
+        Ok(res.add_submessages(callback))
+    }
+
+    /// Sends the message to the callback contract of the task, if any. It can't use more than
+    /// the configured gas, and its failure is caught in `reply`
+    fn notify(
+        task: &Task,
+        config: &Config,
+        task_id: TaskId,
+        msg: CallbackMsg,
+    ) -> StdResult<Option<SubMsg>> {
+        let Some(callback) = &task.callback else {
+            return Ok(None);
+        };
+        let msg = WasmMsg::Execute {
+            contract_addr: callback.to_string(),
+            msg: to_json_binary(&msg)?,
+            funds: vec![],
+        };
+        let msg = SubMsg::reply_on_error(msg, CALLBACK_REPLY_ID)
+            .with_gas_limit(config.callback_gas_limit)
+            .with_payload(to_json_binary(&task_id)?);
+        Ok(Some(msg))
+    }
+
+    /// Opens the pending tasks that were only waiting for this one
+    fn open_dependents(
+        storage: &mut dyn Storage,
+        env: &Env,
+        task_id: TaskId,
+    ) -> Result<Vec<TaskId>, ContractError> {
+        let dependents = DEPENDENTS
+            .prefix(task_id)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut opened = vec![];
+        for id in dependents {
+            let mut task = TASKS.load(storage, id)?;
+            if task.status != (Status::Pending {}) {
+                continue;
+            }
+            // the others may still be running, or fail later on
+            if let Dependencies::Completed(results) =
+                check_dependencies(storage, env, &task.depends_on)?
+            {
+                task.open(env, results)?;
+                TASKS.save(storage, id, &task)?;
+                opened.push(id);
+            }
+        }
+        Ok(opened)
+    }
+
+    /// Cancels the pending tasks depending on a task that failed, and the ones depending on
+    /// them. They are fully refunded, as they never opened
+    fn cancel_dependents(
+        mut deps: DepsMut,
+        env: &Env,
+        config: &Config,
+        mut res: Response,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
+        let mut failed = vec![task_id];
+        while let Some(failed_id) = failed.pop() {
+            let dependents = DEPENDENTS
+                .prefix(failed_id)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            for id in dependents {
+                let mut task = TASKS.load(deps.storage, id)?;
+                if task.status != (Status::Pending {}) {
+                    continue;
+                }
+                task.cancel(env)?;
+                TASKS.save(deps.storage, id, &task)?;
+                track_closed(deps.storage, &task.creator)?;
+
+                res = res.add_attribute("cancelled_task_id", id.to_string());
+                res = settle_payment(deps.branch(), config, res, &task, Decimal::one())?;
+                failed.push(id);
+            }
+        }
         Ok(res)
     }
 
@@ -148,39 +919,524 @@ mod execute {
     ) -> Result<Response, ContractError> {
         nonpayable(&info)?;
 
+        let config = CONFIG.load(deps.storage)?;
+        let res = Response::new()
+            .add_attribute("action", "expired")
+            .add_attribute("task_id", task_id.to_string());
+        expire_task(deps, &env, &config, res, task_id)
+    }
+
+    pub fn timeout_batch(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        // open tasks by expiration, as long as they are overdue
+        let limit = limit.unwrap_or(MAX_TIMEOUT_BATCH).min(MAX_TIMEOUT_BATCH) as usize;
+        let overdue = TASKS
+            .idx
+            .expiration
+            .sub_prefix(Status::Open {}.as_str())
+            .range(deps.storage, None, None, Order::Ascending)
+            .take_while(|r| {
+                r.as_ref()
+                    .map_or(true, |(_, task)| task.timing.is_expired(&env))
+            })
+            .take(limit)
+            .map(|r| r.map(|(id, _)| id))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let mut res = Response::new()
+            .add_attribute("action", "timeout_batch")
+            .add_attribute("expired_count", overdue.len().to_string());
+        for task_id in overdue {
+            res = res.add_event(
+                Event::new("task_expired").add_attribute("task_id", task_id.to_string()),
+            );
+            res = expire_task(deps.branch(), &env, &config, res, task_id)?;
+        }
+        Ok(res)
+    }
+
+    fn expire_task(
+        mut deps: DepsMut,
+        env: &Env,
+        config: &Config,
+        res: Response,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
         // ensures it is open and expired
         let mut task = TASKS.load(deps.storage, task_id)?;
-        task.expire(&env)?;
+        task.expire(env)?;
+        TASKS.save(deps.storage, task_id, &task)?;
+        track_closed(deps.storage, &task.creator)?;
+
+        // the operators failed to deliver, so the creator gets (some of) the payment back
+        let res = cancel_dependents(deps.branch(), env, config, res, task_id)?;
+        let res = settle_payment(deps, config, res, &task, config.refund.timeout)?;
+        let callback = notify(&task, config, task_id, CallbackMsg::TaskExpired { task_id })?;
+        Ok(res.add_submessages(callback))
+    }
+
+    pub fn cancel(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let mut task = TASKS.load(deps.storage, task_id)?;
+        if info.sender != task.creator {
+            return Err(ContractError::Unauthorized);
+        }
+        // ensures it is open or pending, and not expired
+        task.cancel(&env)?;
         TASKS.save(deps.storage, task_id, &task)?;
+        track_closed(deps.storage, &task.creator)?;
 
+        let config = CONFIG.load(deps.storage)?;
         let res = Response::new()
-            .add_attribute("action", "expired")
+            .add_attribute("action", "cancelled")
             .add_attribute("task_id", task_id.to_string());
+        let res = cancel_dependents(deps.branch(), &env, &config, res, task_id)?;
+        settle_payment(deps, &config, res, &task, config.refund.cancel)
+    }
+
+    pub fn withdraw_fees(
+        deps: DepsMut,
+        info: MessageInfo,
+        amount: Payment,
+        to: Option<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized);
+        }
+        let to = match to {
+            Some(to) => deps.api.addr_validate(&to)?,
+            None => config.owner,
+        };
+
+        let key = denom_key(&amount.denom);
+        let collected = COLLECTED_FEES
+            .may_load(deps.storage, key)?
+            .unwrap_or_default();
+        let remaining = collected
+            .checked_sub(amount.amount)
+            .map_err(|_| ContractError::InsufficientFees(amount.with_amount(collected)))?;
+        if remaining.is_zero() {
+            COLLECTED_FEES.remove(deps.storage, key);
+        } else {
+            COLLECTED_FEES.save(deps.storage, key, &remaining)?;
+        }
+
+        let res = Response::new()
+            .add_attribute("action", "withdraw_fees")
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("to", to.as_str())
+            .add_message(amount.transfer_msg(&to)?);
+        Ok(res)
+    }
+
+    pub fn update_callback_gas_limit(
+        deps: DepsMut,
+        info: MessageInfo,
+        gas_limit: u64,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let mut config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized);
+        }
+        config.callback_gas_limit = gas_limit;
+        CONFIG.save(deps.storage, &config)?;
+
+        let res = Response::new()
+            .add_attribute("action", "update_callback_gas_limit")
+            .add_attribute("gas_limit", gas_limit.to_string());
+        Ok(res)
+    }
+
+    pub fn update_payload_schema(
+        deps: DepsMut,
+        info: MessageInfo,
+        schema: Option<PayloadSchema>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let mut config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized);
+        }
+        // only new tasks are checked against it
+        config.payload_schema = schema.map(validate_payload_schema).transpose()?;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new().add_attribute("action", "update_payload_schema"))
+    }
+
+    pub fn update_allow_list(
+        deps: DepsMut,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized);
+        }
+        if !matches!(config.requestor, RequestorConfig::AllowList { .. }) {
+            return Err(ContractError::NoAllowList);
+        }
+
+        for addr in &add {
+            let addr = deps.api.addr_validate(addr)?;
+            ALLOWED_REQUESTORS.save(deps.storage, &addr, &())?;
+        }
+        for addr in &remove {
+            let addr = deps.api.addr_validate(addr)?;
+            ALLOWED_REQUESTORS.remove(deps.storage, &addr);
+        }
+
+        let res = Response::new()
+            .add_attribute("action", "update_allow_list")
+            .add_attribute("added", add.len().to_string())
+            .add_attribute("removed", remove.len().to_string());
+        Ok(res)
+    }
+
+    pub fn update_verifiers(
+        deps: DepsMut,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized);
+        }
+
+        for addr in &add {
+            let addr = deps.api.addr_validate(addr)?;
+            VERIFIERS.save(deps.storage, &addr, &())?;
+        }
+        // the tasks already created keep their verifier, so it can still complete them
+        for addr in &remove {
+            let addr = deps.api.addr_validate(addr)?;
+            if addr == config.verifier {
+                return Err(ContractError::RemoveDefaultVerifier);
+            }
+            VERIFIERS.remove(deps.storage, &addr);
+        }
+
+        let res = Response::new()
+            .add_attribute("action", "update_verifiers")
+            .add_attribute("added", add.len().to_string())
+            .add_attribute("removed", remove.len().to_string());
+        Ok(res)
+    }
+
+    pub fn set_default_verifier(
+        deps: DepsMut,
+        info: MessageInfo,
+        verifier: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let mut config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let verifier = deps.api.addr_validate(&verifier)?;
+        VERIFIERS.save(deps.storage, &verifier, &())?;
+        let previous = std::mem::replace(&mut config.verifier, verifier);
+        CONFIG.save(deps.storage, &config)?;
+
+        let res = Response::new()
+            .add_attribute("action", "set_default_verifier")
+            .add_attribute("previous", previous)
+            .add_attribute("verifier", &config.verifier);
+        Ok(res)
+    }
+
+    /// Refunds the given share of the task payment to the creator, and collects the rest as fees
+    fn settle_payment(
+        deps: DepsMut,
+        config: &Config,
+        mut res: Response,
+        task: &Task,
+        refund_share: Decimal,
+    ) -> Result<Response, ContractError> {
+        let (refund, fees) = task.split_payment(refund_share);
+        if let Some(refund) = refund {
+            res = res
+                .add_attribute("refund", refund.to_string())
+                .add_message(refund.transfer_msg(&task.creator)?);
+        }
+        if let Some(fees) = fees {
+            res = res.add_attribute("fees", fees.to_string());
+            if let Some(rewards) = config.collect_fees(deps.storage, fees)? {
+                res = res.add_message(rewards);
+            }
+        }
         Ok(res)
     }
 }
 
 mod query {
-    use cw_storage_plus::Bound;
-    use lavs_apis::{id::TaskId, tasks::ConfigResponse};
+    use cosmwasm_std::{Addr, Order, StdResult};
+    use cw_storage_plus::{Bound, PrefixBound};
+    use lavs_apis::id::TaskId;
 
     use crate::msg::{
-        CompletedTaskOverview, ListCompletedResponse, ListOpenResponse, OpenTaskOverview,
-        TaskResponse, TaskStatusResponse,
+        AllowListResponse, CollectedFeesResponse, CompletedTaskOverview, ConfigResponse,
+        ExpiredTaskOverview, ListCompletedResponse, ListExpiredResponse, ListOpenResponse,
+        ListSchedulesResponse, ListTasksResponse, OpenTaskOverview, PayloadSchemaResponse, Payment,
+        RequestorUsageResponse, ScheduleResponse, TaskGraphResponse, TaskNode, TaskResponse,
+        TaskStatusResponse, VerifiersResponse,
     };
+    use crate::state::{parse_denom_key, Schedule, COLLECTED_FEES, DEPENDENTS, REQUESTOR_USAGE};
 
     use super::*;
 
     pub fn task(deps: Deps, env: Env, id: TaskId) -> Result<TaskResponse, ContractError> {
         let task = TASKS.load(deps.storage, id)?;
-        let status = task.validate_status(&env);
+        Ok(task_response(&env, id, task))
+    }
 
-        let r = TaskResponse {
+    fn task_response(env: &Env, id: TaskId, task: Task) -> TaskResponse {
+        let status = task.validate_status(env);
+        TaskResponse {
             id,
             description: task.description,
             status,
             payload: task.payload,
             result: task.result,
+            creator: task.creator.into_string(),
+            payment: task.payment,
+            priority: task.priority,
+            depends_on: task.depends_on,
+            created_at: task.timing.created_at,
+            callback: task.callback.map(Addr::into_string),
+            verifier: task.verifier.into_string(),
+        }
+    }
+
+    /// Whether the task is in that status, as reported by `Task`
+    fn has_status(env: &Env, task: &Task, status: &Option<Status>) -> bool {
+        match status {
+            None => true,
+            Some(status) => task.validate_status(env).as_str() == status.as_str(),
+        }
+    }
+
+    /// Turns up to `limit + 1` tasks into a page, the extra one only tells there is a next page
+    fn tasks_page(env: &Env, mut tasks: Vec<(TaskId, Task)>, limit: usize) -> ListTasksResponse {
+        let next_cursor = match tasks.len() > limit {
+            true => {
+                tasks.truncate(limit);
+                tasks.last().map(|(id, _)| *id)
+            }
+            false => None,
+        };
+        let tasks = tasks
+            .into_iter()
+            .map(|(id, task)| task_response(env, id, task))
+            .collect();
+        ListTasksResponse { tasks, next_cursor }
+    }
+
+    /// Pages through the tasks of each range that have `status`, looking at no more than
+    /// `MAX_SCANNED_TASKS` of each. When the scan stops before finding a full page, the cursor is
+    /// the last task looked at, so the page may have fewer tasks and still have a next one
+    fn filtered_tasks_page<'a>(
+        env: &Env,
+        ranges: Vec<Box<dyn Iterator<Item = StdResult<(TaskId, Task)>> + 'a>>,
+        status: &Option<Status>,
+        limit: usize,
+        order: Order,
+    ) -> StdResult<ListTasksResponse> {
+        let mut tasks = vec![];
+        // where the first range to stop stopped, the others may have skipped tasks past it
+        let mut stop: Option<TaskId> = None;
+        for range in ranges {
+            let mut found = 0;
+            for (scanned, r) in range.enumerate() {
+                let (id, task) = r?;
+                if has_status(env, &task, status) {
+                    tasks.push((id, task));
+                    found += 1;
+                }
+                // one more than the page, to know if there is a next one
+                if found > limit || scanned + 1 == MAX_SCANNED_TASKS {
+                    stop = Some(match (stop, order) {
+                        (None, _) => id,
+                        (Some(stop), Order::Ascending) => stop.min(id),
+                        (Some(stop), Order::Descending) => stop.max(id),
+                    });
+                    break;
+                }
+            }
+        }
+
+        match order {
+            Order::Ascending => tasks.sort_by(|(a, _), (b, _)| a.cmp(b)),
+            Order::Descending => tasks.sort_by(|(a, _), (b, _)| b.cmp(a)),
+        }
+        if let Some(stop) = stop {
+            tasks.retain(|(id, _)| match order {
+                Order::Ascending => *id <= stop,
+                Order::Descending => *id >= stop,
+            });
+        }
+        let mut page = tasks_page(env, tasks, limit);
+        if page.next_cursor.is_none() {
+            page.next_cursor = stop;
+        }
+        Ok(page)
+    }
+
+    pub fn list_tasks(
+        deps: Deps,
+        env: Env,
+        status: Option<Status>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    ) -> Result<ListTasksResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let order = order.unwrap_or(Order::Descending);
+        let (min, max) = match order {
+            Order::Ascending => (start_after.map(Bound::exclusive), None),
+            Order::Descending => (None, start_after.map(Bound::exclusive)),
+        };
+
+        // expired tasks may still be stored as open, so those are looked up in both
+        let stored = match &status {
+            None => vec![None],
+            Some(Status::Expired {}) => vec![Some(Status::Expired {}), Some(Status::Open {})],
+            Some(status) => vec![Some(status.clone())],
+        };
+        let ranges = stored
+            .into_iter()
+            .map(|stored| match stored {
+                None => TASKS.range(deps.storage, min.clone(), max.clone(), order),
+                Some(stored) => TASKS.idx.status.prefix(stored.as_str()).range(
+                    deps.storage,
+                    min.clone(),
+                    max.clone(),
+                    order,
+                ),
+            })
+            .collect();
+        Ok(filtered_tasks_page(&env, ranges, &status, limit, order)?)
+    }
+
+    pub fn tasks_by_requestor(
+        deps: Deps,
+        env: Env,
+        address: String,
+        status: Option<Status>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    ) -> Result<ListTasksResponse, ContractError> {
+        let address = deps.api.addr_validate(&address)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let tasks = TASKS.idx.creator.prefix(address).range(
+            deps.storage,
+            None,
+            start_after.map(Bound::exclusive),
+            Order::Descending,
+        );
+        Ok(filtered_tasks_page(
+            &env,
+            vec![tasks],
+            &status,
+            limit,
+            Order::Descending,
+        )?)
+    }
+
+    pub fn tasks_created_between(
+        deps: Deps,
+        env: Env,
+        from: u64,
+        to: u64,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    ) -> Result<ListTasksResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        // the index is ordered by (creation, id), so resume from the creation of the last task,
+        // past the ones created at the same time before it
+        let start_after = start_after
+            .map(|id| -> StdResult<_> {
+                let task = TASKS.load(deps.storage, id)?;
+                Ok((task.timing.created_at, id))
+            })
+            .transpose()?;
+        let from = start_after.map_or(from, |(created_at, _)| created_at.max(from));
+
+        let tasks = TASKS
+            .idx
+            .created
+            .prefix_range(
+                deps.storage,
+                Some(PrefixBound::inclusive(from)),
+                Some(PrefixBound::inclusive(to)),
+                Order::Ascending,
+            )
+            .filter(|r| match (r, start_after) {
+                (Ok((id, task)), Some((created_at, after))) => {
+                    task.timing.created_at != created_at || *id > after
+                }
+                _ => true,
+            })
+            .take(limit + 1)
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(tasks_page(&env, tasks, limit))
+    }
+
+    pub fn task_graph(
+        deps: Deps,
+        env: Env,
+        id: TaskId,
+    ) -> Result<TaskGraphResponse, ContractError> {
+        let task = TASKS.load(deps.storage, id)?;
+        let node = |id: TaskId| -> Result<TaskNode, ContractError> {
+            let status = TASKS.load(deps.storage, id)?.validate_status(&env);
+            Ok(TaskNode { id, status })
+        };
+
+        let depends_on = task
+            .depends_on
+            .iter()
+            .map(|id| node(*id))
+            .collect::<Result<_, _>>()?;
+        let dependents = DEPENDENTS
+            .prefix(id)
+            .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .map(|id| node(id?))
+            .collect::<Result<_, _>>()?;
+
+        let r = TaskGraphResponse {
+            id,
+            status: task.validate_status(&env),
+            depends_on,
+            dependents,
         };
         Ok(r)
     }
@@ -191,7 +1447,7 @@ mod query {
         id: TaskId,
     ) -> Result<TaskStatusResponse, ContractError> {
         let task = TASKS.load(deps.storage, id)?;
-        let status = task.validate_status(&env).into();
+        let status = task.validate_status(&env).try_into()?;
 
         let r = TaskStatusResponse {
             id,
@@ -209,27 +1465,171 @@ mod query {
             requestor: config.requestor.into(),
             timeout: config.timeout,
             verifier: config.verifier.into_string(),
+            refund: config.refund,
+            owner: config.owner.into_string(),
+            fees: config.fees.map(Into::into),
+            limits: config.limits,
+            callback_gas_limit: config.callback_gas_limit,
         };
         Ok(r)
     }
 
-    // TODO: There should probably be a max page limit, but it's left unbound to keep the API simple for now
+    pub fn payload_schema(deps: Deps) -> Result<PayloadSchemaResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        Ok(PayloadSchemaResponse {
+            schema: config.payload_schema,
+        })
+    }
+
+    pub fn schedule(deps: Deps, id: u64) -> Result<ScheduleResponse, ContractError> {
+        let schedule = SCHEDULES.load(deps.storage, id)?;
+        Ok(schedule_response(id, schedule))
+    }
+
+    pub fn list_schedules(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<ListSchedulesResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let schedules = SCHEDULES
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(limit)
+            .map(|r| r.map(|(id, schedule)| schedule_response(id, schedule)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ListSchedulesResponse { schedules })
+    }
+
+    fn schedule_response(id: u64, schedule: Schedule) -> ScheduleResponse {
+        ScheduleResponse {
+            id,
+            creator: schedule.creator.into_string(),
+            description: schedule.description,
+            payload: schedule.payload,
+            timeout: schedule.timeout,
+            interval_secs: schedule.interval,
+            next_run: schedule.next_run,
+            remaining: schedule.remaining,
+            payment: schedule.payment,
+        }
+    }
+
+    pub fn requestor_usage(
+        deps: Deps,
+        env: Env,
+        address: String,
+    ) -> Result<RequestorUsageResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let address = deps.api.addr_validate(&address)?;
+        let usage = REQUESTOR_USAGE
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default();
+
+        let window_ends = usage.window_ends(&env, &config.limits);
+        let r = RequestorUsageResponse {
+            open_tasks: usage.open_tasks,
+            window_tasks: if window_ends.is_some() {
+                usage.window_tasks
+            } else {
+                0
+            },
+            window_ends,
+        };
+        Ok(r)
+    }
+
+    pub fn collected_fees(deps: Deps) -> Result<CollectedFeesResponse, ContractError> {
+        let fees = COLLECTED_FEES
+            .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .map(|r| {
+                r.map(|((kind, denom), amount)| Payment {
+                    denom: parse_denom_key(kind, denom),
+                    amount,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CollectedFeesResponse { fees })
+    }
+
+    pub fn allow_list(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<AllowListResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+
+        let requestors = ALLOWED_REQUESTORS
+            .keys(
+                deps.storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(limit)
+            .map(|r| r.map(Addr::into_string))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(AllowListResponse { requestors })
+    }
+
+    pub fn verifiers(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<VerifiersResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+
+        let verifiers = VERIFIERS
+            .keys(
+                deps.storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(limit)
+            .map(|r| r.map(Addr::into_string))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(VerifiersResponse {
+            default: config.verifier.into_string(),
+            verifiers,
+        })
+    }
+
     pub fn list_open(
         deps: Deps,
         env: Env,
         start_after: Option<TaskId>,
         limit: Option<u32>,
     ) -> Result<ListOpenResponse, ContractError> {
-        let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        // the index is ordered by (priority, id), so resume after the priority of the last task.
+        // Listing from the highest, that is the upper bound
+        let start_after = start_after
+            .map(|id| -> StdResult<_> {
+                let task = TASKS.load(deps.storage, id)?;
+                Ok(Bound::exclusive((task.priority.u128(), id)))
+            })
+            .transpose()?;
 
         let open = TASKS
             .idx
-            .status
-            .prefix(Status::Open {}.as_str())
+            .priority
+            .sub_prefix(Status::Open {}.as_str())
             .range(
                 deps.storage,
                 None,
-                start_after.map(Bound::exclusive),
+                start_after,
                 cosmwasm_std::Order::Descending,
             )
             .filter_map(|r| match r {
@@ -239,12 +1639,14 @@ mod query {
                         payload,
                         status: Status::Open {},
                         timing,
+                        priority,
                         ..
                     },
                 )) if timing.expires_at > env.block.time.seconds() => Some(Ok(OpenTaskOverview {
                     id,
                     expires: timing.expires_at,
                     payload,
+                    priority,
                 })),
                 Ok(_) => None,
                 Err(e) => Some(Err(e)),
@@ -255,13 +1657,78 @@ mod query {
         Ok(ListOpenResponse { tasks: open })
     }
 
+    pub fn list_expired(
+        deps: Deps,
+        env: Env,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    ) -> Result<ListExpiredResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        // the index is ordered by (expiration, id), so resume after the expiration of the last
+        // task. Once done with the marked ones, it goes on with the open ones
+        let (marked_limit, marked_start, open_start) = match start_after {
+            None => (limit, None, None),
+            Some(id) => {
+                let task = TASKS.load(deps.storage, id)?;
+                let bound = Bound::exclusive((task.timing.expires_at, id));
+                match task.status {
+                    Status::Expired {} => (limit, Some(bound), None),
+                    _ => (0, None, Some(bound)),
+                }
+            }
+        };
+
+        let marked = TASKS
+            .idx
+            .expiration
+            .sub_prefix(Status::Expired {}.as_str())
+            .range(
+                deps.storage,
+                marked_start,
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(marked_limit)
+            .map(|r| r.map(|(id, task)| (id, task, true)));
+        let overdue = TASKS
+            .idx
+            .expiration
+            .sub_prefix(Status::Open {}.as_str())
+            .range(
+                deps.storage,
+                open_start,
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take_while(|r| {
+                r.as_ref()
+                    .map_or(true, |(_, task)| task.timing.is_expired(&env))
+            })
+            .map(|r| r.map(|(id, task)| (id, task, false)));
+
+        let tasks = marked
+            .chain(overdue)
+            .take(limit)
+            .map(|r| {
+                r.map(|(id, task, marked)| ExpiredTaskOverview {
+                    id,
+                    expired: task.timing.expires_at,
+                    marked,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(ListExpiredResponse { tasks })
+    }
+
     pub fn list_completed(
         deps: Deps,
         _env: Env,
         start_after: Option<TaskId>,
         limit: Option<u32>,
     ) -> Result<ListCompletedResponse, ContractError> {
-        let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
         let completed = TASKS
             .idx
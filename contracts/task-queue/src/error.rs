@@ -3,6 +3,8 @@ use cw_utils::PaymentError;
 use lavs_apis::id::TaskId;
 use thiserror::Error;
 
+use crate::msg::Payment;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -17,15 +19,57 @@ pub enum ContractError {
     #[error("Timeout Info is invalid")]
     InvalidTimeoutInfo,
 
+    #[error("Refund shares must be between 0 and 1")]
+    InvalidRefundInfo,
+
+    #[error("Reward share must be between 0 and 1")]
+    InvalidFeeInfo,
+
+    #[error("Only {0} of collected fees can be withdrawn")]
+    InsufficientFees(Payment),
+
     #[error("Timeout is shorter than allowed minimum {0}")]
     TimeoutTooShort(u64),
 
     #[error("Timeout is longer than allowed maximum {0}")]
     TimeoutTooLong(u64),
 
+    #[error("Rate limits must be greater than zero")]
+    InvalidRateLimits,
+
+    #[error("The queue already has the maximum of {0} open tasks")]
+    TooManyOpenTasks(u32),
+
+    #[error("Requestor already has the maximum of {0} open tasks")]
+    RequestorOpenLimit(u32),
+
+    #[error("Requestor can only create {max} tasks every {seconds} seconds")]
+    RequestorRateLimit { max: u32, seconds: u64 },
+
+    #[error("Requestor config is invalid: {0}")]
+    InvalidRequestor(String),
+
     #[error("You need to pay at least {0} {1} to create a task")]
     InsufficientPayment(u128, String),
 
+    #[error("Payment in {0} is not accepted")]
+    UnsupportedDenom(String),
+
+    #[error("Tasks must be paid by sending CW20 {0} to the queue")]
+    Cw20PaymentRequired(String),
+
+    #[error("{0} is not on the requestor allow-list")]
+    NotAllowed(String),
+
+    #[error("The task queue doesn't use an allow-list")]
+    NoAllowList,
+
+    #[error("A batch must have between 1 and {0} tasks")]
+    InvalidBatchSize(usize),
+
+    #[error("Schedule is invalid: {0}")]
+    InvalidSchedule(String),
+
     #[error("Task is completed")]
     TaskCompleted,
 
@@ -35,6 +79,46 @@ pub enum ContractError {
     #[error("Task is not yet expired")]
     TaskNotExpired,
 
+    #[error("Task is cancelled")]
+    TaskCancelled,
+
+    #[error("Task is waiting for the tasks it depends on")]
+    TaskPending,
+
+    #[error("Task is not pending")]
+    TaskNotPending,
+
+    #[error("Invalid dependency: {0}")]
+    InvalidDependency(String),
+
+    #[error("Unknown reply id: {0}")]
+    UnknownReplyId(u64),
+
+    #[error("{0} is not one of the verifiers")]
+    NotVerifier(String),
+
+    #[error("The default verifier can't be removed")]
+    RemoveDefaultVerifier,
+
+    #[error("Invalid payload schema: {0}")]
+    InvalidPayloadSchema(String),
+
+    #[error("Payload must be a JSON object")]
+    PayloadNotObject,
+
+    #[error("Payload is {size} bytes, at most {max} are allowed")]
+    PayloadTooLarge { size: usize, max: u32 },
+
+    #[error("Payload is missing the field `{0}`")]
+    MissingPayloadField(String),
+
+    #[error("Payload field `{field}` is {value}, it must be one of {allowed}")]
+    PayloadValueNotAllowed {
+        field: String,
+        value: String,
+        allowed: String,
+    },
+
     #[error("Missing result for completed task {id}")]
     MissingResultCompleted { id: TaskId },
 }
@@ -1,2 +1,696 @@
-// TODO: explicitly import the types we want?
-pub use lavs_apis::tasks::*;
+use std::collections::BTreeMap;
+
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Env, Order, StdResult,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Denom};
+use lavs_apis::id::TaskId;
+
+use crate::error::ContractError;
+
+// The interface shared with the verifiers, which must stay compatible
+pub use lavs_apis::interfaces::tasks::{
+    ResponseType, TaskExecuteMsg, TaskExecuteMsgFns, TaskQueryMsg, TaskQueryMsgFns, TaskStatus,
+    TaskStatusResponse,
+};
+
+/// The payload of a task, interpreted by the wasmatic component
+pub type RequestType = serde_json::Value;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub requestor: Requestor,
+    pub timeout: TimeoutInfo,
+    /// Default verifier, completing the tasks that don't choose one
+    pub verifier: String,
+    /// Other verifiers the tasks can choose at creation
+    #[serde(default)]
+    pub verifiers: Vec<String>,
+    /// How much of the payment is returned to the creator, full refunds if not set
+    pub refund: Option<RefundInfo>,
+    /// Can withdraw the collected fees, defaults to the instantiator
+    pub owner: Option<String>,
+    /// How the fees are split, everything goes to the treasury if not set
+    pub fees: Option<FeeInfo>,
+    /// Initial members of the allow-list, used with `Requestor::AllowList`
+    #[serde(default)]
+    pub allowed_requestors: Vec<String>,
+    /// Caps on the number of tasks, no limits if not set
+    pub limits: Option<RateLimits>,
+    /// Gas limit of each callback, 200k if not set
+    pub callback_gas_limit: Option<u64>,
+    /// Format of the task payloads, anything goes if not set
+    pub payload_schema: Option<PayloadSchema>,
+}
+
+#[cw_serde]
+pub enum Requestor {
+    Fixed(String),
+    OpenPayment(Coin),
+    /// Anyone paying in one of these native denoms, each with its own price
+    MultiDenomPayment(Vec<Coin>),
+    /// Anyone paying at least `amount` of the CW20 `token`, sent with the token's `Send`
+    Cw20Payment {
+        token: String,
+        amount: Uint128,
+    },
+    /// Only the addresses on the allow-list managed by the owner. They must also pay in one of
+    /// these native denoms, unless it is empty
+    AllowList {
+        payment: Vec<Coin>,
+    },
+}
+
+#[cw_serde]
+pub struct TimeoutInfo {
+    pub default: u64,
+    pub minimum: Option<u64>,
+    pub maximum: Option<u64>,
+}
+
+impl TimeoutInfo {
+    pub fn new(default: u64) -> Self {
+        TimeoutInfo {
+            default,
+            minimum: None,
+            maximum: None,
+        }
+    }
+}
+
+/// Shares of the task payment returned to the creator, between 0 and 1
+#[cw_serde]
+pub struct RefundInfo {
+    /// When the creator cancels an open task
+    pub cancel: Decimal,
+    /// When a task expires without a result
+    pub timeout: Decimal,
+}
+
+impl Default for RefundInfo {
+    fn default() -> Self {
+        RefundInfo {
+            cancel: Decimal::one(),
+            timeout: Decimal::one(),
+        }
+    }
+}
+
+/// Split of the fees earned by the queue. They are earned when a task is completed, or with
+/// the part of the payment that is not refunded
+#[cw_serde]
+pub struct FeeInfo {
+    /// Share of the fees sent right away to the reward pool, between 0 and 1.
+    /// The rest stays in the treasury until the owner withdraws it
+    pub reward_share: Decimal,
+    /// Rewards the verifier or the operators
+    pub reward_pool: String,
+}
+
+/// A payment in native or CW20 tokens
+#[cw_serde]
+pub struct Payment {
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+impl Payment {
+    pub fn cw20(token: Addr, amount: impl Into<Uint128>) -> Self {
+        Payment {
+            denom: Denom::Cw20(token),
+            amount: amount.into(),
+        }
+    }
+
+    /// The same denom with another amount
+    pub fn with_amount(&self, amount: Uint128) -> Self {
+        Payment {
+            denom: self.denom.clone(),
+            amount,
+        }
+    }
+
+    /// Message sending this payment from the contract to `to`
+    pub fn transfer_msg(&self, to: &Addr) -> StdResult<CosmosMsg> {
+        let msg = match &self.denom {
+            Denom::Native(denom) => BankMsg::Send {
+                to_address: to.to_string(),
+                amount: vec![Coin::new(self.amount, denom)],
+            }
+            .into(),
+            Denom::Cw20(token) => WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: to.to_string(),
+                    amount: self.amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        };
+        Ok(msg)
+    }
+}
+
+impl From<Coin> for Payment {
+    fn from(coin: Coin) -> Self {
+        Payment {
+            denom: Denom::Native(coin.denom),
+            amount: coin.amount,
+        }
+    }
+}
+
+impl std::fmt::Display for Payment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.denom {
+            Denom::Native(denom) => write!(f, "{}{}", self.amount, denom),
+            Denom::Cw20(token) => write!(f, "{} of CW20 {}", self.amount, token),
+        }
+    }
+}
+
+/// Limits on task creation, so the queue can't be spammed. Tasks count as open until they are
+/// completed, cancelled or marked as expired with `Timeout`
+#[cw_serde]
+#[derive(Default)]
+pub struct RateLimits {
+    /// Open tasks in the whole queue
+    pub max_open_tasks: Option<u32>,
+    /// Open tasks created by a single requestor
+    pub max_open_per_requestor: Option<u32>,
+    /// Tasks a single requestor can create within a time window
+    pub window: Option<WindowLimit>,
+}
+
+#[cw_serde]
+pub struct WindowLimit {
+    pub max_tasks: u32,
+    /// Length of the window in seconds, starting with the first task created in it
+    pub seconds: u64,
+}
+
+/// What task payloads must look like, checked when tasks are created so the requestors and the
+/// wasmatic components agree on the format. Payloads must then be JSON objects
+#[cw_serde]
+#[derive(Default)]
+pub struct PayloadSchema {
+    /// Fields every payload must have
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Values allowed for some fields when they are set, e.g. the supported `pair`s
+    #[serde(default)]
+    pub allowed_values: BTreeMap<String, Vec<serde_json::Value>>,
+    /// Largest payload in bytes, as serialized JSON
+    pub max_size: Option<u32>,
+}
+
+#[cw_serde]
+#[serde(untagged)]
+pub enum ExecuteMsg {
+    Api(TaskExecuteMsg),
+    Custom(CustomExecuteMsg),
+}
+
+impl From<TaskExecuteMsg> for ExecuteMsg {
+    fn from(msg: TaskExecuteMsg) -> Self {
+        ExecuteMsg::Api(msg)
+    }
+}
+
+impl From<CustomExecuteMsg> for ExecuteMsg {
+    fn from(msg: CustomExecuteMsg) -> Self {
+        ExecuteMsg::Custom(msg)
+    }
+}
+
+#[cw_serde]
+#[derive(cw_orch::ExecuteFns)]
+#[cw_orch(impl_into(ExecuteMsg))]
+#[cw_orch(disable_fields_sorting)]
+pub enum CustomExecuteMsg {
+    /// Creates a new task, paying for it if the requestor config requires it.
+    /// Anything paid above the price is a priority fee, moving the task up in `ListOpen`.
+    /// A task depending on others is pending until they are all completed, see `NewTask`.
+    /// The `callback` contract is notified of the result, see `CallbackMsg`. Only the `verifier`
+    /// can complete it, one of the verifiers of the queue and the default one if not set
+    #[cw_orch(payable)]
+    Create {
+        description: String,
+        timeout: Option<u64>,
+        payload: RequestType,
+        #[serde(default)]
+        depends_on: Vec<TaskId>,
+        callback: Option<String>,
+        verifier: Option<String>,
+    },
+    /// Creates several tasks at once. The funds are split evenly between them and each task must
+    /// cover the price, the remainder of the split is refunded
+    #[cw_orch(payable)]
+    CreateBatch { tasks: Vec<NewTask> },
+    /// Completes several tasks at once, failing if any of them can't be completed (verifier only)
+    CompleteBatch { results: Vec<TaskResult> },
+    /// Registers a schedule creating the same task every `interval_secs`, the first one right away.
+    /// The funds pay for all the runs upfront, split evenly between them. The remainder of the
+    /// split is refunded
+    #[cw_orch(payable)]
+    CreateRecurring {
+        description: String,
+        timeout: Option<u64>,
+        payload: RequestType,
+        interval_secs: u64,
+        end: ScheduleEnd,
+    },
+    /// Stops a schedule and refunds the runs left (creator only)
+    CancelRecurring { schedule_id: u64 },
+    /// Creates the tasks of the schedules that are due, at most one per schedule. Anyone can call it
+    Tick {},
+    /// Marks an expired task as such and refunds the creator
+    Timeout { task_id: TaskId },
+    /// Marks up to `limit` expired tasks as such, the longest overdue first, and refunds their
+    /// creators. Anyone can call it
+    TimeoutBatch { limit: Option<u32> },
+    /// Cancels an open task and refunds the creator (creator only)
+    Cancel { task_id: TaskId },
+    /// Sends collected fees from the treasury, to the owner if `to` is not set (owner only)
+    WithdrawFees { amount: Payment, to: Option<String> },
+    /// Sets the gas limit of the callbacks (owner only)
+    UpdateCallbackGasLimit { gas_limit: u64 },
+    /// Sets the format of the payloads of new tasks, or removes it (owner only)
+    UpdatePayloadSchema { schema: Option<PayloadSchema> },
+    /// Adds and removes verifiers that new tasks can choose (owner only). The tasks already
+    /// created keep theirs, and the default verifier can't be removed
+    UpdateVerifiers {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Makes a verifier the default one for new tasks, adding it to the verifiers if needed
+    /// (owner only). The tasks already created keep theirs
+    SetDefaultVerifier { verifier: String },
+    /// Adds and removes addresses from the requestor allow-list (owner only)
+    UpdateAllowList {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Hook of the CW20 `Send`, same as `Cw20ReceiveMsg`. `msg` must be a `ReceiveMsg`
+    Receive {
+        sender: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+}
+
+/// A task to create
+#[cw_serde]
+pub struct NewTask {
+    pub description: String,
+    pub timeout: Option<u64>,
+    pub payload: RequestType,
+    /// Tasks that must be completed before this one opens. Their results are added to the
+    /// payload, which must then be an object, under `"dependencies"` by task id. The timeout
+    /// starts once the task opens
+    #[serde(default)]
+    pub depends_on: Vec<TaskId>,
+    /// Contract receiving a `CallbackMsg` when the task is completed or expires
+    pub callback: Option<String>,
+    /// One of the verifiers of the queue, which alone can complete the task. The default one if
+    /// not set
+    pub verifier: Option<String>,
+}
+
+/// Sent to the callback contract of a task, which must accept it in its execute messages.
+/// It runs with the configured gas limit, and its failure doesn't revert the task queue
+#[cw_serde]
+pub enum CallbackMsg {
+    TaskCompleted {
+        task_id: TaskId,
+        result: ResponseType,
+    },
+    TaskExpired {
+        task_id: TaskId,
+    },
+}
+
+/// The verified result of a task in a batch
+#[cw_serde]
+pub struct TaskResult {
+    pub task_id: TaskId,
+    pub response: ResponseType,
+}
+
+/// Messages sent along with CW20 tokens
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// Creates a new task paid with the tokens, for `Requestor::Cw20Payment`
+    Create {
+        description: String,
+        timeout: Option<u64>,
+        payload: RequestType,
+        #[serde(default)]
+        depends_on: Vec<TaskId>,
+        callback: Option<String>,
+        verifier: Option<String>,
+    },
+    /// Creates several tasks paid with the tokens, like `CustomExecuteMsg::CreateBatch`
+    CreateBatch { tasks: Vec<NewTask> },
+    /// Registers a schedule prepaid with the tokens, like `CustomExecuteMsg::CreateRecurring`
+    CreateRecurring {
+        description: String,
+        timeout: Option<u64>,
+        payload: RequestType,
+        interval_secs: u64,
+        end: ScheduleEnd,
+    },
+}
+
+/// When a schedule stops creating tasks
+#[cw_serde]
+pub enum ScheduleEnd {
+    /// After this many tasks, including the first one
+    Count(u32),
+    /// No more tasks after this time in UNIX seconds
+    Until(u64),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+#[serde(untagged)]
+pub enum QueryMsg {
+    Api(TaskQueryMsg),
+    Custom(CustomQueryMsg),
+}
+
+impl From<TaskQueryMsg> for QueryMsg {
+    fn from(msg: TaskQueryMsg) -> Self {
+        QueryMsg::Api(msg)
+    }
+}
+
+impl From<CustomQueryMsg> for QueryMsg {
+    fn from(msg: CustomQueryMsg) -> Self {
+        QueryMsg::Custom(msg)
+    }
+}
+
+#[cw_serde]
+#[derive(QueryResponses, cw_orch::QueryFns)]
+#[cw_orch(impl_into(QueryMsg))]
+#[cw_orch(disable_fields_sorting)]
+pub enum CustomQueryMsg {
+    #[returns(TaskResponse)]
+    Task { id: TaskId },
+    /// All tasks by id, newest first unless `order` says otherwise. `status` only keeps the tasks
+    /// in that status (its fields are ignored), as reported by `Task`. Pass `next_cursor` as
+    /// `start_after` to get the next page. With a status, a page may be short or even empty when
+    /// few of the tasks looked at match, and still have a `next_cursor`
+    #[returns(ListTasksResponse)]
+    ListTasks {
+        status: Option<Status>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+    /// Tasks created by an address, newest first. `status` and the pages work as in `ListTasks`
+    #[returns(ListTasksResponse)]
+    TasksByRequestor {
+        address: String,
+        status: Option<Status>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
+    /// Tasks created between two times in UNIX seconds (both included), oldest first
+    #[returns(ListTasksResponse)]
+    TasksCreatedBetween {
+        from: u64,
+        to: u64,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
+    /// Open tasks that are not expired, highest priority first then newest first
+    #[returns(ListOpenResponse)]
+    ListOpen {
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
+    /// Completed tasks, newest first
+    #[returns(ListCompletedResponse)]
+    ListCompleted {
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
+    /// Expired tasks, the ones already marked first, then the open ones past their timeout.
+    /// Both by expiration, oldest first
+    #[returns(ListExpiredResponse)]
+    ListExpired {
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Format of the task payloads, if any
+    #[returns(PayloadSchemaResponse)]
+    PayloadSchema {},
+    /// Fees in the treasury that the owner can withdraw
+    #[returns(CollectedFeesResponse)]
+    CollectedFees {},
+    /// Tasks the given task depends on, and the tasks depending on it
+    #[returns(TaskGraphResponse)]
+    TaskGraph { id: TaskId },
+    #[returns(ScheduleResponse)]
+    Schedule { id: u64 },
+    /// Active schedules, in ascending id order
+    #[returns(ListSchedulesResponse)]
+    ListSchedules {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// How many tasks a requestor has open and created in the current window
+    #[returns(RequestorUsageResponse)]
+    RequestorUsage { address: String },
+    /// Addresses on the requestor allow-list, in ascending order
+    #[returns(AllowListResponse)]
+    AllowList {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Verifiers new tasks can choose, in ascending order
+    #[returns(VerifiersResponse)]
+    Verifiers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct TaskResponse {
+    pub id: TaskId,
+    pub description: String,
+    pub status: Status,
+    pub payload: RequestType,
+    pub result: Option<ResponseType>,
+    /// Address that created the task
+    pub creator: String,
+    /// What the creator paid for the task, if anything
+    pub payment: Option<Payment>,
+    /// Fee paid above the price, in the payment denom
+    pub priority: Uint128,
+    /// Tasks that must be completed before this one opens
+    pub depends_on: Vec<TaskId>,
+    /// Creation in UNIX seconds
+    pub created_at: u64,
+    /// Contract notified of the result
+    pub callback: Option<String>,
+    /// The only one that can complete the task
+    pub verifier: String,
+}
+
+#[cw_serde]
+pub struct TaskGraphResponse {
+    pub id: TaskId,
+    pub status: Status,
+    pub depends_on: Vec<TaskNode>,
+    pub dependents: Vec<TaskNode>,
+}
+
+#[cw_serde]
+pub struct TaskNode {
+    pub id: TaskId,
+    pub status: Status,
+}
+
+#[cw_serde]
+pub struct ListTasksResponse {
+    pub tasks: Vec<TaskResponse>,
+    /// Last task of the page, `None` if there are no more
+    pub next_cursor: Option<TaskId>,
+}
+
+#[cw_serde]
+pub struct ListOpenResponse {
+    pub tasks: Vec<OpenTaskOverview>,
+}
+
+#[cw_serde]
+pub struct OpenTaskOverview {
+    pub id: TaskId,
+    pub expires: u64,
+    pub payload: RequestType,
+    pub priority: Uint128,
+}
+
+#[cw_serde]
+pub struct ListCompletedResponse {
+    pub tasks: Vec<CompletedTaskOverview>,
+}
+
+#[cw_serde]
+pub struct CompletedTaskOverview {
+    pub id: TaskId,
+    pub completed: u64,
+    pub result: ResponseType,
+}
+
+#[cw_serde]
+pub struct ListExpiredResponse {
+    pub tasks: Vec<ExpiredTaskOverview>,
+}
+
+#[cw_serde]
+pub struct ExpiredTaskOverview {
+    pub id: TaskId,
+    pub expired: u64,
+    /// False if it is still stored as open, waiting for a `Timeout`
+    pub marked: bool,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub requestor: Requestor,
+    pub timeout: TimeoutConfig,
+    pub verifier: String,
+    pub refund: RefundInfo,
+    pub owner: String,
+    pub fees: Option<FeeInfo>,
+    pub limits: RateLimits,
+    pub callback_gas_limit: u64,
+}
+
+#[cw_serde]
+pub struct PayloadSchemaResponse {
+    pub schema: Option<PayloadSchema>,
+}
+
+#[cw_serde]
+pub struct CollectedFeesResponse {
+    pub fees: Vec<Payment>,
+}
+
+#[cw_serde]
+pub struct ScheduleResponse {
+    pub id: u64,
+    pub creator: String,
+    pub description: String,
+    pub payload: RequestType,
+    pub timeout: u64,
+    pub interval_secs: u64,
+    /// When the next task can be created in UNIX seconds
+    pub next_run: u64,
+    /// Tasks left to create
+    pub remaining: u32,
+    /// Prepaid for each task
+    pub payment: Option<Payment>,
+}
+
+#[cw_serde]
+pub struct ListSchedulesResponse {
+    pub schedules: Vec<ScheduleResponse>,
+}
+
+#[cw_serde]
+pub struct RequestorUsageResponse {
+    pub open_tasks: u32,
+    /// Tasks created in the current window, zero if there is none
+    pub window_tasks: u32,
+    /// When the current window ends in UNIX seconds, if one is running
+    pub window_ends: Option<u64>,
+}
+
+#[cw_serde]
+pub struct AllowListResponse {
+    pub requestors: Vec<String>,
+}
+
+#[cw_serde]
+pub struct VerifiersResponse {
+    /// Verifier of the tasks that don't choose one
+    pub default: String,
+    pub verifiers: Vec<String>,
+}
+
+#[cw_serde]
+pub struct TimeoutConfig {
+    pub default: u64,
+    pub minimum: u64,
+    pub maximum: u64,
+}
+
+#[cw_serde]
+pub enum Status {
+    /// Waiting for the tasks it depends on
+    Pending {},
+    Open {},
+    Completed {
+        /// Completion time in UNIX seconds
+        completed: u64,
+    },
+    Expired {},
+    Cancelled {},
+}
+
+impl Status {
+    pub fn new() -> Self {
+        Status::Open {}
+    }
+
+    pub fn completed(env: &Env) -> Self {
+        Status::Completed {
+            completed: env.block.time.seconds(),
+        }
+    }
+
+    /// Key of the status index
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Pending {} => "pending",
+            Status::Open {} => "open",
+            Status::Completed { .. } => "completed",
+            Status::Expired {} => "expired",
+            Status::Cancelled {} => "cancelled",
+        }
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryFrom<Status> for TaskStatus {
+    type Error = ContractError;
+
+    /// Pending tasks have no equivalent: they are not expired, and verifiers can't vote on them
+    /// until they open, at a later height
+    fn try_from(status: Status) -> Result<Self, Self::Error> {
+        match status {
+            Status::Pending {} => Err(ContractError::TaskPending),
+            Status::Open {} => Ok(TaskStatus::Open),
+            Status::Completed { .. } => Ok(TaskStatus::Completed),
+            // verifiers only need to know a cancelled task can't be completed anymore
+            Status::Expired {} | Status::Cancelled {} => Ok(TaskStatus::Expired),
+        }
+    }
+}
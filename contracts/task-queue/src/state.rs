@@ -1,23 +1,89 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Deps, Env, MessageInfo, StdError};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
-use cw_utils::must_pay;
+use cosmwasm_std::{
+    to_json_vec, Addr, Coin, CosmosMsg, Decimal, Deps, Env, MessageInfo, StdResult, Storage,
+    Uint128,
+};
+use cw20::Denom;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{must_pay, nonpayable, one_coin};
 
 use lavs_apis::id::TaskId;
-use lavs_apis::tasks::{Requestor, Status, TimeoutConfig};
 
 use crate::error::ContractError;
-use crate::msg::{self, InstantiateMsg, RequestType, ResponseType};
+use crate::msg::{
+    self, FeeInfo, InstantiateMsg, PayloadSchema, Payment, RateLimits, RefundInfo, RequestType,
+    Requestor, ResponseType, ScheduleEnd, Status, TimeoutConfig,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Gas limit of the callbacks when none is configured
+pub const DEFAULT_CALLBACK_GAS_LIMIT: u64 = 200_000;
+
+/// Verifiers that new tasks can choose, including the default one in `Config`
+pub const VERIFIERS: Map<&Addr, ()> = Map::new("verifiers");
+
+/// Fees in the treasury by denom, waiting to be withdrawn by the owner.
+/// Keyed by `denom_key`, so native and CW20 denoms can't collide
+pub const COLLECTED_FEES: Map<(&str, &str), Uint128> = Map::new("collected_fees");
+
+/// Number of open tasks in the whole queue
+pub const OPEN_TASKS: Item<u32> = Item::new("open_tasks");
+
+/// Task counts of each creator, checked against the rate limits
+pub const REQUESTOR_USAGE: Map<&Addr, Usage> = Map::new("requestor_usage");
+
+/// Edges of the dependency graph, as (task, dependent task)
+pub const DEPENDENTS: Map<(TaskId, TaskId), ()> = Map::new("dependents");
+
+/// Recurring tasks by schedule id
+pub const SCHEDULES: Map<u64, Schedule> = Map::new("schedules");
+
+/// Schedules by the time of their next run, so `Tick` finds the due ones
+pub const SCHEDULE_QUEUE: Map<(u64, u64), ()> = Map::new("schedule_queue");
+
+pub const NEXT_SCHEDULE_ID: Item<u64> = Item::new("next_schedule_id");
+
+/// Addresses allowed to create tasks with `Requestor::AllowList`
+pub const ALLOWED_REQUESTORS: Map<&Addr, ()> = Map::new("allowed_requestors");
+
+/// Storage key of a denom in `COLLECTED_FEES`
+pub fn denom_key(denom: &Denom) -> (&'static str, &str) {
+    match denom {
+        Denom::Native(denom) => ("native", denom.as_str()),
+        Denom::Cw20(token) => ("cw20", token.as_str()),
+    }
+}
+
+/// Inverse of `denom_key`, for keys read back from storage
+pub fn parse_denom_key(kind: String, denom: String) -> Denom {
+    match kind.as_str() {
+        "cw20" => Denom::Cw20(Addr::unchecked(denom)),
+        _ => Denom::Native(denom),
+    }
+}
+
 pub struct TaskIndexes<'a> {
     pub status: MultiIndex<'a, &'a str, Task, TaskId>,
+    /// By status then priority, so open tasks can be listed most urgent first
+    pub priority: MultiIndex<'a, (&'a str, u128), Task, TaskId>,
+    /// By status then expiration, so overdue open tasks can be found without scanning them all
+    pub expiration: MultiIndex<'a, (&'a str, u64), Task, TaskId>,
+    pub creator: MultiIndex<'a, Addr, Task, TaskId>,
+    /// By creation time, in UNIX seconds
+    pub created: MultiIndex<'a, u64, Task, TaskId>,
 }
 
 impl<'a> IndexList<Task> for TaskIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Task>> + '_> {
-        Box::new(std::iter::once(&self.status as &dyn Index<Task>))
+        let v: Vec<&dyn Index<Task>> = vec![
+            &self.status,
+            &self.priority,
+            &self.expiration,
+            &self.creator,
+            &self.created,
+        ];
+        Box::new(v.into_iter())
     }
 }
 
@@ -25,6 +91,18 @@ pub const TASKS: IndexedMap<TaskId, Task, TaskIndexes<'static>> = IndexedMap::ne
     "tasks",
     TaskIndexes {
         status: MultiIndex::new(|_, d: &Task| d.status.as_str(), "tasks", "tasks__status"),
+        priority: MultiIndex::new(
+            |_, d: &Task| (d.status.as_str(), d.priority.u128()),
+            "tasks",
+            "tasks__priority",
+        ),
+        expiration: MultiIndex::new(
+            |_, d: &Task| (d.status.as_str(), d.timing.expires_at),
+            "tasks",
+            "tasks__expiration",
+        ),
+        creator: MultiIndex::new(|_, d: &Task| d.creator.clone(), "tasks", "tasks__creator"),
+        created: MultiIndex::new(|_, d: &Task| d.timing.created_at, "tasks", "tasks__created"),
     },
 );
 
@@ -34,44 +112,158 @@ pub struct Config {
     pub requestor: RequestorConfig,
     pub timeout: TimeoutConfig,
     pub verifier: Addr,
+    pub refund: RefundInfo,
+    pub owner: Addr,
+    pub fees: Option<FeeConfig>,
+    pub limits: RateLimits,
+    pub callback_gas_limit: u64,
+    pub payload_schema: Option<PayloadSchema>,
 }
 
 impl Config {
-    pub fn validate(deps: Deps, input: InstantiateMsg) -> Result<Self, ContractError> {
+    pub fn validate(
+        deps: Deps,
+        sender: &Addr,
+        input: InstantiateMsg,
+    ) -> Result<Self, ContractError> {
         let requestor = RequestorConfig::validate(deps, input.requestor)?;
         let timeout = validate_timeout_info(input.timeout)?;
         let verifier = deps.api.addr_validate(&input.verifier)?;
+        let refund = validate_refund_info(input.refund.unwrap_or_default())?;
+        let owner = match input.owner {
+            Some(owner) => deps.api.addr_validate(&owner)?,
+            None => sender.clone(),
+        };
+        let fees = input
+            .fees
+            .map(|fees| FeeConfig::validate(deps, fees))
+            .transpose()?;
+        let limits = validate_rate_limits(input.limits.unwrap_or_default())?;
+        let callback_gas_limit = input
+            .callback_gas_limit
+            .unwrap_or(DEFAULT_CALLBACK_GAS_LIMIT);
+        let payload_schema = input
+            .payload_schema
+            .map(validate_payload_schema)
+            .transpose()?;
         Ok(Config {
             next_id: TaskId::new(1),
             requestor,
             timeout,
             verifier,
+            refund,
+            owner,
+            fees,
+            limits,
+            callback_gas_limit,
+            payload_schema,
+        })
+    }
+
+    /// Collects the fees earned by the queue, returning the message paying the reward pool if any
+    pub fn collect_fees(
+        &self,
+        storage: &mut dyn Storage,
+        fees: Payment,
+    ) -> StdResult<Option<CosmosMsg>> {
+        let rewards = match &self.fees {
+            Some(split) => fees.amount.mul_floor(split.reward_share),
+            None => Uint128::zero(),
+        };
+        let treasury = fees.amount - rewards;
+        if !treasury.is_zero() {
+            COLLECTED_FEES.update(
+                storage,
+                denom_key(&fees.denom),
+                |collected| -> StdResult<_> { Ok(collected.unwrap_or_default() + treasury) },
+            )?;
+        }
+
+        match &self.fees {
+            Some(split) if !rewards.is_zero() => fees
+                .with_amount(rewards)
+                .transfer_msg(&split.reward_pool)
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct FeeConfig {
+    pub reward_share: Decimal,
+    pub reward_pool: Addr,
+}
+
+impl FeeConfig {
+    pub fn validate(deps: Deps, input: FeeInfo) -> Result<Self, ContractError> {
+        if input.reward_share > Decimal::one() {
+            return Err(ContractError::InvalidFeeInfo);
+        }
+        Ok(FeeConfig {
+            reward_share: input.reward_share,
+            reward_pool: deps.api.addr_validate(&input.reward_pool)?,
         })
     }
 }
 
+impl From<FeeConfig> for FeeInfo {
+    fn from(val: FeeConfig) -> Self {
+        FeeInfo {
+            reward_share: val.reward_share,
+            reward_pool: val.reward_pool.into_string(),
+        }
+    }
+}
+
 #[cw_serde]
 pub enum RequestorConfig {
     Fixed(Addr),
     OpenPayment(Coin),
+    MultiDenomPayment(Vec<Coin>),
+    Cw20Payment { token: Addr, amount: Uint128 },
+    AllowList { payment: Vec<Coin> },
 }
 
 impl RequestorConfig {
-    pub fn validate(deps: Deps, input: msg::Requestor) -> Result<Self, StdError> {
+    pub fn validate(deps: Deps, input: msg::Requestor) -> Result<Self, ContractError> {
         match input {
             msg::Requestor::Fixed(addr) => {
                 Ok(RequestorConfig::Fixed(deps.api.addr_validate(&addr)?))
             }
             msg::Requestor::OpenPayment(coin) => Ok(RequestorConfig::OpenPayment(coin)),
+            msg::Requestor::MultiDenomPayment(prices) => {
+                if prices.is_empty() {
+                    return Err(ContractError::InvalidRequestor(
+                        "at least one price is needed".to_string(),
+                    ));
+                }
+                validate_prices(&prices)?;
+                Ok(RequestorConfig::MultiDenomPayment(prices))
+            }
+            msg::Requestor::Cw20Payment { token, amount } => Ok(RequestorConfig::Cw20Payment {
+                token: deps.api.addr_validate(&token)?,
+                amount,
+            }),
+            msg::Requestor::AllowList { payment } => {
+                validate_prices(&payment)?;
+                Ok(RequestorConfig::AllowList { payment })
+            }
         }
     }
 
-    pub fn check_requestor(&self, info: &MessageInfo) -> Result<(), ContractError> {
+    /// Returns the payment for the task, if any
+    pub fn check_requestor(
+        &self,
+        storage: &dyn Storage,
+        info: &MessageInfo,
+    ) -> Result<Option<Payment>, ContractError> {
         match self {
             RequestorConfig::Fixed(addr) => {
                 if info.sender != addr {
                     return Err(ContractError::Unauthorized);
                 }
+                Ok(None)
             }
             RequestorConfig::OpenPayment(needed) => {
                 let paid = must_pay(info, &needed.denom)?;
@@ -81,10 +273,93 @@ impl RequestorConfig {
                         needed.denom.clone(),
                     ));
                 }
+                Ok(Some(Coin::new(paid, &needed.denom).into()))
+            }
+            RequestorConfig::MultiDenomPayment(prices) => check_prices(prices, info).map(Some),
+            RequestorConfig::Cw20Payment { token, .. } => {
+                Err(ContractError::Cw20PaymentRequired(token.to_string()))
+            }
+            RequestorConfig::AllowList { payment } => {
+                if !ALLOWED_REQUESTORS.has(storage, &info.sender) {
+                    return Err(ContractError::NotAllowed(info.sender.to_string()));
+                }
+                if payment.is_empty() {
+                    nonpayable(info)?;
+                    return Ok(None);
+                }
+                check_prices(payment, info).map(Some)
             }
         }
-        Ok(())
     }
+
+    /// The part of the payment above the price of its denom, used as the task priority
+    pub fn priority_fee(&self, payment: &Payment) -> Uint128 {
+        let price = match (self, &payment.denom) {
+            (RequestorConfig::OpenPayment(price), Denom::Native(denom)) => {
+                (&price.denom == denom).then_some(price.amount)
+            }
+            (
+                RequestorConfig::MultiDenomPayment(prices)
+                | RequestorConfig::AllowList { payment: prices },
+                Denom::Native(denom),
+            ) => prices.iter().find(|p| &p.denom == denom).map(|p| p.amount),
+            (RequestorConfig::Cw20Payment { token, amount }, Denom::Cw20(paid)) => {
+                (token == paid).then_some(*amount)
+            }
+            _ => None,
+        };
+        price
+            .map(|price| payment.amount.saturating_sub(price))
+            .unwrap_or_default()
+    }
+
+    /// Returns the payment for a task created by sending `amount` of the CW20 `token`
+    pub fn check_cw20(&self, token: &Addr, amount: Uint128) -> Result<Payment, ContractError> {
+        match self {
+            RequestorConfig::Cw20Payment {
+                token: accepted,
+                amount: needed,
+            } if accepted == token => {
+                if amount < *needed {
+                    return Err(ContractError::InsufficientPayment(
+                        needed.u128(),
+                        token.to_string(),
+                    ));
+                }
+                Ok(Payment::cw20(token.clone(), amount))
+            }
+            _ => Err(ContractError::UnsupportedDenom(token.to_string())),
+        }
+    }
+}
+
+/// Ensures there is at most one price per denom
+fn validate_prices(prices: &[Coin]) -> Result<(), ContractError> {
+    for (i, price) in prices.iter().enumerate() {
+        if prices[..i].iter().any(|p| p.denom == price.denom) {
+            return Err(ContractError::InvalidRequestor(format!(
+                "duplicate price for {}",
+                price.denom
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the single coin sent covers the price of its denom
+fn check_prices(prices: &[Coin], info: &MessageInfo) -> Result<Payment, ContractError> {
+    let paid = one_coin(info)?;
+    let needed = prices
+        .iter()
+        .find(|p| p.denom == paid.denom)
+        .ok_or_else(|| ContractError::UnsupportedDenom(paid.denom.clone()))?;
+    if paid.amount < needed.amount {
+        return Err(ContractError::InsufficientPayment(
+            needed.amount.u128(),
+            needed.denom.clone(),
+        ));
+    }
+    Ok(paid.into())
 }
 
 impl From<RequestorConfig> for Requestor {
@@ -92,6 +367,12 @@ impl From<RequestorConfig> for Requestor {
         match val {
             RequestorConfig::Fixed(addr) => Requestor::Fixed(addr.into_string()),
             RequestorConfig::OpenPayment(coin) => Requestor::OpenPayment(coin),
+            RequestorConfig::MultiDenomPayment(prices) => Requestor::MultiDenomPayment(prices),
+            RequestorConfig::Cw20Payment { token, amount } => Requestor::Cw20Payment {
+                token: token.into_string(),
+                amount,
+            },
+            RequestorConfig::AllowList { payment } => Requestor::AllowList { payment },
         }
     }
 }
@@ -110,6 +391,223 @@ pub fn validate_timeout_info(input: msg::TimeoutInfo) -> Result<TimeoutConfig, C
     })
 }
 
+pub fn validate_refund_info(input: RefundInfo) -> Result<RefundInfo, ContractError> {
+    if input.cancel > Decimal::one() || input.timeout > Decimal::one() {
+        return Err(ContractError::InvalidRefundInfo);
+    }
+    Ok(input)
+}
+
+pub fn validate_rate_limits(input: RateLimits) -> Result<RateLimits, ContractError> {
+    let window_is_zero = input
+        .window
+        .as_ref()
+        .is_some_and(|w| w.max_tasks == 0 || w.seconds == 0);
+    if input.max_open_tasks == Some(0) || input.max_open_per_requestor == Some(0) || window_is_zero
+    {
+        return Err(ContractError::InvalidRateLimits);
+    }
+    Ok(input)
+}
+
+pub fn validate_payload_schema(input: PayloadSchema) -> Result<PayloadSchema, ContractError> {
+    if input.max_size == Some(0) {
+        return Err(ContractError::InvalidPayloadSchema(
+            "max size must be greater than zero".to_string(),
+        ));
+    }
+    if input.required.iter().any(String::is_empty) {
+        return Err(ContractError::InvalidPayloadSchema(
+            "required fields must have a name".to_string(),
+        ));
+    }
+    if let Some(field) = input
+        .allowed_values
+        .iter()
+        .find_map(|(field, values)| (field.is_empty() || values.is_empty()).then_some(field))
+    {
+        return Err(ContractError::InvalidPayloadSchema(format!(
+            "field `{field}` must have a name and allowed values"
+        )));
+    }
+    Ok(input)
+}
+
+/// Checks the payload of a new task against the schema
+pub fn check_payload(schema: &PayloadSchema, payload: &RequestType) -> Result<(), ContractError> {
+    if let Some(max) = schema.max_size {
+        let size = to_json_vec(payload)?.len();
+        if size > max as usize {
+            return Err(ContractError::PayloadTooLarge { size, max });
+        }
+    }
+
+    let fields = payload.as_object().ok_or(ContractError::PayloadNotObject)?;
+    if let Some(missing) = schema
+        .required
+        .iter()
+        .find(|field| !fields.contains_key(*field))
+    {
+        return Err(ContractError::MissingPayloadField(missing.clone()));
+    }
+    for (field, allowed) in &schema.allowed_values {
+        match fields.get(field) {
+            Some(value) if !allowed.contains(value) => {
+                return Err(ContractError::PayloadValueNotAllowed {
+                    field: field.clone(),
+                    value: value.to_string(),
+                    allowed: serde_json::Value::from(allowed.clone()).to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Usage {
+    /// Tasks created by this requestor that are still open
+    pub open_tasks: u32,
+    /// Start of the current window in UNIX seconds
+    pub window_start: u64,
+    /// Tasks created since the start of the window
+    pub window_tasks: u32,
+}
+
+impl Usage {
+    /// End of the current window, if there is one running
+    pub fn window_ends(&self, env: &Env, limits: &RateLimits) -> Option<u64> {
+        let window = limits.window.as_ref()?;
+        let ends = self.window_start + window.seconds;
+        (self.window_tasks > 0 && env.block.time.seconds() < ends).then_some(ends)
+    }
+}
+
+/// Counts a new task against the rate limits, failing if any of them is reached.
+/// Nothing is written unless all the checks pass
+pub fn track_created(
+    storage: &mut dyn Storage,
+    env: &Env,
+    limits: &RateLimits,
+    creator: &Addr,
+) -> Result<(), ContractError> {
+    let open = OPEN_TASKS.load(storage)?;
+    if let Some(max) = limits.max_open_tasks {
+        if open >= max {
+            return Err(ContractError::TooManyOpenTasks(max));
+        }
+    }
+
+    let mut usage = REQUESTOR_USAGE
+        .may_load(storage, creator)?
+        .unwrap_or_default();
+    if let Some(max) = limits.max_open_per_requestor {
+        if usage.open_tasks >= max {
+            return Err(ContractError::RequestorOpenLimit(max));
+        }
+    }
+    usage.open_tasks += 1;
+
+    if let Some(window) = &limits.window {
+        if usage.window_ends(env, limits).is_none() {
+            usage.window_start = env.block.time.seconds();
+            usage.window_tasks = 0;
+        }
+        if usage.window_tasks >= window.max_tasks {
+            return Err(ContractError::RequestorRateLimit {
+                max: window.max_tasks,
+                seconds: window.seconds,
+            });
+        }
+        usage.window_tasks += 1;
+    }
+    OPEN_TASKS.save(storage, &(open + 1))?;
+    REQUESTOR_USAGE.save(storage, creator, &usage)?;
+    Ok(())
+}
+
+/// Frees the slots of a task that is no longer open
+pub fn track_closed(storage: &mut dyn Storage, creator: &Addr) -> StdResult<()> {
+    OPEN_TASKS.update(storage, |open| -> StdResult<_> {
+        Ok(open.saturating_sub(1))
+    })?;
+    REQUESTOR_USAGE.update(storage, creator, |usage| -> StdResult<_> {
+        let mut usage = usage.unwrap_or_default();
+        usage.open_tasks = usage.open_tasks.saturating_sub(1);
+        Ok(usage)
+    })?;
+    Ok(())
+}
+
+#[cw_serde]
+pub struct Schedule {
+    pub creator: Addr,
+    pub description: String,
+    pub payload: RequestType,
+    /// Timeout of each task, already checked against the config
+    pub timeout: u64,
+    pub interval: u64,
+    /// When the next task can be created in UNIX seconds
+    pub next_run: u64,
+    /// Tasks left to create
+    pub remaining: u32,
+    /// Prepaid for each task
+    pub payment: Option<Payment>,
+}
+
+impl Schedule {
+    /// Payments still held for the runs left, to refund when the schedule is cancelled
+    pub fn prepaid(&self) -> Option<Payment> {
+        self.payment
+            .as_ref()
+            .map(|paid| paid.with_amount(paid.amount * Uint128::from(self.remaining)))
+            .filter(|paid| !paid.amount.is_zero())
+    }
+}
+
+/// Number of tasks a schedule starting now creates
+pub fn schedule_runs(env: &Env, interval: u64, end: &ScheduleEnd) -> Result<u32, ContractError> {
+    if interval == 0 {
+        return Err(ContractError::InvalidSchedule(
+            "interval must be greater than zero".to_string(),
+        ));
+    }
+    let runs = match end {
+        ScheduleEnd::Count(count) => *count,
+        ScheduleEnd::Until(until) => {
+            let span = until
+                .checked_sub(env.block.time.seconds())
+                .ok_or_else(|| ContractError::InvalidSchedule("end is in the past".to_string()))?;
+            u32::try_from(span / interval + 1)
+                .map_err(|_| ContractError::InvalidSchedule("too many runs".to_string()))?
+        }
+    };
+    if runs == 0 {
+        return Err(ContractError::InvalidSchedule(
+            "count must be greater than zero".to_string(),
+        ));
+    }
+    Ok(runs)
+}
+
+/// Splits funds evenly between the runs of a schedule, returning the funds of each run and the
+/// remainder to refund
+pub fn split_funds(funds: &[Coin], runs: u32) -> (Vec<Coin>, Vec<Coin>) {
+    let runs = Uint128::from(runs);
+    funds
+        .iter()
+        .map(|coin| {
+            let per_run = coin.amount / runs;
+            (
+                Coin::new(per_run, &coin.denom),
+                Coin::new(coin.amount - per_run * runs, &coin.denom),
+            )
+        })
+        .unzip()
+}
+
 pub fn check_timeout(config: &TimeoutConfig, timeout: Option<u64>) -> Result<u64, ContractError> {
     match timeout {
         Some(t) if t < config.minimum => Err(ContractError::TimeoutTooShort(config.minimum)),
@@ -126,6 +624,20 @@ pub struct Task {
     pub timing: Timing,
     pub payload: RequestType,
     pub result: Option<ResponseType>,
+    pub creator: Addr,
+    /// What the creator paid for the task, refunds are taken from it
+    pub payment: Option<Payment>,
+    /// What was paid above the price, open tasks are listed by it
+    #[serde(default)]
+    pub priority: Uint128,
+    /// Tasks that must be completed before this one opens
+    #[serde(default)]
+    pub depends_on: Vec<TaskId>,
+    /// Contract notified when the task is completed or expires
+    #[serde(default)]
+    pub callback: Option<Addr>,
+    /// The only one that can complete the task
+    pub verifier: Addr,
 }
 
 impl Task {
@@ -133,9 +645,23 @@ impl Task {
         match self.status {
             Status::Open {} if !self.timing.is_expired(env) => self.status.clone(),
             Status::Expired {} | Status::Open {} => Status::Expired {},
-            Status::Completed { .. } => self.status.clone(),
+            Status::Pending {} | Status::Completed { .. } | Status::Cancelled {} => {
+                self.status.clone()
+            }
         }
     }
+
+    /// Splits the payment between the refund to the creator, given its share, and the fees kept by
+    /// the queue. Either is `None` if there is nothing to pay.
+    pub fn split_payment(&self, refund_share: Decimal) -> (Option<Payment>, Option<Payment>) {
+        let Some(paid) = &self.payment else {
+            return (None, None);
+        };
+        let refund = paid.amount.mul_floor(refund_share);
+        let fees = paid.amount - refund;
+        let non_zero = |amount: Uint128| (!amount.is_zero()).then(|| paid.with_amount(amount));
+        (non_zero(refund), non_zero(fees))
+    }
 }
 
 #[cw_serde]
@@ -144,7 +670,7 @@ pub struct Timing {
     pub created_at: u64,
     /// Expiration in UNIX seconds
     pub expires_at: u64,
-    /// Creation in block height
+    /// Creation in block height, or opening for the tasks that were pending
     pub created_height: u64,
 }
 
@@ -168,6 +694,8 @@ impl Task {
             Status::Open {} if !self.timing.is_expired(env) => {}
             Status::Open {} | Status::Expired {} => return Err(ContractError::TaskExpired),
             Status::Completed { .. } => return Err(ContractError::TaskCompleted),
+            Status::Cancelled {} => return Err(ContractError::TaskCancelled),
+            Status::Pending {} => return Err(ContractError::TaskPending),
         };
         self.status = Status::completed(env);
         self.result = Some(result);
@@ -180,8 +708,100 @@ impl Task {
             Status::Open {} => return Err(ContractError::TaskNotExpired),
             Status::Expired {} => return Err(ContractError::TaskExpired),
             Status::Completed { .. } => return Err(ContractError::TaskCompleted),
+            Status::Cancelled {} => return Err(ContractError::TaskCancelled),
+            Status::Pending {} => return Err(ContractError::TaskPending),
         };
         self.status = Status::Expired {};
         Ok(())
     }
+
+    /// Also used when a task it depends on fails
+    pub fn cancel(&mut self, env: &Env) -> Result<(), ContractError> {
+        match self.status {
+            Status::Open {} if !self.timing.is_expired(env) => {}
+            Status::Pending {} => {}
+            Status::Open {} | Status::Expired {} => return Err(ContractError::TaskExpired),
+            Status::Completed { .. } => return Err(ContractError::TaskCompleted),
+            Status::Cancelled {} => return Err(ContractError::TaskCancelled),
+        };
+        self.status = Status::Cancelled {};
+        Ok(())
+    }
+
+    /// Opens a pending task once the tasks it depends on are completed, adding their results
+    /// to the payload. The timeout starts now, but it keeps its creation time. Its creation
+    /// height moves to this block, as verifiers weigh the votes with the power at that height
+    pub fn open(
+        &mut self,
+        env: &Env,
+        results: Vec<(TaskId, ResponseType)>,
+    ) -> Result<(), ContractError> {
+        if self.status != (Status::Pending {}) {
+            return Err(ContractError::TaskNotPending);
+        }
+        inject_results(&mut self.payload, results)?;
+        let timeout = self.timing.expires_at - self.timing.created_at;
+        self.timing.expires_at = env.block.time.seconds() + timeout;
+        self.timing.created_height = env.block.height;
+        self.status = Status::Open {};
+        Ok(())
+    }
+}
+
+/// Where the tasks a task depends on stand
+pub enum Dependencies {
+    /// All completed, with their results
+    Completed(Vec<(TaskId, ResponseType)>),
+    /// Some are still open or pending
+    Waiting,
+    /// This one can't be completed anymore
+    Failed(TaskId),
+}
+
+/// Checks the tasks a task depends on, which must all exist
+pub fn check_dependencies(
+    storage: &dyn Storage,
+    env: &Env,
+    depends_on: &[TaskId],
+) -> Result<Dependencies, ContractError> {
+    let mut results = Vec::with_capacity(depends_on.len());
+    let mut waiting = false;
+    for id in depends_on {
+        let task = TASKS
+            .may_load(storage, *id)?
+            .ok_or_else(|| ContractError::InvalidDependency(format!("task {id} doesn't exist")))?;
+        match task.validate_status(env) {
+            Status::Completed { .. } => {
+                let result = task
+                    .result
+                    .ok_or(ContractError::MissingResultCompleted { id: *id })?;
+                results.push((*id, result));
+            }
+            Status::Open {} | Status::Pending {} => waiting = true,
+            Status::Expired {} | Status::Cancelled {} => return Ok(Dependencies::Failed(*id)),
+        }
+    }
+    match waiting {
+        true => Ok(Dependencies::Waiting),
+        false => Ok(Dependencies::Completed(results)),
+    }
+}
+
+/// Adds the results of the tasks a task depends on to its payload, under `"dependencies"`
+pub fn inject_results(
+    payload: &mut RequestType,
+    results: Vec<(TaskId, ResponseType)>,
+) -> Result<(), ContractError> {
+    let object = payload
+        .as_object_mut()
+        .ok_or_else(|| ContractError::InvalidDependency("payload must be an object".to_string()))?;
+    let dependencies = results
+        .into_iter()
+        .map(|(id, result)| (id.to_string(), result))
+        .collect();
+    object.insert(
+        "dependencies".to_string(),
+        serde_json::Value::Object(dependencies),
+    );
+    Ok(())
 }
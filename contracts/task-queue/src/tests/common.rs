@@ -1,14 +1,17 @@
+use cosmwasm_std::{coin, coins, to_json_binary, Decimal, Order, Uint128};
 use cw_orch::environment::{ChainState, CwEnv, Environment, IndexResponse, QueryHandler};
 use cw_orch::prelude::*;
 use lavs_apis::id::TaskId;
 use lavs_apis::tasks::TaskStatus;
 use serde_json::json;
 
+use crate::contract::{MAX_BATCH_SIZE, MAX_SCANNED_TASKS, MAX_TICK_TASKS};
 use crate::error::ContractError;
 use crate::interface::Contract as TaskContract;
 use crate::msg::{
-    CompletedTaskOverview, InstantiateMsg, ListCompletedResponse, ListOpenResponse,
-    OpenTaskOverview, Requestor, Status, TimeoutInfo,
+    CompletedTaskOverview, FeeInfo, InstantiateMsg, ListCompletedResponse, ListOpenResponse,
+    ListTasksResponse, NewTask, OpenTaskOverview, PayloadSchema, Payment, RateLimits, ReceiveMsg,
+    RefundInfo, Requestor, ScheduleEnd, Status, TaskNode, TaskResult, TimeoutInfo, WindowLimit,
 };
 
 // FIXME: any way to get these as one import, rather than import all sub traits?
@@ -17,7 +20,14 @@ use crate::msg::{CustomExecuteMsgFns, CustomQueryMsgFns, TaskExecuteMsgFns, Task
 
 use lavs_orch::{Addressable, AltSigner};
 
+pub const DENOM: &str = "uslay";
+pub const PRICE: u128 = 100;
+
+/// Signers the payment tests expect to hold 1000 of `DENOM`
+pub const REQUESTOR_INDEX: u32 = 1;
+pub const OTHER_INDEX: u32 = 2;
 const VERIFIER_INDEX: u32 = 3;
+const ANYONE_INDEX: u32 = 4;
 
 pub fn setup<Chain: CwEnv>(chain: Chain, msg: InstantiateMsg) -> TaskContract<Chain> {
     let tasker = TaskContract::new(chain);
@@ -26,20 +36,40 @@ pub fn setup<Chain: CwEnv>(chain: Chain, msg: InstantiateMsg) -> TaskContract<Ch
     tasker
 }
 
+/// A queue with the given requestor mode, owned by the chain sender and verified by
+/// the `VERIFIER_INDEX` signer. Tests change the other fields with struct update syntax.
+fn queue_msg<Chain>(chain: &Chain, requestor: Requestor) -> InstantiateMsg
+where
+    Chain: CwEnv + AltSigner,
+    Chain::Sender: Addressable,
+{
+    InstantiateMsg {
+        requestor,
+        timeout: TimeoutInfo::new(100),
+        verifier: chain.alt_signer(VERIFIER_INDEX).addr().into(),
+        verifiers: vec![],
+        refund: None,
+        owner: None,
+        fees: None,
+        allowed_requestors: vec![],
+        limits: None,
+        callback_gas_limit: None,
+        payload_schema: None,
+    }
+}
+
 fn fixed_requestor<Chain>(chain: &Chain, timeout: u64) -> (TaskContract<Chain>, Chain::Sender)
 where
     Chain: CwEnv + AltSigner,
     Chain::Sender: Addressable,
 {
-    let verifier = chain.alt_signer(VERIFIER_INDEX);
     let msg = InstantiateMsg {
-        requestor: Requestor::Fixed(chain.sender_addr().into()),
         timeout: mock_timeout(timeout),
-        verifier: verifier.addr().into(),
+        ..queue_msg(chain, Requestor::Fixed(chain.sender_addr().into()))
     };
 
     let contract = setup(chain.clone(), msg);
-    (contract, verifier)
+    (contract, chain.alt_signer(VERIFIER_INDEX))
 }
 
 // This sets the chain signer to be the requestor
@@ -136,7 +166,15 @@ where
     // {} will just show the method call,
     // {:#} or {:?} will show the full error chain (but :# is nicer to read)
     let err = contract
-        .create("Too Short".to_string(), Some(4), payload.clone(), &[])
+        .create(
+            "Too Short".to_string(),
+            Some(4),
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
         .unwrap_err();
     assert!(
         err.root()
@@ -147,7 +185,15 @@ where
     );
 
     let one = contract
-        .create("One".to_string(), None, payload.clone(), &[])
+        .create(
+            "One".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
         .unwrap();
     let task_one = one.event_attr_value("wasm", "task_id").unwrap();
     assert_eq!(task_one, "1");
@@ -155,7 +201,15 @@ where
     assert_eq!(task_one, 1u64);
 
     let two = contract
-        .create("Two".to_string(), None, payload.clone(), &[])
+        .create(
+            "Two".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
         .unwrap();
     let task_two = get_task_id(&two);
     assert_eq!(task_two, TaskId::new(2u64));
@@ -191,6 +245,7 @@ where
             id: three,
             expires: start + 200 + 2 * block_time + offset, // we waited two blocks to create
             payload: payload_three,
+            priority: Uint128::zero(),
         }
     );
     assert_eq!(
@@ -199,6 +254,7 @@ where
             id: two,
             expires: start + 100 + block_time + offset, // we waited one block to create
             payload: payload_two,
+            priority: Uint128::zero(),
         }
     );
     assert_eq!(
@@ -207,6 +263,7 @@ where
             id: one,
             expires: start + 300 + offset,
             payload: payload_one,
+            priority: Uint128::zero(),
         }
     );
 
@@ -455,11 +512,1752 @@ where
     );
 }
 
+/// A queue anyone can use by paying `PRICE`, called as the requestor
+fn payment_queue<C>(
+    chain: &C,
+    refund: Option<RefundInfo>,
+    fees: Option<FeeInfo>,
+) -> (TaskContract<C>, C::Sender, C::Sender)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let msg = InstantiateMsg {
+        refund,
+        fees,
+        ..queue_msg(chain, Requestor::OpenPayment(coin(PRICE, DENOM)))
+    };
+    let requestor = chain.alt_signer(REQUESTOR_INDEX);
+    let contract = setup(chain.clone(), msg).call_as(&requestor);
+    (contract, requestor, chain.alt_signer(VERIFIER_INDEX))
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn cancel_refunds_creator<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let refund = RefundInfo {
+        cancel: Decimal::percent(50),
+        timeout: Decimal::one(),
+    };
+    let (contract, requestor, verifier) = payment_queue(&chain, Some(refund.clone()), None);
+    assert_eq!(contract.config().unwrap().refund, refund);
+    let balance = || query_balance(&chain, &requestor.addr());
+
+    let payload = json!({ "pair": ["eth", "usd"] });
+    let res = contract
+        .create(
+            "One".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &coins(PRICE, DENOM),
+        )
+        .unwrap();
+    let one = get_task_id(&res);
+    assert_eq!(balance(), 900);
+
+    let task = contract.task(one).unwrap();
+    assert_eq!(task.creator, requestor.addr().to_string());
+    assert_eq!(task.payment, Some(Payment::from(coin(PRICE, DENOM))));
+
+    // only the creator can cancel
+    let err = contract.call_as(&verifier).cancel(one).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+
+    // half of the payment is returned, the rest stays in the contract
+    contract.cancel(one).unwrap();
+    assert_eq!(balance(), 950);
+    assert_eq!(query_balance(&chain, &contract.address().unwrap()), 50);
+
+    // a cancelled task is done for good
+    assert_eq!(contract.task(one).unwrap().status, Status::Cancelled {});
+    assert_eq!(
+        contract.task_status(one).unwrap().status,
+        TaskStatus::Expired
+    );
+    let err = contract.cancel(one).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskCancelled.to_string()));
+    let err = contract
+        .call_as(&verifier)
+        .complete(one, json!({ "price": "1" }))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskCancelled.to_string()));
+    assert!(contract.list_open(None, None).unwrap().tasks.is_empty());
+
+    // completed tasks can't be cancelled
+    let res = contract
+        .create(
+            "Two".to_string(),
+            None,
+            payload,
+            vec![],
+            None,
+            None,
+            &coins(PRICE, DENOM),
+        )
+        .unwrap();
+    let two = get_task_id(&res);
+    contract
+        .call_as(&verifier)
+        .complete(two, json!({ "price": "1" }))
+        .unwrap();
+    let err = contract.cancel(two).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskCompleted.to_string()));
+    assert_eq!(balance(), 850);
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn timeout_refunds_creator<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    // full refunds by default
+    let (contract, requestor, _verifier) = payment_queue(&chain, None, None);
+    assert_eq!(contract.config().unwrap().refund, RefundInfo::default());
+    let balance = || query_balance(&chain, &requestor.addr());
+
+    let payload = json!({ "pair": ["eth", "usd"] });
+    let res = contract
+        .create(
+            "One".to_string(),
+            None,
+            payload,
+            vec![],
+            None,
+            None,
+            &coins(PRICE, DENOM),
+        )
+        .unwrap();
+    let one = get_task_id(&res);
+    assert_eq!(balance(), 900);
+
+    // can't cancel or time out at the wrong time
+    let err = contract.timeout(one).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskNotExpired.to_string()));
+    chain.wait_seconds(100).unwrap();
+    let err = contract.cancel(one).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskExpired.to_string()));
+
+    // anyone can time out the task, the refund goes to the creator
+    let anyone = chain.alt_signer(ANYONE_INDEX);
+    contract.call_as(&anyone).timeout(one).unwrap();
+    assert_eq!(balance(), 1000);
+    assert_eq!(contract.task(one).unwrap().status, Status::Expired {});
+}
+
+pub fn invalid_refund_info<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let tasker = TaskContract::new(chain.clone());
+    tasker.upload().unwrap();
+
+    let msg = InstantiateMsg {
+        refund: Some(RefundInfo {
+            cancel: Decimal::percent(101),
+            timeout: Decimal::one(),
+        }),
+        ..queue_msg(&chain, Requestor::OpenPayment(coin(PRICE, DENOM)))
+    };
+    let err = tasker.instantiate(&msg, None, &[]).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InvalidRefundInfo.to_string()));
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn fees_are_split_and_withdrawn<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let pool = chain.alt_signer(5).addr();
+    let treasury = chain.alt_signer(6).addr();
+    let refund = RefundInfo {
+        cancel: Decimal::percent(50),
+        timeout: Decimal::one(),
+    };
+    let fees = FeeInfo {
+        reward_share: Decimal::percent(30),
+        reward_pool: pool.to_string(),
+    };
+    let (contract, requestor, verifier) = payment_queue(&chain, Some(refund), Some(fees.clone()));
+    let balance = |addr: &Addr| query_balance(&chain, addr);
+    let collected = || contract.collected_fees().unwrap().fees;
+
+    // the instantiator owns the queue by default
+    let config = contract.config().unwrap();
+    assert_eq!(config.owner, chain.sender_addr().to_string());
+    assert_eq!(config.fees, Some(fees));
+    assert!(collected().is_empty());
+
+    // nothing is earned until the task is done
+    let payload = json!({ "pair": ["eth", "usd"] });
+    let create = || {
+        let res = contract
+            .create(
+                "Task".to_string(),
+                None,
+                payload.clone(),
+                vec![],
+                None,
+                None,
+                &coins(PRICE, DENOM),
+            )
+            .unwrap();
+        get_task_id(&res)
+    };
+    let one = create();
+    assert!(collected().is_empty());
+
+    // a completed task earns the whole payment, 30% goes to the pool right away
+    contract
+        .call_as(&verifier)
+        .complete(one, json!({ "price": "1" }))
+        .unwrap();
+    assert_eq!(balance(&pool), 30);
+    assert_eq!(collected(), vec![Payment::from(coin(70, DENOM))]);
+
+    // a cancelled task only earns what is not refunded
+    let two = create();
+    contract.cancel(two).unwrap();
+    assert_eq!(balance(&pool), 45);
+    assert_eq!(collected(), vec![Payment::from(coin(105, DENOM))]);
+
+    // and an expired task with a full refund earns nothing
+    let three = create();
+    chain.wait_seconds(100).unwrap();
+    contract.timeout(three).unwrap();
+    assert_eq!(balance(&pool), 45);
+    assert_eq!(collected(), vec![Payment::from(coin(105, DENOM))]);
+    assert_eq!(balance(&requestor.addr()), 1000 - 100 - 50);
+
+    // only the owner can withdraw, and no more than collected
+    let owner = contract.call_as(chain.sender());
+    let err = contract
+        .withdraw_fees(Payment::from(coin(10, DENOM)), None)
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+    let err = owner
+        .withdraw_fees(Payment::from(coin(106, DENOM)), Some(treasury.to_string()))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InsufficientFees(coin(105, DENOM).into()).to_string()));
+
+    owner
+        .withdraw_fees(Payment::from(coin(100, DENOM)), Some(treasury.to_string()))
+        .unwrap();
+    assert_eq!(balance(&treasury), 100);
+    assert_eq!(collected(), vec![Payment::from(coin(5, DENOM))]);
+    owner
+        .withdraw_fees(Payment::from(coin(5, DENOM)), None)
+        .unwrap();
+    assert!(collected().is_empty());
+
+    // the contract only holds the payments of open tasks
+    assert_eq!(balance(&contract.address().unwrap()), 0);
+}
+
+pub fn allow_list_requestors<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let alice = chain.alt_signer(REQUESTOR_INDEX);
+    let bob = chain.alt_signer(OTHER_INDEX);
+    let msg = InstantiateMsg {
+        allowed_requestors: vec![alice.addr().to_string()],
+        ..queue_msg(&chain, Requestor::AllowList { payment: vec![] })
+    };
+    let contract = setup(chain.clone(), msg);
+    let payload = json!({ "pair": ["eth", "usd"] });
+    let allowed = || contract.allow_list(None, None).unwrap().requestors;
+    assert_eq!(allowed(), vec![alice.addr().to_string()]);
+
+    // only members can create tasks, without paying
+    contract
+        .call_as(&alice)
+        .create(
+            "Alice".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
+        .unwrap();
+    let err = contract
+        .call_as(&bob)
+        .create(
+            "Bob".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NotAllowed(bob.addr().to_string()).to_string()));
+
+    // only the owner manages the list
+    let err = contract
+        .call_as(&alice)
+        .update_allow_list(vec![bob.addr().to_string()], vec![])
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+    contract
+        .update_allow_list(vec![bob.addr().to_string()], vec![alice.addr().to_string()])
+        .unwrap();
+    assert_eq!(allowed(), vec![bob.addr().to_string()]);
+
+    contract
+        .call_as(&bob)
+        .create(
+            "Bob".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
+        .unwrap();
+    let err = contract
+        .call_as(&alice)
+        .create("Alice".to_string(), None, payload, vec![], None, None, &[])
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NotAllowed(alice.addr().to_string()).to_string()));
+
+    // other requestor modes have no list
+    let open = setup(
+        chain.clone(),
+        queue_msg(&chain, Requestor::OpenPayment(coin(PRICE, DENOM))),
+    );
+    let err = open
+        .update_allow_list(vec![bob.addr().to_string()], vec![])
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NoAllowList.to_string()));
+}
+
+/// The requestor and the other signer must be funded with 1000 of `DENOM`
+pub fn allow_list_with_payment<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let alice = chain.alt_signer(REQUESTOR_INDEX);
+    let bob = chain.alt_signer(OTHER_INDEX);
+    let msg = InstantiateMsg {
+        allowed_requestors: vec![alice.addr().to_string()],
+        ..queue_msg(
+            &chain,
+            Requestor::AllowList {
+                payment: vec![coin(PRICE, DENOM)],
+            },
+        )
+    };
+    let contract = setup(chain.clone(), msg);
+    let payload = json!({ "pair": ["eth", "usd"] });
+
+    // members must still pay
+    let err = contract
+        .call_as(&alice)
+        .create(
+            "Alice".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.root().to_string().contains("No funds sent"));
+    let res = contract
+        .call_as(&alice)
+        .create(
+            "Alice".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &coins(PRICE, DENOM),
+        )
+        .unwrap();
+    let task = contract.task(get_task_id(&res)).unwrap();
+    assert_eq!(task.payment, Some(Payment::from(coin(PRICE, DENOM))));
+
+    // and paying doesn't get anyone else in
+    let err = contract
+        .call_as(&bob)
+        .create(
+            "Bob".to_string(),
+            None,
+            payload,
+            vec![],
+            None,
+            None,
+            &coins(PRICE, DENOM),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NotAllowed(bob.addr().to_string()).to_string()));
+}
+
+/// The requestor must be funded with 1000 of `DENOM`, "uatom" and "ufoo"
+pub fn multi_denom_payment<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let requestor = chain.alt_signer(REQUESTOR_INDEX);
+    let prices = vec![coin(PRICE, DENOM), coin(5, "uatom")];
+    let contract = setup(
+        chain.clone(),
+        queue_msg(&chain, Requestor::MultiDenomPayment(prices.clone())),
+    )
+    .call_as(&requestor);
+    assert_eq!(
+        contract.config().unwrap().requestor,
+        Requestor::MultiDenomPayment(prices)
+    );
+    let payload = json!({ "pair": ["eth", "usd"] });
+
+    // each denom has its own price
+    let res = contract
+        .create(
+            "Atom".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &coins(5, "uatom"),
+        )
+        .unwrap();
+    let task = contract.task(get_task_id(&res)).unwrap();
+    assert_eq!(task.payment, Some(Payment::from(coin(5, "uatom"))));
+    let res = contract
+        .create(
+            "Slay".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &coins(PRICE, DENOM),
+        )
+        .unwrap();
+    let task = contract.task(get_task_id(&res)).unwrap();
+    assert_eq!(task.payment, Some(Payment::from(coin(PRICE, DENOM))));
+
+    let err = contract
+        .create(
+            "Cheap".to_string(),
+            None,
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &coins(4, "uatom"),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InsufficientPayment(5, "uatom".to_string()).to_string()));
+    let err = contract
+        .create(
+            "Foo".to_string(),
+            None,
+            payload,
+            vec![],
+            None,
+            None,
+            &coins(PRICE, "ufoo"),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::UnsupportedDenom("ufoo".to_string()).to_string()));
+
+    // a denom can only have one price
+    let tasker = TaskContract::new(chain.clone());
+    let msg = queue_msg(
+        &chain,
+        Requestor::MultiDenomPayment(vec![coin(PRICE, DENOM), coin(5, DENOM)]),
+    );
+    let err = tasker.instantiate(&msg, None, &[]).unwrap_err();
+    assert!(err.root().to_string().contains("duplicate price for uslay"));
+}
+
+pub fn cw20_payment<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    // the queue trusts the hook sender to be the token contract, so any signer can play it
+    let token = chain.alt_signer(5);
+    let requestor = chain.alt_signer(REQUESTOR_INDEX);
+    let contract = setup(
+        chain.clone(),
+        queue_msg(
+            &chain,
+            Requestor::Cw20Payment {
+                token: token.addr().to_string(),
+                amount: PRICE.into(),
+            },
+        ),
+    );
+    let create = |description: &str| {
+        to_json_binary(&ReceiveMsg::Create {
+            description: description.to_string(),
+            timeout: None,
+            payload: json!({ "pair": ["eth", "usd"] }),
+            depends_on: vec![],
+            callback: None,
+            verifier: None,
+        })
+        .unwrap()
+    };
+
+    // the task is created for whoever sent the tokens
+    let res = contract
+        .call_as(&token)
+        .receive(
+            requestor.addr().to_string(),
+            Uint128::new(PRICE),
+            create("One"),
+        )
+        .unwrap();
+    let task = contract.task(get_task_id(&res)).unwrap();
+    assert_eq!(task.creator, requestor.addr().to_string());
+    assert_eq!(task.payment, Some(Payment::cw20(token.addr(), PRICE)));
+
+    let err = contract
+        .call_as(&token)
+        .receive(
+            requestor.addr().to_string(),
+            Uint128::new(PRICE - 1),
+            create("Cheap"),
+        )
+        .unwrap_err();
+    assert!(err.root().to_string().contains(
+        &ContractError::InsufficientPayment(PRICE, token.addr().to_string()).to_string()
+    ));
+
+    // other tokens and direct creation are refused
+    let other = chain.alt_signer(6);
+    let err = contract
+        .call_as(&other)
+        .receive(
+            requestor.addr().to_string(),
+            Uint128::new(PRICE),
+            create("Other"),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::UnsupportedDenom(other.addr().to_string()).to_string()));
+    let err = contract
+        .call_as(&requestor)
+        .create(
+            "Direct".to_string(),
+            None,
+            json!({ "pair": ["eth", "usd"] }),
+            vec![],
+            None,
+            None,
+            &[],
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Cw20PaymentRequired(token.addr().to_string()).to_string()));
+}
+
+pub fn rate_limits<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let alice = chain.alt_signer(REQUESTOR_INDEX);
+    let bob = chain.alt_signer(OTHER_INDEX);
+    let tasker = TaskContract::new(chain.clone());
+    tasker.upload().unwrap();
+
+    let limits = RateLimits {
+        max_open_tasks: Some(3),
+        max_open_per_requestor: Some(2),
+        window: Some(WindowLimit {
+            max_tasks: 3,
+            seconds: 100,
+        }),
+    };
+    let mut msg = InstantiateMsg {
+        allowed_requestors: vec![alice.addr().to_string(), bob.addr().to_string()],
+        limits: Some(RateLimits {
+            max_open_per_requestor: Some(0),
+            ..limits.clone()
+        }),
+        ..queue_msg(&chain, Requestor::AllowList { payment: vec![] })
+    };
+    let err = tasker.instantiate(&msg, None, &[]).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InvalidRateLimits.to_string()));
+    msg.limits = Some(limits.clone());
+    tasker.instantiate(&msg, None, &[]).unwrap();
+    assert_eq!(tasker.config().unwrap().limits, limits);
+
+    let payload = json!({ "pair": ["eth", "usd"] });
+    let create = |who: &C::Sender| {
+        tasker
+            .call_as(who)
+            .create(
+                "Task".to_string(),
+                None,
+                payload.clone(),
+                vec![],
+                None,
+                None,
+                &[],
+            )
+            .map(|res| get_task_id(&res))
+    };
+    let assert_err = |res: Result<_, CwOrchError>, expected: ContractError| {
+        assert!(res
+            .unwrap_err()
+            .root()
+            .to_string()
+            .contains(&expected.to_string()));
+    };
+
+    // each requestor has its own cap on open tasks
+    let start = get_time(&chain);
+    let one = create(&alice).unwrap();
+    create(&alice).unwrap();
+    assert_err(create(&alice), ContractError::RequestorOpenLimit(2));
+    let usage = tasker.requestor_usage(alice.addr().to_string()).unwrap();
+    assert_eq!(usage.open_tasks, 2);
+    assert_eq!(usage.window_tasks, 2);
+    assert_eq!(usage.window_ends, Some(start + 100));
+
+    // and the whole queue too
+    let three = create(&bob).unwrap();
+    assert_err(create(&bob), ContractError::TooManyOpenTasks(3));
+
+    // closed tasks free their slots, but still count in the window
+    tasker.call_as(&alice).cancel(one).unwrap();
+    let four = create(&alice).unwrap();
+    tasker.call_as(&alice).cancel(four).unwrap();
+    assert_err(
+        create(&alice),
+        ContractError::RequestorRateLimit {
+            max: 3,
+            seconds: 100,
+        },
+    );
+
+    // a new window starts once the previous one is over
+    chain.wait_seconds(100).unwrap();
+    let usage = tasker.requestor_usage(alice.addr().to_string()).unwrap();
+    assert_eq!(usage.open_tasks, 1);
+    assert_eq!(usage.window_tasks, 0);
+    assert_eq!(usage.window_ends, None);
+    create(&alice).unwrap();
+
+    // expired tasks count as open until they are timed out
+    assert_err(create(&bob), ContractError::TooManyOpenTasks(3));
+    tasker.timeout(three).unwrap();
+    create(&bob).unwrap();
+    let usage = tasker.requestor_usage(bob.addr().to_string()).unwrap();
+    assert_eq!(usage.open_tasks, 1);
+    assert_eq!(usage.window_tasks, 1);
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn priority_fees_order_open_tasks<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, _requestor, _verifier) = payment_queue(&chain, None, None);
+    let payload = json!({ "pair": ["eth", "usd"] });
+
+    // anything paid above the price is the priority fee
+    let ids: Vec<_> = [PRICE, PRICE + 50, PRICE + 20, PRICE + 50]
+        .into_iter()
+        .map(|paid| {
+            let res = contract
+                .create(
+                    "Task".to_string(),
+                    None,
+                    payload.clone(),
+                    vec![],
+                    None,
+                    None,
+                    &coins(paid, DENOM),
+                )
+                .unwrap();
+            get_task_id(&res)
+        })
+        .collect();
+    let task = contract.task(ids[3]).unwrap();
+    assert_eq!(task.priority.u128(), 50);
+    assert_eq!(task.payment, Some(Payment::from(coin(PRICE + 50, DENOM))));
+
+    // highest priority first, then newest first
+    let open = contract.list_open(None, None).unwrap().tasks;
+    let listed: Vec<_> = open.iter().map(|t| (t.id, t.priority.u128())).collect();
+    assert_eq!(
+        listed,
+        vec![(ids[3], 50), (ids[1], 50), (ids[2], 20), (ids[0], 0)]
+    );
+
+    // pagination follows the same order
+    let page = contract.list_open(Some(ids[1]), Some(2)).unwrap().tasks;
+    let listed: Vec<_> = page.iter().map(|t| t.id).collect();
+    assert_eq!(listed, vec![ids[2], ids[0]]);
+
+    // closed tasks leave the list
+    contract.cancel(ids[3]).unwrap();
+    let open = contract.list_open(None, None).unwrap().tasks;
+    assert_eq!(open[0].id, ids[1]);
+    assert_eq!(open.len(), 3);
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn recurring_tasks<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, requestor, _verifier) = payment_queue(&chain, None, None);
+    let anyone = chain.alt_signer(ANYONE_INDEX);
+    let balance = || query_balance(&chain, &requestor.addr());
+    let open_tasks = || contract.list_open(None, None).unwrap().tasks.len();
+    let payload = json!({ "pair": ["eth", "usd"] });
+
+    // each run must pay the price
+    let err = contract
+        .create_recurring(
+            "Feed".to_string(),
+            None,
+            payload.clone(),
+            30,
+            ScheduleEnd::Count(3),
+            &coins(3 * PRICE - 1, DENOM),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InsufficientPayment(PRICE, DENOM.to_string()).to_string()));
+
+    // the first task is created right away, the remainder of the split is refunded
+    let start = get_time(&chain);
+    let res = contract
+        .create_recurring(
+            "Feed".to_string(),
+            None,
+            payload.clone(),
+            30,
+            ScheduleEnd::Count(3),
+            &coins(3 * PRICE + 10, DENOM),
+        )
+        .unwrap();
+    let first = get_task_id(&res);
+    assert_eq!(balance(), 1000 - 3 * (PRICE + 3));
+    let task = contract.task(first).unwrap();
+    assert_eq!(task.payment, Some(Payment::from(coin(PRICE + 3, DENOM))));
+    assert_eq!(task.priority.u128(), 3);
+
+    let schedule = contract.schedule(1).unwrap();
+    assert_eq!(schedule.creator, requestor.addr().to_string());
+    assert_eq!(schedule.remaining, 2);
+    assert_eq!(schedule.next_run, start + 30);
+    assert_eq!(open_tasks(), 1);
+
+    // anyone can tick, but only due schedules create tasks
+    contract.call_as(&anyone).tick().unwrap();
+    assert_eq!(open_tasks(), 1);
+    chain.wait_seconds(30).unwrap();
+    contract.call_as(&anyone).tick().unwrap();
+    contract.call_as(&anyone).tick().unwrap();
+    assert_eq!(open_tasks(), 2);
+    assert_eq!(contract.schedule(1).unwrap().remaining, 1);
+
+    // the schedule is gone after its last run
+    chain.wait_seconds(30).unwrap();
+    contract.call_as(&anyone).tick().unwrap();
+    assert_eq!(open_tasks(), 3);
+    contract.schedule(1).unwrap_err();
+    assert!(contract
+        .list_schedules(None, None)
+        .unwrap()
+        .schedules
+        .is_empty());
+
+    // runs until a given time, and can be cancelled with a refund of the runs left
+    let now = get_time(&chain);
+    let before = balance();
+    contract
+        .create_recurring(
+            "Feed".to_string(),
+            None,
+            payload,
+            30,
+            ScheduleEnd::Until(now + 60),
+            &coins(3 * PRICE, DENOM),
+        )
+        .unwrap();
+    let schedules = contract.list_schedules(None, None).unwrap().schedules;
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].remaining, 2);
+
+    let err = contract
+        .call_as(&anyone)
+        .cancel_recurring(schedules[0].id)
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+    contract.cancel_recurring(schedules[0].id).unwrap();
+    assert_eq!(balance(), before - PRICE);
+    chain.wait_seconds(30).unwrap();
+    contract.tick().unwrap();
+    assert_eq!(open_tasks(), 4);
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn batch_create_and_complete<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, requestor, verifier) = payment_queue(&chain, None, None);
+    let balance = || query_balance(&chain, &requestor.addr());
+    let new_tasks = |count: usize| -> Vec<NewTask> {
+        (0..count)
+            .map(|i| NewTask {
+                description: format!("Task {i}"),
+                timeout: None,
+                payload: json!({ "pair": ["eth", "usd"], "index": i }),
+                depends_on: vec![],
+                callback: None,
+                verifier: None,
+            })
+            .collect()
+    };
+
+    // the funds must cover the price of every task
+    let err = contract
+        .create_batch(new_tasks(3), &coins(3 * PRICE - 1, DENOM))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InsufficientPayment(PRICE, DENOM.to_string()).to_string()));
+    let err = contract.create_batch(vec![], &[]).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InvalidBatchSize(MAX_BATCH_SIZE).to_string()));
+
+    // the remainder of the split is refunded
+    let res = contract
+        .create_batch(new_tasks(3), &coins(3 * PRICE + 2, DENOM))
+        .unwrap();
+    assert_eq!(balance(), 1000 - 3 * PRICE);
+    let ids: Vec<TaskId> = res
+        .event_attr_values("wasm", "task_id")
+        .into_iter()
+        .map(|id| id.parse().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 3);
+    for id in &ids {
+        let task = contract.task(*id).unwrap();
+        assert_eq!(task.payment, Some(Payment::from(coin(PRICE, DENOM))));
+    }
+
+    // only the verifier completes batches
+    let results = |ids: &[TaskId]| -> Vec<TaskResult> {
+        ids.iter()
+            .map(|id| TaskResult {
+                task_id: *id,
+                response: json!({ "price": "1" }),
+            })
+            .collect()
+    };
+    let err = contract.complete_batch(results(&ids)).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+
+    let verifier = contract.call_as(&verifier);
+    verifier.complete_batch(results(&ids[..2])).unwrap();
+    assert_eq!(contract.list_open(None, None).unwrap().tasks.len(), 1);
+    assert_eq!(contract.list_completed(None, None).unwrap().tasks.len(), 2);
+
+    // a batch fails as a whole
+    let err = verifier
+        .complete_batch(results(&[ids[2], ids[0]]))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskCompleted.to_string()));
+    assert_eq!(contract.task(ids[2]).unwrap().status, Status::Open {});
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn task_dependencies<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let refund = RefundInfo {
+        cancel: Decimal::percent(50),
+        timeout: Decimal::percent(50),
+    };
+    let (contract, requestor, verifier) = payment_queue(&chain, Some(refund), None);
+    let balance = || query_balance(&chain, &requestor.addr());
+    let price = coins(PRICE, DENOM);
+    let payload = json!({ "pair": ["eth", "usd"] });
+    let create = |name: &str, depends_on: Vec<TaskId>| {
+        contract.create(
+            name.to_string(),
+            None,
+            payload.clone(),
+            depends_on,
+            None,
+            None,
+            &price,
+        )
+    };
+
+    let one = get_task_id(&create("One", vec![]).unwrap());
+    let two = get_task_id(&create("Two", vec![]).unwrap());
+
+    // the tasks it depends on must exist, and the results need an object to go in
+    let err = create("Missing", vec![one, 99]).unwrap_err();
+    assert!(err.root().to_string().contains(
+        &ContractError::InvalidDependency("task 99 doesn't exist".to_string()).to_string()
+    ));
+    let err = contract
+        .create(
+            "Text".to_string(),
+            None,
+            json!("eth"),
+            vec![one],
+            None,
+            None,
+            &price,
+        )
+        .unwrap_err();
+    assert!(err.root().to_string().contains(
+        &ContractError::InvalidDependency("payload must be an object".to_string()).to_string()
+    ));
+
+    // it waits for both, and can't be completed before
+    let three = get_task_id(&create("Three", vec![one, two, one]).unwrap());
+    let task = contract.task(three).unwrap();
+    assert_eq!(task.status, Status::Pending {});
+    assert_eq!(task.depends_on, vec![one, two]);
+    let err = contract.task_status(three).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskPending.to_string()));
+    assert_eq!(contract.list_open(None, None).unwrap().tasks.len(), 2);
+
+    let verifier = contract.call_as(&verifier);
+    let err = verifier
+        .complete(three, json!({ "price": "3" }))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskPending.to_string()));
+    let err = verifier.timeout(three).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskPending.to_string()));
+
+    // it opens with the results once the last one is completed, and voting starts at that height
+    let res = verifier.complete(one, json!({ "price": "1" })).unwrap();
+    assert_eq!(res.event_attr_values("wasm", "opened_task_id").len(), 0);
+    assert_eq!(contract.task(three).unwrap().status, Status::Pending {});
+    chain.next_block().unwrap();
+    let res = verifier.complete(two, json!({ "price": "2" })).unwrap();
+    assert_eq!(
+        res.event_attr_values("wasm", "opened_task_id"),
+        vec![three.to_string()]
+    );
+    let status = contract.task_status(three).unwrap();
+    assert_eq!(status.status, TaskStatus::Open);
+    assert_eq!(status.created_height, chain.block_info().unwrap().height);
+    assert!(status.created_height > contract.task_status(one).unwrap().created_height);
+    let task = contract.task(three).unwrap();
+    assert_eq!(task.status, Status::Open {});
+    assert_eq!(
+        task.payload,
+        json!({
+            "pair": ["eth", "usd"],
+            "dependencies": {
+                one.to_string(): { "price": "1" },
+                two.to_string(): { "price": "2" },
+            },
+        })
+    );
+
+    let graph = contract.task_graph(one).unwrap();
+    assert_eq!(graph.depends_on, vec![]);
+    assert_eq!(
+        graph.dependents,
+        vec![TaskNode {
+            id: three,
+            status: Status::Open {}
+        }]
+    );
+    let graph = contract.task_graph(three).unwrap();
+    assert_eq!(
+        graph
+            .depends_on
+            .iter()
+            .map(|node| node.id)
+            .collect::<Vec<_>>(),
+        vec![one, two]
+    );
+    assert!(graph
+        .depends_on
+        .iter()
+        .all(|node| matches!(node.status, Status::Completed { .. })));
+
+    // depending on completed tasks only opens it right away
+    let four = get_task_id(&create("Four", vec![one]).unwrap());
+    assert_eq!(contract.task(four).unwrap().status, Status::Open {});
+
+    // a chain of pending tasks is cancelled and fully refunded when the first one expires
+    let five = get_task_id(&create("Five", vec![four]).unwrap());
+    let six = get_task_id(&create("Six", vec![five]).unwrap());
+    let before = balance();
+    chain.wait_seconds(100).unwrap();
+    let err = create("Late", vec![four]).unwrap_err();
+    assert!(err.root().to_string().contains(
+        &ContractError::InvalidDependency(format!("task {four} can't be completed anymore"))
+            .to_string()
+    ));
+
+    let res = contract.timeout(four).unwrap();
+    assert_eq!(
+        res.event_attr_values("wasm", "cancelled_task_id"),
+        vec![five.to_string(), six.to_string()]
+    );
+    assert_eq!(balance(), before + 2 * PRICE + PRICE / 2);
+    assert_eq!(contract.task(five).unwrap().status, Status::Cancelled {});
+    assert_eq!(contract.task(six).unwrap().status, Status::Cancelled {});
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn timeout_batch_and_list_expired<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, requestor, _) = payment_queue(&chain, None, None);
+    let balance = || query_balance(&chain, &requestor.addr());
+    let create = |name: &str| {
+        let res = contract
+            .create(
+                name.to_string(),
+                None,
+                json!({ "pair": ["eth", "usd"] }),
+                vec![],
+                None,
+                None,
+                &coins(PRICE, DENOM),
+            )
+            .unwrap();
+        get_task_id(&res)
+    };
+
+    let one = create("One");
+    let two = create("Two");
+    chain.wait_seconds(50).unwrap();
+    let three = create("Three");
+    chain.wait_seconds(60).unwrap();
+
+    // the overdue tasks are listed before anyone marks them
+    let expired = contract.list_expired(None, None).unwrap().tasks;
+    assert_eq!(
+        expired.iter().map(|t| (t.id, t.marked)).collect::<Vec<_>>(),
+        vec![(one, false), (two, false)]
+    );
+
+    // anyone can mark them, up to the limit, the longest overdue first
+    let anyone = contract.call_as(&chain.alt_signer(ANYONE_INDEX));
+    let before = balance();
+    let res = anyone.timeout_batch(Some(1)).unwrap();
+    assert_eq!(
+        res.event_attr_values("task_expired", "task_id"),
+        vec![one.to_string()]
+    );
+    assert_eq!(balance(), before + PRICE);
+    assert_eq!(contract.task(one).unwrap().status, Status::Expired {});
+    assert_eq!(contract.task(two).unwrap().status, Status::Open {});
+
+    // the marked ones come first, and pages resume across both
+    let page = contract.list_expired(None, Some(1)).unwrap().tasks;
+    assert_eq!(page.len(), 1);
+    assert_eq!((page[0].id, page[0].marked), (one, true));
+    let page = contract.list_expired(Some(one), Some(1)).unwrap().tasks;
+    assert_eq!((page[0].id, page[0].marked), (two, false));
+    assert_eq!(
+        contract.list_expired(Some(two), None).unwrap().tasks,
+        vec![]
+    );
+
+    let res = anyone.timeout_batch(None).unwrap();
+    assert_eq!(
+        res.event_attr_values("task_expired", "task_id"),
+        vec![two.to_string()]
+    );
+    assert_eq!(balance(), before + 2 * PRICE);
+
+    // nothing left to mark, the last task is still running
+    let res = anyone.timeout_batch(None).unwrap();
+    assert_eq!(
+        res.event_attr_value("wasm", "expired_count").unwrap(),
+        "0".to_string()
+    );
+    let expired = contract.list_expired(None, None).unwrap().tasks;
+    assert_eq!(
+        expired.iter().map(|t| (t.id, t.marked)).collect::<Vec<_>>(),
+        vec![(one, true), (two, true)]
+    );
+    assert_eq!(contract.task(three).unwrap().status, Status::Open {});
+}
+
+pub fn blocked_schedules_dont_starve_tick<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let alice = chain.alt_signer(REQUESTOR_INDEX);
+    let bob = chain.alt_signer(OTHER_INDEX);
+    let blocked = MAX_TICK_TASKS as u64 + 1;
+    let msg = InstantiateMsg {
+        allowed_requestors: vec![alice.addr().to_string(), bob.addr().to_string()],
+        limits: Some(RateLimits {
+            max_open_per_requestor: Some(blocked as u32),
+            ..RateLimits::default()
+        }),
+        ..queue_msg(&chain, Requestor::AllowList { payment: vec![] })
+    };
+    let contract = setup(chain.clone(), msg);
+    let create = |by: &C::Sender| {
+        contract
+            .call_as(by)
+            .create_recurring(
+                "Feed".to_string(),
+                None,
+                json!({ "pair": ["eth", "usd"] }),
+                30,
+                ScheduleEnd::Count(3),
+                &[],
+            )
+            .unwrap();
+    };
+    let open_tasks = || contract.list_open(None, None).unwrap().tasks.len();
+
+    // more schedules than a tick handles fill the open tasks alice may have, ahead of bob's
+    for _ in 0..blocked {
+        create(&alice);
+    }
+    create(&bob);
+    chain.wait_seconds(30).unwrap();
+
+    // the first tick only gets to blocked ones, and moves them one interval later
+    let res = contract.tick().unwrap();
+    let skipped = res.event_attr_values("recurring_task_skipped", "schedule_id");
+    assert_eq!(skipped.len(), MAX_TICK_TASKS);
+    assert!(res
+        .event_attr_values("recurring_task", "schedule_id")
+        .is_empty());
+    let now = get_time(&chain);
+    let schedule = contract.schedule(1).unwrap();
+    assert_eq!(schedule.next_run, now + 30);
+    assert_eq!(schedule.remaining, 2);
+    let before = open_tasks();
+
+    // so the next one reaches the good schedule
+    let res = contract.tick().unwrap();
+    assert_eq!(
+        res.event_attr_values("recurring_task_skipped", "schedule_id"),
+        vec![blocked.to_string()]
+    );
+    assert_eq!(
+        res.event_attr_values("recurring_task", "schedule_id"),
+        vec![(blocked + 1).to_string()]
+    );
+    assert_eq!(open_tasks(), before + 1);
+}
+
+/// The ids and the cursor of a page of tasks
+fn page_ids(res: ListTasksResponse) -> (Vec<TaskId>, Option<TaskId>) {
+    (
+        res.tasks.into_iter().map(|task| task.id).collect(),
+        res.next_cursor,
+    )
+}
+
+pub fn status_filter_scans_are_bounded<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, _verifier) = fixed_requestor(&chain, 200);
+    let payload = json!({ "pair": ["eth", "usd"] });
+    let expired = make_task(&contract, "Expired", 100, &payload);
+    chain.wait_seconds(100).unwrap();
+
+    // more open tasks than a query looks at come after the expired one
+    let count = MAX_SCANNED_TASKS + MAX_BATCH_SIZE;
+    for start in (0..count).step_by(MAX_BATCH_SIZE) {
+        let tasks = (start..start + MAX_BATCH_SIZE)
+            .map(|i| NewTask {
+                description: format!("Task {i}"),
+                timeout: None,
+                payload: payload.clone(),
+                depends_on: vec![],
+                callback: None,
+                verifier: None,
+            })
+            .collect();
+        contract.create_batch(tasks, &[]).unwrap();
+    }
+    let last = TaskId::new(expired.u64() + count as u64);
+
+    // the first page stops at the last task looked at, without a match
+    let expired_page = |start_after: Option<TaskId>| {
+        page_ids(
+            contract
+                .list_tasks(Some(Status::Expired {}), start_after, Some(10), None)
+                .unwrap(),
+        )
+    };
+    let cursor = TaskId::new(last.u64() + 1 - MAX_SCANNED_TASKS as u64);
+    assert_eq!(expired_page(None), (vec![], Some(cursor)));
+
+    // and the next one resumes from there
+    assert_eq!(expired_page(Some(cursor)), (vec![expired], None));
+
+    // the tasks of a requestor are looked at the same way
+    let requestor_page = |start_after: Option<TaskId>| {
+        page_ids(
+            contract
+                .tasks_by_requestor(
+                    chain.sender_addr().to_string(),
+                    Some(Status::Expired {}),
+                    start_after,
+                    Some(10),
+                )
+                .unwrap(),
+        )
+    };
+    assert_eq!(requestor_page(None), (vec![], Some(cursor)));
+    assert_eq!(requestor_page(Some(cursor)), (vec![expired], None));
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn list_tasks_pages<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, _, verifier) = payment_queue(&chain, None, None);
+    let create = |name: &str| {
+        let res = contract
+            .create(
+                name.to_string(),
+                None,
+                json!({ "pair": ["eth", "usd"] }),
+                vec![],
+                None,
+                None,
+                &coins(PRICE, DENOM),
+            )
+            .unwrap();
+        get_task_id(&res)
+    };
+
+    let one = create("One");
+    let two = create("Two");
+    let three = create("Three");
+    contract
+        .call_as(&verifier)
+        .complete(one, json!({ "price": "1" }))
+        .unwrap();
+    contract.cancel(two).unwrap();
+    chain.wait_seconds(100).unwrap();
+    let four = create("Four");
+    let five = create("Five");
+
+    // newest first by default, in pages following the cursor
+    let all = page_ids(contract.list_tasks(None, None, None, None).unwrap());
+    assert_eq!(all, (vec![five, four, three, two, one], None));
+    let page = page_ids(contract.list_tasks(None, None, Some(2), None).unwrap());
+    assert_eq!(page, (vec![five, four], Some(four)));
+    let page = page_ids(contract.list_tasks(None, page.1, Some(2), None).unwrap());
+    assert_eq!(page, (vec![three, two], Some(two)));
+    let page = page_ids(contract.list_tasks(None, page.1, Some(2), None).unwrap());
+    assert_eq!(page, (vec![one], None));
+
+    let page = page_ids(
+        contract
+            .list_tasks(None, None, Some(2), Some(Order::Ascending))
+            .unwrap(),
+    );
+    assert_eq!(page, (vec![one, two], Some(two)));
+    let page = page_ids(
+        contract
+            .list_tasks(None, page.1, Some(3), Some(Order::Ascending))
+            .unwrap(),
+    );
+    assert_eq!(page, (vec![three, four, five], None));
+
+    // by status as reported, the third one expired without being marked
+    let by_status =
+        |status: Status| page_ids(contract.list_tasks(Some(status), None, None, None).unwrap()).0;
+    assert_eq!(by_status(Status::Open {}), vec![five, four]);
+    assert_eq!(by_status(Status::Expired {}), vec![three]);
+    assert_eq!(by_status(Status::Completed { completed: 0 }), vec![one]);
+    assert_eq!(by_status(Status::Cancelled {}), vec![two]);
+    assert_eq!(by_status(Status::Pending {}), vec![]);
+
+    contract.timeout(three).unwrap();
+    let page = page_ids(
+        contract
+            .list_tasks(Some(Status::Expired {}), None, Some(1), None)
+            .unwrap(),
+    );
+    assert_eq!(page, (vec![three], None));
+}
+
+/// The requestor and the other signer must be funded with 1000 of `DENOM`
+pub fn tasks_by_requestor_and_creation<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, requestor, verifier) = payment_queue(&chain, None, None);
+    let other = chain.alt_signer(OTHER_INDEX);
+    let create = |by: &C::Sender, name: &str| {
+        let res = contract
+            .call_as(by)
+            .create(
+                name.to_string(),
+                None,
+                json!({ "pair": ["eth", "usd"] }),
+                vec![],
+                None,
+                None,
+                &coins(PRICE, DENOM),
+            )
+            .unwrap();
+        get_task_id(&res)
+    };
+
+    let start = get_time(&chain);
+    let one = create(&requestor, "One");
+    let two = create(&other, "Two");
+    chain.wait_seconds(30).unwrap();
+    let three = create(&requestor, "Three");
+    let four = create(&requestor, "Four");
+    chain.wait_seconds(30).unwrap();
+    let five = create(&other, "Five");
+    assert_eq!(contract.task(three).unwrap().created_at, start + 30);
+
+    contract
+        .call_as(&verifier)
+        .complete(three, json!({ "price": "3" }))
+        .unwrap();
+
+    // each requestor sees their own tasks, with the results
+    let mine = contract
+        .tasks_by_requestor(requestor.addr().to_string(), None, None, None)
+        .unwrap();
+    assert_eq!(mine.tasks[1].result, Some(json!({ "price": "3" })));
+    assert_eq!(page_ids(mine), (vec![four, three, one], None));
+    let page = page_ids(
+        contract
+            .tasks_by_requestor(requestor.addr().to_string(), None, Some(four), Some(1))
+            .unwrap(),
+    );
+    assert_eq!(page, (vec![three], Some(three)));
+    let theirs = contract
+        .tasks_by_requestor(other.addr().to_string(), Some(Status::Open {}), None, None)
+        .unwrap();
+    assert_eq!(page_ids(theirs), (vec![five, two], None));
+    let completed = contract
+        .tasks_by_requestor(
+            requestor.addr().to_string(),
+            Some(Status::Completed { completed: 0 }),
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(page_ids(completed), (vec![three], None));
+
+    // by creation time, both ends included, oldest first
+    let between = |from: u64, to: u64, start_after: Option<TaskId>, limit: Option<u32>| {
+        page_ids(
+            contract
+                .tasks_created_between(from, to, start_after, limit)
+                .unwrap(),
+        )
+    };
+    assert_eq!(
+        between(start, start + 30, None, None),
+        (vec![one, two, three, four], None)
+    );
+    assert_eq!(
+        between(start + 1, start + 60, None, None),
+        (vec![three, four, five], None)
+    );
+    assert_eq!(between(start + 31, start + 59, None, None), (vec![], None));
+
+    // pages resume among the tasks created at the same time
+    let page = between(start, start + 60, None, Some(3));
+    assert_eq!(page, (vec![one, two, three], Some(three)));
+    let page = between(start, start + 60, page.1, Some(3));
+    assert_eq!(page, (vec![four, five], None));
+}
+
+/// The requestor must be funded with 1000 of `DENOM`. `receiver` must record the
+/// completions it gets as `received_task_id` and `received_result` attributes,
+/// and reject the expirations.
+pub fn result_callbacks<C>(chain: C, receiver: Addr)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, requestor, verifier) = payment_queue(&chain, None, None);
+    let balance = || query_balance(&chain, &requestor.addr());
+    assert_eq!(contract.config().unwrap().callback_gas_limit, 200_000);
+
+    let create = |callback: &str| {
+        contract.create(
+            "Callback".to_string(),
+            None,
+            json!({ "pair": ["eth", "usd"] }),
+            vec![],
+            Some(callback.to_string()),
+            None,
+            &coins(PRICE, DENOM),
+        )
+    };
+
+    // the callback must be an address
+    create("receiver").unwrap_err();
+
+    // the result is sent when the task is completed
+    let one = get_task_id(&create(receiver.as_str()).unwrap());
+    assert_eq!(
+        contract.task(one).unwrap().callback,
+        Some(receiver.to_string())
+    );
+    let res = contract
+        .call_as(&verifier)
+        .complete(one, json!({ "price": "1" }))
+        .unwrap();
+    assert_eq!(
+        res.event_attr_value("wasm", "received_task_id").unwrap(),
+        one.to_string()
+    );
+    assert_eq!(
+        res.event_attr_value("wasm", "received_result").unwrap(),
+        json!({ "price": "1" }).to_string()
+    );
+
+    // a failing callback doesn't stop the timeout, nor the refund
+    let two = get_task_id(&create(receiver.as_str()).unwrap());
+    chain.wait_seconds(100).unwrap();
+    let before = balance();
+    let res = contract.timeout(two).unwrap();
+    assert!(res
+        .event_attr_values("wasm", "action")
+        .contains(&"callback_failed".to_string()));
+    assert_eq!(contract.task(two).unwrap().status, Status::Expired {});
+    assert_eq!(balance(), before + PRICE);
+
+    // only the owner sets the gas limit
+    let err = contract.update_callback_gas_limit(500_000).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+    contract
+        .call_as(chain.sender())
+        .update_callback_gas_limit(500_000)
+        .unwrap();
+    assert_eq!(contract.config().unwrap().callback_gas_limit, 500_000);
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn payload_schema<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, _, _) = payment_queue(&chain, None, None);
+    let owner = contract.call_as(chain.sender());
+    assert_eq!(contract.payload_schema().unwrap().schema, None);
+    let create = |payload: serde_json::Value| {
+        contract.create(
+            "Price".to_string(),
+            None,
+            payload,
+            vec![],
+            None,
+            None,
+            &coins(PRICE, DENOM),
+        )
+    };
+    let assert_err = |payload: serde_json::Value, expected: ContractError| {
+        let err = create(payload).unwrap_err();
+        assert!(err.root().to_string().contains(&expected.to_string()));
+    };
+
+    // only the owner sets it, and it must make sense
+    let schema = PayloadSchema {
+        required: vec!["pair".to_string()],
+        allowed_values: [(
+            "pair".to_string(),
+            vec![json!(["eth", "usd"]), json!(["btc", "usd"])],
+        )]
+        .into(),
+        max_size: Some(100),
+    };
+    let err = contract
+        .update_payload_schema(Some(schema.clone()))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+    let err = owner
+        .update_payload_schema(Some(PayloadSchema {
+            max_size: Some(0),
+            ..schema.clone()
+        }))
+        .unwrap_err();
+    assert!(err.root().to_string().contains(
+        &ContractError::InvalidPayloadSchema("max size must be greater than zero".to_string())
+            .to_string()
+    ));
+    owner.update_payload_schema(Some(schema.clone())).unwrap();
+    assert_eq!(contract.payload_schema().unwrap().schema, Some(schema));
+
+    // payloads not following it are rejected, saying why
+    assert_err(json!("eth/usd"), ContractError::PayloadNotObject);
+    assert_err(
+        json!({ "index": 1 }),
+        ContractError::MissingPayloadField("pair".to_string()),
+    );
+    assert_err(
+        json!({ "pair": ["doge", "usd"] }),
+        ContractError::PayloadValueNotAllowed {
+            field: "pair".to_string(),
+            value: json!(["doge", "usd"]).to_string(),
+            allowed: json!([["eth", "usd"], ["btc", "usd"]]).to_string(),
+        },
+    );
+    let large = json!({ "pair": ["eth", "usd"], "note": "x".repeat(100) });
+    let size = serde_json::to_vec(&large).unwrap().len();
+    assert_err(large, ContractError::PayloadTooLarge { size, max: 100 });
+
+    create(json!({ "pair": ["btc", "usd"], "note": "fine" })).unwrap();
+
+    // without it, anything goes again
+    owner.update_payload_schema(None).unwrap();
+    create(json!("eth/usd")).unwrap();
+}
+
+/// The requestor must be funded with 1000 of `DENOM`
+pub fn verifier_rotation<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let (contract, _, old) = payment_queue(&chain, None, None);
+    let owner = contract.call_as(chain.sender());
+    let new = chain.alt_signer(5);
+    let other = chain.alt_signer(6);
+    let create = |verifier: Option<&C::Sender>| {
+        contract
+            .create(
+                "Price".to_string(),
+                None,
+                json!({ "pair": ["eth", "usd"] }),
+                vec![],
+                None,
+                verifier.map(|verifier| verifier.addr().to_string()),
+                &coins(PRICE, DENOM),
+            )
+            .map(|res| get_task_id(&res))
+    };
+    let complete = |verifier: &C::Sender, task_id: TaskId| {
+        contract
+            .call_as(verifier)
+            .complete(task_id, json!({ "price": "1" }))
+    };
+    let assert_unauthorized = |err: CwOrchError| {
+        assert!(err
+            .root()
+            .to_string()
+            .contains(&ContractError::Unauthorized.to_string()));
+    };
+    let (old_addr, new_addr, other_addr) = (
+        old.addr().to_string(),
+        new.addr().to_string(),
+        other.addr().to_string(),
+    );
+
+    // only the default verifier to begin with
+    let verifiers = contract.verifiers(None, None).unwrap();
+    assert_eq!(verifiers.default, old_addr);
+    assert_eq!(verifiers.verifiers, vec![old_addr.clone()]);
+
+    // tasks can only choose one of the verifiers, which the owner manages
+    let err = create(Some(&other)).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NotVerifier(other_addr.clone()).to_string()));
+    assert_unauthorized(
+        contract
+            .update_verifiers(vec![other_addr.clone()], vec![])
+            .unwrap_err(),
+    );
+    owner
+        .update_verifiers(vec![other_addr.clone()], vec![])
+        .unwrap();
+    let chosen = create(Some(&other)).unwrap();
+    assert_eq!(contract.task(chosen).unwrap().verifier, other_addr);
+    assert_unauthorized(complete(&old, chosen).unwrap_err());
+    complete(&other, chosen).unwrap();
+
+    // rotating makes new tasks use the new one, while in-flight tasks keep the old one
+    let in_flight = create(None).unwrap();
+    assert_eq!(contract.task(in_flight).unwrap().verifier, old_addr);
+    assert_unauthorized(contract.set_default_verifier(new_addr.clone()).unwrap_err());
+    owner.set_default_verifier(new_addr.clone()).unwrap();
+    let rotated = create(None).unwrap();
+    assert_eq!(contract.task(rotated).unwrap().verifier, new_addr);
+    assert_unauthorized(complete(&old, rotated).unwrap_err());
+    assert_unauthorized(complete(&new, in_flight).unwrap_err());
+
+    // the default one can't be removed, the old one can and still completes its tasks
+    let err = owner
+        .update_verifiers(vec![], vec![new_addr.clone()])
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::RemoveDefaultVerifier.to_string()));
+    owner
+        .update_verifiers(vec![], vec![old_addr.clone()])
+        .unwrap();
+    let verifiers = contract.verifiers(None, None).unwrap();
+    assert_eq!(verifiers.default, new_addr);
+    let mut expected = vec![new_addr, other_addr];
+    expected.sort();
+    assert_eq!(verifiers.verifiers, expected);
+    let paged = contract
+        .verifiers(Some(expected[0].clone()), Some(1))
+        .unwrap();
+    assert_eq!(paged.verifiers, vec![expected[1].clone()]);
+    assert!(create(Some(&old)).is_err());
+
+    complete(&old, in_flight).unwrap();
+    complete(&new, rotated).unwrap();
+    assert_eq!(contract.list_completed(None, None).unwrap().tasks.len(), 3);
+}
+
 #[track_caller]
 pub fn get_time(chain: &impl QueryHandler) -> u64 {
     chain.block_info().unwrap().time.seconds()
 }
 
+#[track_caller]
+pub fn query_balance<C: CwEnv>(chain: &C, addr: &Addr) -> u128 {
+    chain
+        .bank_querier()
+        .balance(addr, Some(DENOM.to_string()))
+        .unwrap()
+        .first()
+        .map(|coin| coin.amount.u128())
+        .unwrap_or_default()
+}
+
 #[track_caller]
 pub fn make_task<C: ChainState + TxHandler>(
     contract: &TaskContract<C>,
@@ -468,7 +2266,15 @@ pub fn make_task<C: ChainState + TxHandler>(
     payload: &serde_json::Value,
 ) -> TaskId {
     let res = contract
-        .create(name.to_string(), timeout.into(), payload.clone(), &[])
+        .create(
+            name.to_string(),
+            timeout.into(),
+            payload.clone(),
+            vec![],
+            None,
+            None,
+            &[],
+        )
         .unwrap();
     get_task_id(&res)
 }
@@ -1,9 +1,13 @@
 // use cw_orch::environment::IndexResponse;
+use cosmwasm_std::{coin, coins, Empty};
 use cw_orch::prelude::*;
+use lavs_orch::{Addressable, AltSigner};
 
 use crate::interface::Contract;
 use crate::msg::{InstantiateMsg, Requestor, TimeoutInfo};
 
+use super::common::{DENOM, OTHER_INDEX, REQUESTOR_INDEX};
+
 // TODO: shared variable
 const BECH_PREFIX: &str = "layer";
 
@@ -65,8 +69,202 @@ fn sanity_check() {
             maximum: None,
         },
         verifier: verifier.to_string(),
+        verifiers: vec![],
+        refund: None,
+        owner: None,
+        fees: None,
+        allowed_requestors: vec![],
+        limits: None,
+        callback_gas_limit: None,
+        payload_schema: None,
     };
     let init_res = tasker.instantiate(&msg, None, &[]).unwrap();
     let contract_addr = init_res.instantiated_contract_address().unwrap();
     assert_eq!(contract_addr, tasker.address().unwrap());
 }
+
+/// Gives the requestor and the other signer 1000 of `DENOM` to pay for tasks
+fn funded_chain() -> MockBech32 {
+    let chain = MockBech32::new(BECH_PREFIX);
+    for signer in [REQUESTOR_INDEX, OTHER_INDEX] {
+        chain
+            .add_balance(&chain.alt_signer(signer).addr(), coins(1000, DENOM))
+            .unwrap();
+    }
+    chain
+}
+
+#[test]
+fn cancel_refunds_creator() {
+    let chain = funded_chain();
+    super::common::cancel_refunds_creator(chain);
+}
+
+#[test]
+fn timeout_refunds_creator() {
+    let chain = funded_chain();
+    super::common::timeout_refunds_creator(chain);
+}
+
+#[test]
+fn invalid_refund_info() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::invalid_refund_info(chain);
+}
+
+#[test]
+fn fees_are_split_and_withdrawn() {
+    let chain = funded_chain();
+    super::common::fees_are_split_and_withdrawn(chain);
+}
+
+#[test]
+fn allow_list_requestors() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::allow_list_requestors(chain);
+}
+
+#[test]
+fn allow_list_with_payment() {
+    let chain = funded_chain();
+    super::common::allow_list_with_payment(chain);
+}
+
+#[test]
+fn multi_denom_payment() {
+    let chain = funded_chain();
+    chain
+        .add_balance(
+            &chain.alt_signer(REQUESTOR_INDEX).addr(),
+            vec![coin(1000, "uatom"), coin(1000, "ufoo")],
+        )
+        .unwrap();
+    super::common::multi_denom_payment(chain);
+}
+
+#[test]
+fn cw20_payment() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::cw20_payment(chain);
+}
+
+#[test]
+fn rate_limits() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::rate_limits(chain);
+}
+
+#[test]
+fn priority_fees_order_open_tasks() {
+    let chain = funded_chain();
+    super::common::priority_fees_order_open_tasks(chain);
+}
+
+#[test]
+fn recurring_tasks() {
+    let chain = funded_chain();
+    super::common::recurring_tasks(chain);
+}
+
+#[test]
+fn blocked_schedules_dont_starve_tick() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::blocked_schedules_dont_starve_tick(chain);
+}
+
+#[test]
+fn batch_create_and_complete() {
+    let chain = funded_chain();
+    super::common::batch_create_and_complete(chain);
+}
+
+#[test]
+fn task_dependencies() {
+    let chain = funded_chain();
+    super::common::task_dependencies(chain);
+}
+
+#[test]
+fn timeout_batch_and_list_expired() {
+    let chain = funded_chain();
+    super::common::timeout_batch_and_list_expired(chain);
+}
+
+#[test]
+fn list_tasks_pages() {
+    let chain = funded_chain();
+    super::common::list_tasks_pages(chain);
+}
+
+#[test]
+fn status_filter_scans_are_bounded() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::status_filter_scans_are_bounded(chain);
+}
+
+#[test]
+fn tasks_by_requestor_and_creation() {
+    let chain = funded_chain();
+    super::common::tasks_by_requestor_and_creation(chain);
+}
+
+/// Records the callbacks it gets in events, and rejects the expirations
+mod callback_receiver {
+    use cosmwasm_std::{
+        Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+    };
+
+    use crate::msg::CallbackMsg;
+
+    pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(_: DepsMut, _: Env, _: MessageInfo, msg: CallbackMsg) -> StdResult<Response> {
+        match msg {
+            CallbackMsg::TaskCompleted { task_id, result } => Ok(Response::new()
+                .add_attribute("received_task_id", task_id.to_string())
+                .add_attribute("received_result", result.to_string())),
+            CallbackMsg::TaskExpired { .. } => Err(StdError::generic_err("not interested")),
+        }
+    }
+
+    pub fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+        Ok(Binary::default())
+    }
+}
+
+#[test]
+fn result_callbacks() {
+    let chain = funded_chain();
+    let code_id = chain
+        .upload_custom(
+            "callback_receiver",
+            Box::new(ContractWrapper::new_with_empty(
+                callback_receiver::execute,
+                callback_receiver::instantiate,
+                callback_receiver::query,
+            )),
+        )
+        .unwrap()
+        .uploaded_code_id()
+        .unwrap();
+    let receiver = chain
+        .instantiate(code_id, &Empty {}, Some("receiver"), None, &[])
+        .unwrap()
+        .instantiated_contract_address()
+        .unwrap();
+    super::common::result_callbacks(chain, receiver);
+}
+
+#[test]
+fn payload_schema() {
+    let chain = funded_chain();
+    super::common::payload_schema(chain);
+}
+
+#[test]
+fn verifier_rotation() {
+    let chain = funded_chain();
+    super::common::verifier_rotation(chain);
+}
@@ -110,6 +110,16 @@ pub enum DeployCommand {
         /// "deployer" - will require the caller be the same as the deployer
         #[clap(short, long, default_value_t = DeployTaskRequestor::default())]
         requestor: DeployTaskRequestor,
+
+        /// Maximum share of the total voting power a single operator can hold
+        #[clap(long)]
+        max_power_share: Option<Decimal>,
+        /// Minimum number of operators in the set
+        #[clap(long)]
+        min_operators: Option<u32>,
+        /// Minimum voting power of every operator
+        #[clap(long)]
+        min_power: Option<u128>,
     },
 }
 
@@ -169,6 +179,28 @@ pub enum TaskQueueCommand {
         timeout: Option<u64>,
     },
 
+    /// Registers a schedule creating the same task at a regular interval
+    AddRecurringTask {
+        /// The body of each task, must be valid JSON
+        #[clap(short, long)]
+        body: String,
+        /// Human-readable description of the tasks
+        #[clap(short, long)]
+        description: String,
+        /// Specify a task timeout, or use the default
+        #[clap(short, long)]
+        timeout: Option<u64>,
+        /// Seconds between two tasks
+        #[clap(short, long)]
+        interval: u64,
+        /// How many tasks to create, all of them are paid upfront
+        #[clap(short, long)]
+        count: u32,
+    },
+
+    /// Creates the tasks of the schedules that are due
+    Tick,
+
     /// View the task queue
     ViewQueue {
         #[clap(short, long)]
@@ -210,6 +242,11 @@ impl FaucetCommand {
 pub struct WasmaticArgs {
     #[command(subcommand)]
     pub command: WasmaticCommand,
+
+    /// Operator contract to discover the wasmatic endpoints from,
+    /// instead of using the ones in config.json
+    #[clap(long, global = true)]
+    pub operators: Option<String>,
 }
 
 #[derive(Clone, Subcommand)]
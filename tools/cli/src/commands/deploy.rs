@@ -1,6 +1,7 @@
 use crate::{args::DeployTaskRequestor, config::load_wasmatic_addresses, context::AppContext};
 use anyhow::{anyhow, bail, Result};
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, Uint128};
+use lavs_mock_operators::state::Constraints;
 use lavs_task_queue::msg::{Requestor, TimeoutInfo};
 use layer_climb::prelude::*;
 use std::path::PathBuf;
@@ -16,6 +17,7 @@ pub struct DeployContractArgs {
     threshold_percentage: Decimal,
     allowed_spread: Decimal,
     slashable_spread: Decimal,
+    constraints: Constraints,
 }
 
 impl DeployContractArgs {
@@ -30,6 +32,7 @@ impl DeployContractArgs {
         slashable_spread: Decimal,
         operators: Vec<String>,
         requestor: DeployTaskRequestor,
+        constraints: Constraints,
     ) -> Result<Self> {
         if operators.is_empty() {
             bail!("At least one operator must be specified");
@@ -72,6 +75,9 @@ impl DeployContractArgs {
             }
         }
 
+        // fail before uploading anything if the contract would reject the operators
+        validate_constraints(&instantiate_operators, &constraints)?;
+
         let requestor = match requestor {
             DeployTaskRequestor::Deployer => {
                 Requestor::Fixed(ctx.signing_client().await?.addr.to_string())
@@ -98,10 +104,30 @@ impl DeployContractArgs {
             threshold_percentage,
             allowed_spread,
             slashable_spread,
+            constraints,
         })
     }
 }
 
+fn validate_constraints(
+    operators: &[lavs_mock_operators::msg::InstantiateOperator],
+    constraints: &Constraints,
+) -> Result<()> {
+    constraints.validate()?;
+
+    let powers: Vec<Uint128> = operators
+        .iter()
+        .map(|op| Uint128::from(op.voting_power))
+        .collect();
+    constraints.check(
+        powers.len() as u32,
+        powers.iter().min().copied().unwrap_or_default(),
+        powers.iter().max().copied().unwrap_or_default(),
+        powers.iter().sum(),
+    )?;
+    Ok(())
+}
+
 pub async fn deploy_contracts(
     ctx: AppContext,
     args: DeployContractArgs,
@@ -117,6 +143,7 @@ pub async fn deploy_contracts(
         threshold_percentage,
         allowed_spread,
         slashable_spread,
+        constraints,
     } = args;
 
     let wasm_files = WasmFiles::read(artifacts_path.clone()).await?;
@@ -136,7 +163,11 @@ pub async fn deploy_contracts(
             client.addr.clone(),
             operators_code_id,
             "Mock Operators",
-            &lavs_mock_operators::msg::InstantiateMsg { operators },
+            &lavs_mock_operators::msg::InstantiateMsg {
+                operators,
+                admin: Some(client.addr.to_string()),
+                constraints,
+            },
             vec![],
             None,
         )
@@ -156,6 +187,8 @@ pub async fn deploy_contracts(
                 threshold_percentage,
                 allowed_spread,
                 slashable_spread,
+                cross_check: None,
+                dispute: None,
             },
             vec![],
             None,
@@ -174,6 +207,14 @@ pub async fn deploy_contracts(
                 requestor,
                 timeout: task_timeout,
                 verifier: verifier_addr.to_string(),
+                verifiers: vec![],
+                refund: None,
+                owner: None,
+                fees: None,
+                allowed_requestors: vec![],
+                limits: None,
+                callback_gas_limit: None,
+                payload_schema: None,
             },
             vec![],
             None,
@@ -1,6 +1,6 @@
 use crate::context::AppContext;
 use anyhow::Result;
-use lavs_mock_operators::msg::{AllVotersResponse, QueryMsg};
+use lavs_mock_operators::msg::{AllVotersResponse, OperatorInfoResponse, QueryMsg};
 use layer_climb::prelude::*;
 
 pub struct OperatorQuerier {
@@ -10,6 +10,8 @@ pub struct OperatorQuerier {
 }
 
 impl OperatorQuerier {
+    const PAGE_SIZE: u32 = 100;
+
     pub async fn new(ctx: AppContext, contract_addr: Address) -> Result<Self> {
         let querier = ctx.query_client().await?;
         Ok(Self {
@@ -20,22 +22,67 @@ impl OperatorQuerier {
     }
 
     pub async fn all_operators(&self) -> Result<Vec<Operator>> {
-        let all_voters: AllVotersResponse = self
-            .querier
-            .contract_smart(&self.contract_addr, &QueryMsg::AllVoters {})
-            .await?;
+        let mut operators = Vec::new();
+        let mut start_after = None;
 
-        all_voters
-            .voters
-            .into_iter()
-            .map(|v| {
+        // load page by page so large operator sets stay within the query gas limit
+        loop {
+            let all_voters: AllVotersResponse = self
+                .querier
+                .contract_smart(
+                    &self.contract_addr,
+                    &QueryMsg::AllVoters {
+                        start_after: start_after.clone(),
+                        limit: Some(Self::PAGE_SIZE),
+                    },
+                )
+                .await?;
+
+            let Some(last) = all_voters.voters.last() else {
+                break;
+            };
+            start_after = Some(last.address.clone());
+
+            for v in all_voters.voters {
                 let address = self.ctx.chain_config()?.parse_address(&v.address)?;
-                Ok(Operator {
+                operators.push(Operator {
                     address,
                     power: v.power.u128(),
-                })
-            })
-            .collect()
+                });
+            }
+        }
+
+        Ok(operators)
+    }
+
+    pub async fn operator_info(&self, address: &Address) -> Result<Option<OperatorInfoResponse>> {
+        self.querier
+            .contract_smart(
+                &self.contract_addr,
+                &QueryMsg::OperatorInfo {
+                    address: address.to_string(),
+                },
+            )
+            .await
+    }
+
+    /// The wasmatic endpoints published on chain by the operators
+    pub async fn wasmatic_endpoints(&self) -> Result<Vec<String>> {
+        let operators = self.all_operators().await?;
+        let infos = futures::future::join_all(
+            operators
+                .iter()
+                .map(|operator| self.operator_info(&operator.address)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        Ok(infos
+            .into_iter()
+            .flatten()
+            .filter_map(|info| info.metadata.wasmatic_endpoint)
+            .collect())
     }
 }
 
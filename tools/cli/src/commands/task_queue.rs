@@ -4,12 +4,12 @@ use crate::{
     context::AppContext,
 };
 use anyhow::{bail, Context, Result};
-use cosmwasm_std::Order;
-use lavs_apis::{
-    id::TaskId,
-    tasks::{CompletedTaskOverview, ListCompletedResponse, ListOpenResponse, OpenTaskOverview},
+use cosmwasm_std::{Order, Uint128};
+use lavs_apis::id::TaskId;
+use lavs_task_queue::msg::{
+    ConfigResponse, CustomExecuteMsg, CustomQueryMsg, ListTasksResponse, QueryMsg, Requestor,
+    ScheduleEnd, Status, TaskResponse,
 };
-use lavs_task_queue::msg::{ConfigResponse, CustomExecuteMsg, CustomQueryMsg, QueryMsg, Requestor};
 use layer_climb::{prelude::*, proto::abci::TxResponse};
 
 use super::operator::Operator;
@@ -61,17 +61,12 @@ impl TaskQueue {
     ) -> Result<(TaskId, TxResponse)> {
         let payload = serde_json::from_str(&body).context("Failed to parse body into JSON")?;
 
-        let contract_config = self.querier.config().await?;
-
-        let payment = match contract_config.requestor {
-            Requestor::OpenPayment(coin) => vec![new_coin(coin.amount, coin.denom)],
-            Requestor::Fixed(addr) => {
-                if addr != self.admin.addr.to_string() {
-                    bail!("Only the requestor can pay for the task")
-                }
-                Vec::new()
-            }
-        };
+        let payment = self
+            .task_price()
+            .await?
+            .into_iter()
+            .map(|coin| new_coin(coin.amount, coin.denom))
+            .collect();
 
         let tx_resp = self
             .admin
@@ -81,6 +76,9 @@ impl TaskQueue {
                     description,
                     timeout,
                     payload,
+                    depends_on: vec![],
+                    callback: None,
+                    verifier: None,
                 },
                 payment,
                 None,
@@ -97,6 +95,93 @@ impl TaskQueue {
 
         Ok((task_id, tx_resp))
     }
+
+    /// Registers a schedule creating `count` tasks, one every `interval` seconds
+    pub async fn add_recurring_task(
+        &self,
+        body: String,
+        description: String,
+        timeout: Option<u64>,
+        interval: u64,
+        count: u32,
+    ) -> Result<(u64, TxResponse)> {
+        let payload = serde_json::from_str(&body).context("Failed to parse body into JSON")?;
+
+        // all the runs are paid upfront
+        let payment = self
+            .task_price()
+            .await?
+            .into_iter()
+            .map(|coin| new_coin(coin.amount * Uint128::from(count), coin.denom))
+            .collect();
+
+        let tx_resp = self
+            .admin
+            .contract_execute(
+                &self.contract_addr,
+                &CustomExecuteMsg::CreateRecurring {
+                    description,
+                    timeout,
+                    payload,
+                    interval_secs: interval,
+                    end: ScheduleEnd::Count(count),
+                },
+                payment,
+                None,
+            )
+            .await?;
+
+        let schedule_id: u64 = CosmosTxEvents::from(&tx_resp)
+            .attr_first("wasm", "schedule_id")?
+            .value()
+            .parse()?;
+
+        tracing::info!("Schedule added with id: {schedule_id}");
+        tracing::debug!("Tx hash: {}", tx_resp.txhash);
+
+        Ok((schedule_id, tx_resp))
+    }
+
+    /// Creates the tasks of the schedules that are due
+    pub async fn tick(&self) -> Result<TxResponse> {
+        let tx_resp = self
+            .admin
+            .contract_execute(
+                &self.contract_addr,
+                &CustomExecuteMsg::Tick {},
+                Vec::new(),
+                None,
+            )
+            .await?;
+
+        tracing::info!("Tick done");
+        tracing::debug!("Tx hash: {}", tx_resp.txhash);
+
+        Ok(tx_resp)
+    }
+
+    /// Funds needed to create a single task
+    async fn task_price(&self) -> Result<Vec<cosmwasm_std::Coin>> {
+        let contract_config = self.querier.config().await?;
+
+        let payment = match contract_config.requestor {
+            Requestor::OpenPayment(coin) => vec![coin],
+            // pays in the first accepted denom
+            Requestor::MultiDenomPayment(prices) | Requestor::AllowList { payment: prices } => {
+                prices.into_iter().take(1).collect()
+            }
+            Requestor::Cw20Payment { token, .. } => {
+                bail!("Tasks must be paid by sending the CW20 token {token} to the queue")
+            }
+            Requestor::Fixed(addr) => {
+                if addr != self.admin.addr.to_string() {
+                    bail!("Only the requestor can pay for the task")
+                }
+                Vec::new()
+            }
+        };
+        Ok(payment)
+    }
 }
 
 pub struct TaskQueueQuerier {
@@ -137,7 +222,7 @@ impl TaskQueueQuerier {
 
         let operators = operator_querier.all_operators().await?;
 
-        let tasks = self
+        let ListTasksResponse { tasks, next_cursor } = self
             .tasks_view(start_after, limit, Order::Descending)
             .await?;
 
@@ -146,6 +231,7 @@ impl TaskQueueQuerier {
             operator_addr,
             operators,
             tasks,
+            next_cursor,
         })
     }
 
@@ -154,40 +240,18 @@ impl TaskQueueQuerier {
         start_after: Option<TaskId>,
         limit: Option<u32>,
         order: Order,
-    ) -> Result<Vec<TaskView>> {
-        let tasks_open: ListOpenResponse = self
-            .querier
-            .contract_smart(
-                &self.contract_addr,
-                &QueryMsg::Custom(CustomQueryMsg::ListOpen { start_after, limit }),
-            )
-            .await?;
-
-        let tasks_completed: ListCompletedResponse = self
-            .querier
+    ) -> Result<ListTasksResponse> {
+        self.querier
             .contract_smart(
                 &self.contract_addr,
-                &QueryMsg::Custom(CustomQueryMsg::ListCompleted { start_after, limit }),
+                &QueryMsg::Custom(CustomQueryMsg::ListTasks {
+                    status: None,
+                    start_after,
+                    limit,
+                    order: Some(order),
+                }),
             )
-            .await?;
-
-        let mut all_tasks =
-            Vec::with_capacity(tasks_open.tasks.len() + tasks_completed.tasks.len());
-
-        for task in tasks_open.tasks {
-            all_tasks.push(TaskView::Open(task));
-        }
-
-        for task in tasks_completed.tasks {
-            all_tasks.push(TaskView::Completed(task));
-        }
-
-        all_tasks.sort_by(|a, b| match order {
-            Order::Ascending => a.id().cmp(&b.id()),
-            Order::Descending => b.id().cmp(&a.id()),
-        });
-
-        Ok(all_tasks)
+            .await
     }
 }
 
@@ -195,7 +259,8 @@ pub struct TaskQueueView {
     pub verifier_addr: Address,
     pub operator_addr: Address,
     pub operators: Vec<Operator>,
-    pub tasks: Vec<TaskView>,
+    pub tasks: Vec<TaskResponse>,
+    pub next_cursor: Option<TaskId>,
 }
 
 impl TaskQueueView {
@@ -211,44 +276,33 @@ impl TaskQueueView {
         log("\nTasks:");
 
         for task in &self.tasks {
-            let data_json_string = task.data_json_string()?;
-
-            match task {
-                TaskView::Open(task) => {
-                    log(&format!("  - Open Task: {}", task.id));
-                    log(&format!("    Expires: {}", task.expires));
-                    log(&format!("    Payload: {}", data_json_string));
+            log(&format!("  - Task {}: {}", task.id, task.status.as_str()));
+            match &task.status {
+                Status::Open {} => {
+                    log(&format!("    Priority: {}", task.priority));
+                    log(&format!("    Payload: {}", json_string(&task.payload)?));
+                }
+                Status::Completed { completed } => {
+                    log(&format!("    Completed: {}", completed));
+                    if let Some(result) = &task.result {
+                        log(&format!("    Result: {}", json_string(result)?));
+                    }
                 }
-                TaskView::Completed(task) => {
-                    log(&format!("  - Completed Task: {}", task.id));
-                    log(&format!("    Completed: {}", task.completed));
-                    log(&format!("    Result: {}", data_json_string));
+                _ => {
+                    log(&format!("    Payload: {}", json_string(&task.payload)?));
                 }
             }
         }
 
+        if let Some(cursor) = self.next_cursor {
+            log(&format!("\nMore tasks with --start-after {}", cursor));
+        }
+
         Ok(())
     }
 }
 
-pub enum TaskView {
-    Open(OpenTaskOverview),
-    Completed(CompletedTaskOverview),
-}
-
-impl TaskView {
-    pub fn id(&self) -> TaskId {
-        match self {
-            TaskView::Open(task) => task.id,
-            TaskView::Completed(task) => task.id,
-        }
-    }
-
-    pub fn data_json_string(&self) -> Result<String> {
-        serde_json::to_string_pretty(match self {
-            TaskView::Open(task) => &task.payload,
-            TaskView::Completed(task) => &task.result,
-        })
+fn json_string(value: &serde_json::Value) -> Result<String> {
+    serde_json::to_string_pretty(value)
         .map_err(|e| anyhow::anyhow!("Failed to serialize payload: {}", e))
-    }
 }
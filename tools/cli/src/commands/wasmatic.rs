@@ -5,6 +5,7 @@ use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 use tokio::fs;
 
+use crate::commands::operator::OperatorQuerier;
 use crate::context::AppContext;
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -20,9 +21,27 @@ pub enum Trigger {
     },
 }
 
+/// Loads the endpoints from the operator contract if one is given, otherwise from config.json
+pub async fn endpoints(ctx: &AppContext, operator_contract: Option<String>) -> Result<Vec<String>> {
+    match operator_contract {
+        Some(addr) => {
+            let addr = ctx.chain_config()?.parse_address(&addr)?;
+            let endpoints = OperatorQuerier::new(ctx.clone(), addr)
+                .await?
+                .wasmatic_endpoints()
+                .await?;
+            if endpoints.is_empty() {
+                bail!("Error: No operator has published a wasmatic endpoint");
+            }
+            Ok(endpoints)
+        }
+        None => Ok(ctx.chain_info()?.wasmatic.endpoints.clone()),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn deploy(
-    ctx: &AppContext,
+    endpoints: &[String],
     name: String,
     digest: Option<String>,
     wasm_source: String,
@@ -31,7 +50,6 @@ pub async fn deploy(
     env_pairs: Vec<String>,
     testable: bool,
 ) -> Result<()> {
-    let endpoints = &ctx.chain_info()?.wasmatic.endpoints;
     let client = Client::new();
 
     let envs = env_pairs
@@ -124,8 +142,7 @@ pub async fn deploy(
     Ok(())
 }
 
-pub async fn remove(ctx: &AppContext, app_name: String) -> Result<()> {
-    let endpoints = &ctx.chain_info()?.wasmatic.endpoints;
+pub async fn remove(endpoints: &[String], app_name: String) -> Result<()> {
     let client = Client::new();
 
     // Prepare the JSON body
@@ -165,8 +182,7 @@ pub struct TestOutput {
     pub output: Option<Value>,
 }
 
-pub async fn test(ctx: &AppContext, app_name: String, input: String) -> Result<()> {
-    let endpoints = &ctx.chain_info()?.wasmatic.endpoints;
+pub async fn test(endpoints: &[String], app_name: String, input: String) -> Result<()> {
     let client = Client::new();
 
     // Parse input into json
@@ -10,9 +10,10 @@ use commands::{
     deploy::{deploy_contracts, DeployContractArgs},
     faucet::tap_faucet,
     task_queue::TaskQueue,
-    wasmatic::{deploy, remove, test, Trigger},
+    wasmatic::{deploy, endpoints, remove, test, Trigger},
 };
 use context::AppContext;
+use lavs_mock_operators::state::Constraints;
 use layer_climb::prelude::*;
 use layer_climb_cli::command::{ContractLog, WalletLog};
 
@@ -46,7 +47,15 @@ async fn main() -> Result<()> {
                 slashable_spread,
                 operators,
                 requestor,
+                max_power_share,
+                min_operators,
+                min_power,
             } => {
+                let constraints = Constraints {
+                    max_power_share,
+                    min_operators,
+                    min_power: min_power.map(Into::into),
+                };
                 let args = DeployContractArgs::parse(
                     &ctx,
                     artifacts_path,
@@ -57,6 +66,7 @@ async fn main() -> Result<()> {
                     slashable_spread,
                     operators,
                     requestor,
+                    constraints,
                 )
                 .await?;
 
@@ -81,6 +91,20 @@ async fn main() -> Result<()> {
                 } => {
                     let _ = task_queue.add_task(body, description, timeout).await?;
                 }
+                TaskQueueCommand::AddRecurringTask {
+                    body,
+                    description,
+                    timeout,
+                    interval,
+                    count,
+                } => {
+                    let _ = task_queue
+                        .add_recurring_task(body, description, timeout, interval, count)
+                        .await?;
+                }
+                TaskQueueCommand::Tick => {
+                    let _ = task_queue.tick().await?;
+                }
                 TaskQueueCommand::ViewQueue { start_after, limit } => {
                     let res = task_queue
                         .querier
@@ -166,49 +190,52 @@ async fn main() -> Result<()> {
                 })
                 .await?;
         }
-        Command::Wasmatic(wasmatic_args) => match wasmatic_args.command {
-            WasmaticCommand::Deploy {
-                name,
-                digest,
-                wasm_source,
-                cron_trigger,
-                task_trigger,
-                hd_index,
-                poll_interval,
-                permissions,
-                envs,
-                testable,
-            } => {
-                let trigger = match (cron_trigger, task_trigger) {
-                    (Some(cron), None) => Trigger::Cron { schedule: cron },
-                    (None, Some(task)) => Trigger::Queue {
-                        task_queue_addr: task,
-                        hd_index,
-                        poll_interval,
-                    },
-                    _ => {
-                        panic!("Error: You need to provide either cron_trigger or task_trigger")
-                    }
-                };
-                deploy(
-                    &ctx,
+        Command::Wasmatic(wasmatic_args) => {
+            let endpoints = endpoints(&ctx, wasmatic_args.operators).await?;
+            match wasmatic_args.command {
+                WasmaticCommand::Deploy {
                     name,
                     digest,
                     wasm_source,
-                    trigger,
+                    cron_trigger,
+                    task_trigger,
+                    hd_index,
+                    poll_interval,
                     permissions,
                     envs,
                     testable,
-                )
-                .await?;
-            }
-            WasmaticCommand::Remove { name } => {
-                remove(&ctx, name).await?;
-            }
-            WasmaticCommand::Test { name, input } => {
-                test(&ctx, name, input).await?;
+                } => {
+                    let trigger = match (cron_trigger, task_trigger) {
+                        (Some(cron), None) => Trigger::Cron { schedule: cron },
+                        (None, Some(task)) => Trigger::Queue {
+                            task_queue_addr: task,
+                            hd_index,
+                            poll_interval,
+                        },
+                        _ => {
+                            panic!("Error: You need to provide either cron_trigger or task_trigger")
+                        }
+                    };
+                    deploy(
+                        &endpoints,
+                        name,
+                        digest,
+                        wasm_source,
+                        trigger,
+                        permissions,
+                        envs,
+                        testable,
+                    )
+                    .await?;
+                }
+                WasmaticCommand::Remove { name } => {
+                    remove(&endpoints, name).await?;
+                }
+                WasmaticCommand::Test { name, input } => {
+                    test(&endpoints, name, input).await?;
+                }
             }
-        },
+        }
     }
 
     Ok(())